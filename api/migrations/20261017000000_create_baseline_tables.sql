@@ -0,0 +1,84 @@
+-- The schema of the former `db/table.sql`, from which the existing databases were created.
+--
+-- Every statement is a no-op for those databases, and the later migrations upgrade both alike.
+-- Do not change this file; add a new migration instead.
+
+CREATE TABLE IF NOT EXISTS
+    problems (
+        id VARCHAR(255) PRIMARY KEY,
+        contest_id VARCHAR(255) NOT NULL,
+        contest_name VARCHAR(255) NOT NULL,
+        problem_index VARCHAR(255) NOT NULL,
+        name VARCHAR(255) NOT NULL,
+        title VARCHAR(255) NOT NULL,
+        platform VARCHAR(255) NOT NULL,
+        raw_point DOUBLE PRECISION,
+        difficulty DOUBLE PRECISION,
+        category VARCHAR(255) NOT NULL,
+        is_experimental BOOLEAN,
+        url VARCHAR(255) NOT NULL,
+        solver_count INT,
+        submissions INT,
+        success_rate DOUBLE PRECISION
+    );
+
+CREATE TABLE IF NOT EXISTS
+    algorithms (
+        id VARCHAR(255) PRIMARY KEY,
+        name VARCHAR(255) NOT NULL
+    );
+
+CREATE TABLE IF NOT EXISTS
+    technical_tags (
+        id VARCHAR(255) PRIMARY KEY,
+        en_name VARCHAR(255) NOT NULL,
+        ja_name VARCHAR(255) NOT NULL,
+        algorithm_id VARCHAR(255) NOT NULL,
+        FOREIGN KEY (algorithm_id) REFERENCES algorithms (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    problem_tags (
+        problem_id VARCHAR(255) NOT NULL,
+        technical_tag_id VARCHAR(255) NOT NULL,
+        PRIMARY KEY (problem_id, technical_tag_id),
+        FOREIGN KEY (problem_id) REFERENCES problems (id),
+        FOREIGN KEY (technical_tag_id) REFERENCES technical_tags (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    contests (
+        id VARCHAR(255) PRIMARY KEY,
+        raw_id VARCHAR(255) NOT NULL,
+        name VARCHAR(255) NOT NULL,
+        category VARCHAR(255) NOT NULL,
+        platform VARCHAR(255) NOT NULL,
+        phase VARCHAR(255) NOT NULL,
+        start_time_seconds BIGINT,
+        duration_seconds BIGINT,
+        url VARCHAR(255) NOT NULL
+    );
+
+CREATE TABLE IF NOT EXISTS
+    contest_problems (
+        contest_id VARCHAR(255) NOT NULL,
+        problem_id VARCHAR(255) NOT NULL,
+        PRIMARY KEY (contest_id, problem_id),
+        FOREIGN KEY (contest_id) REFERENCES contests (id),
+        FOREIGN KEY (problem_id) REFERENCES problems (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    internal_users (
+        id VARCHAR(255) PRIMARY KEY,
+        username VARCHAR(255),
+        github_id VARCHAR(255),
+        github_username VARCHAR(255),
+        google_id VARCHAR(255),
+        google_username VARCHAR(255),
+        atcoder_username VARCHAR(255),
+        codeforces_username VARCHAR(255),
+        yukicoder_username VARCHAR(255),
+        aoj_username VARCHAR(255),
+        yoj_username VARCHAR(255)
+    );
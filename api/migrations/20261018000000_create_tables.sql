@@ -0,0 +1,235 @@
+-- Upgrade the baseline schema, and create the tables added since.
+--
+-- The databases created from the former `db/table.sql` already have the baseline tables,
+-- so the new columns and constraints are added to them rather than to the CREATE statements.
+
+ALTER TABLE problems ADD COLUMN IF NOT EXISTS difficulty_confidence VARCHAR(255);
+
+-- The existing rows are filled in by the seed of the algorithms.
+ALTER TABLE algorithms ADD COLUMN IF NOT EXISTS ja_name VARCHAR(255) NOT NULL DEFAULT '';
+
+ALTER TABLE algorithms ALTER COLUMN ja_name DROP DEFAULT;
+
+-- Google accounts are identified by their email addresses, which were stored as `google_username`.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'internal_users' AND column_name = 'google_username'
+    ) AND NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'internal_users' AND column_name = 'google_email'
+    ) THEN
+        ALTER TABLE internal_users RENAME COLUMN google_username TO google_email;
+    END IF;
+END $$;
+
+ALTER TABLE internal_users ADD COLUMN IF NOT EXISTS google_email VARCHAR(255);
+
+ALTER TABLE internal_users ADD COLUMN IF NOT EXISTS role VARCHAR(255) NOT NULL DEFAULT 'member';
+
+-- The users are upserted by the IDs of their accounts
+CREATE UNIQUE INDEX IF NOT EXISTS internal_users_github_id_key ON internal_users (github_id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS internal_users_google_id_key ON internal_users (google_id);
+
+-- プラットフォーム固有のタグ (小文字) と technical_tags の対応
+CREATE TABLE IF NOT EXISTS
+    tag_aliases (
+        platform VARCHAR(255) NOT NULL,
+        alias VARCHAR(255) NOT NULL,
+        technical_tag_id VARCHAR(255) NOT NULL,
+        PRIMARY KEY (platform, alias),
+        FOREIGN KEY (technical_tag_id) REFERENCES technical_tags (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    crawl_watermarks (
+        platform VARCHAR(255) PRIMARY KEY,
+        last_seconds BIGINT NOT NULL,
+        last_id BIGINT NOT NULL,
+        updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+
+CREATE TABLE IF NOT EXISTS
+    submissions (
+        id VARCHAR(255) PRIMARY KEY,
+        raw_id VARCHAR(255) NOT NULL,
+        user_id VARCHAR(255) NOT NULL,
+        language VARCHAR(255) NOT NULL,
+        raw_language VARCHAR(255) NOT NULL,
+        platform VARCHAR(255) NOT NULL,
+        verdict VARCHAR(255) NOT NULL,
+        execution_time BIGINT,
+        memory BIGINT,
+        code_size BIGINT,
+        submission_date BIGINT NOT NULL,
+        contest_id VARCHAR(255),
+        problem_index VARCHAR(255),
+        problem_name VARCHAR(255),
+        raw_point DOUBLE PRECISION,
+        difficulty DOUBLE PRECISION
+    );
+
+CREATE TABLE IF NOT EXISTS
+    auth_sessions (
+        csrf_token VARCHAR(255) PRIMARY KEY,
+        provider VARCHAR(255) NOT NULL,
+        nonce VARCHAR(255) NOT NULL,
+        pkce_verifier VARCHAR(255) NOT NULL,
+        created_at BIGINT NOT NULL
+    );
+
+CREATE TABLE IF NOT EXISTS
+    contest_results (
+        contest_id VARCHAR(255) NOT NULL,
+        platform VARCHAR(255) NOT NULL,
+        user_id VARCHAR(255) NOT NULL,
+        rating INT NOT NULL,
+        PRIMARY KEY (contest_id, user_id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    contest_result_crawls (
+        contest_id VARCHAR(255) PRIMARY KEY,
+        fetched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+
+CREATE TABLE IF NOT EXISTS
+    tag_proposals (
+        id VARCHAR(255) PRIMARY KEY,
+        problem_id VARCHAR(255) NOT NULL,
+        technical_tag_id VARCHAR(255) NOT NULL,
+        proposer_id VARCHAR(255) NOT NULL,
+        status VARCHAR(255) NOT NULL DEFAULT 'pending',
+        created_at BIGINT NOT NULL,
+        reviewer_id VARCHAR(255),
+        reviewed_at BIGINT,
+        FOREIGN KEY (problem_id) REFERENCES problems (id),
+        FOREIGN KEY (technical_tag_id) REFERENCES technical_tags (id),
+        FOREIGN KEY (proposer_id) REFERENCES internal_users (id),
+        FOREIGN KEY (reviewer_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    tag_proposal_votes (
+        proposal_id VARCHAR(255) NOT NULL,
+        user_id VARCHAR(255) NOT NULL,
+        -- 1 (up) or -1 (down)
+        vote SMALLINT NOT NULL,
+        PRIMARY KEY (proposal_id, user_id),
+        FOREIGN KEY (proposal_id) REFERENCES tag_proposals (id),
+        FOREIGN KEY (user_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    tag_proposal_events (
+        id BIGSERIAL PRIMARY KEY,
+        proposal_id VARCHAR(255) NOT NULL,
+        actor_id VARCHAR(255) NOT NULL,
+        action VARCHAR(255) NOT NULL,
+        comment TEXT,
+        created_at BIGINT NOT NULL,
+        FOREIGN KEY (proposal_id) REFERENCES tag_proposals (id),
+        FOREIGN KEY (actor_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    virtual_contests (
+        id VARCHAR(255) PRIMARY KEY,
+        title VARCHAR(255) NOT NULL,
+        owner_id VARCHAR(255) NOT NULL,
+        start_time BIGINT NOT NULL,
+        duration BIGINT NOT NULL,
+        scoring VARCHAR(255) NOT NULL,
+        created_at BIGINT NOT NULL,
+        FOREIGN KEY (owner_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    virtual_contest_problems (
+        contest_id VARCHAR(255) NOT NULL,
+        problem_id VARCHAR(255) NOT NULL,
+        position INT NOT NULL,
+        PRIMARY KEY (contest_id, problem_id),
+        FOREIGN KEY (contest_id) REFERENCES virtual_contests (id),
+        FOREIGN KEY (problem_id) REFERENCES problems (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    virtual_contest_participants (
+        contest_id VARCHAR(255) NOT NULL,
+        user_id VARCHAR(255) NOT NULL,
+        PRIMARY KEY (contest_id, user_id),
+        FOREIGN KEY (contest_id) REFERENCES virtual_contests (id),
+        FOREIGN KEY (user_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    problem_lists (
+        id VARCHAR(255) PRIMARY KEY,
+        owner_id VARCHAR(255) NOT NULL,
+        name VARCHAR(255) NOT NULL,
+        description TEXT,
+        is_public BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL,
+        FOREIGN KEY (owner_id) REFERENCES internal_users (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    problem_list_items (
+        list_id VARCHAR(255) NOT NULL,
+        problem_id VARCHAR(255) NOT NULL,
+        position INT NOT NULL,
+        note TEXT,
+        PRIMARY KEY (list_id, problem_id),
+        FOREIGN KEY (list_id) REFERENCES problem_lists (id) ON DELETE CASCADE,
+        FOREIGN KEY (problem_id) REFERENCES problems (id)
+    );
+
+CREATE TABLE IF NOT EXISTS
+    rating_history (
+        contest_id VARCHAR(255) NOT NULL,
+        platform VARCHAR(255) NOT NULL,
+        user_id VARCHAR(255) NOT NULL,
+        contest_name VARCHAR(255) NOT NULL,
+        rank INT NOT NULL,
+        old_rating INT NOT NULL,
+        new_rating INT NOT NULL,
+        performance INT,
+        rated_at BIGINT NOT NULL,
+        PRIMARY KEY (contest_id, user_id)
+    );
+
+-- The statistics are recomputed when the fingerprint of the stored submissions changes
+CREATE TABLE IF NOT EXISTS
+    user_stats (
+        user_id VARCHAR(255) PRIMARY KEY,
+        fingerprint TEXT NOT NULL,
+        stats TEXT NOT NULL,
+        computed_at BIGINT NOT NULL,
+        FOREIGN KEY (user_id) REFERENCES internal_users (id) ON DELETE CASCADE
+    );
+
+-- Index
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS problems_name_trgm_idx ON problems USING GIN (name gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS problems_title_trgm_idx ON problems USING GIN (title gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS problems_contest_name_trgm_idx ON problems USING GIN (contest_name gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS submissions_platform_user_id_submission_date_idx ON submissions (platform, user_id, submission_date);
+
+-- A tag can be proposed for a problem only once at a time
+CREATE UNIQUE INDEX IF NOT EXISTS tag_proposals_pending_idx ON tag_proposals (problem_id, technical_tag_id)
+WHERE
+    status = 'pending';
+
+CREATE INDEX IF NOT EXISTS virtual_contest_participants_user_id_idx ON virtual_contest_participants (user_id);
+
+CREATE INDEX IF NOT EXISTS problem_lists_owner_id_idx ON problem_lists (owner_id);
+
+CREATE INDEX IF NOT EXISTS rating_history_platform_user_id_rated_at_idx ON rating_history (platform, user_id, rated_at);
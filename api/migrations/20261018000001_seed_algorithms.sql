@@ -0,0 +1,45 @@
+-- algorithms のデータを挿入
+-- The algorithms seeded before `ja_name` was added get their Japanese names.
+INSERT INTO algorithms (id, name, ja_name) VALUES
+    ('1', 'Implementation', '実装'),
+    ('2', 'Math', '数学'),
+    ('3', 'Greedy', '貪欲法'),
+    ('4', 'DP', '動的計画法'),
+    ('5', 'Data Structures', 'データ構造'),
+    ('6', 'Brute Force', '全探索'),
+    ('7', 'Constructive Algorithms', '構築'),
+    ('8', 'Graphs', 'グラフ'),
+    ('9', 'Sortings', 'ソート'),
+    ('10', 'Binary Search', '二分探索'),
+    ('11', 'DFS and Similar', 'DFS'),
+    ('12', 'Trees', '木'),
+    ('13', 'Strings', '文字列'),
+    ('14', 'Number Theory', '数論'),
+    ('15', 'Combinatorics', '組合せ'),
+    ('16', 'Geometry', '幾何'),
+    ('17', 'Bitmasks', 'ビット演算'),
+    ('18', 'Two Pointers', '尺取り法'),
+    ('19', 'DSU', 'Union Find'),
+    ('20', 'Shortest Paths', '最短経路'),
+    ('21', 'Probabilities', '確率'),
+    ('22', 'Divide and Conquer', '分割統治'),
+    ('23', 'Hashing', 'ハッシュ'),
+    ('24', 'Games', 'ゲーム'),
+    ('25', 'Flows', 'フロー'),
+    ('26', 'Interactive', 'インタラクティブ'),
+    ('27', 'Matrices', '行列'),
+    ('28', 'FFT', '高速フーリエ変換'),
+    ('29', 'Ternary Search', '三分探索'),
+    ('30', 'Expression Parsing', '構文解析'),
+    ('31', 'Meet-in-the-Middle', 'Meet-in-the-Middle'),
+    ('32', '2-SAT', '2-SAT'),
+    ('33', 'Chinese Remainder Theorem', '中国剰余定理'),
+    ('34', 'Schedules', 'スケジューリング'),
+    ('35', 'Special', '特殊'),
+    ('36', 'Other', 'その他'),
+    ('37', 'Technique', 'テクニック'),
+    ('38', 'Query', 'クエリ')
+ON CONFLICT (id) DO UPDATE SET
+    ja_name = EXCLUDED.ja_name
+WHERE
+    algorithms.ja_name = '';
@@ -0,0 +1,97 @@
+-- tag_aliases のデータを挿入 (別名は小文字で登録する)
+INSERT INTO tag_aliases (platform, alias, technical_tag_id) VALUES
+    -- Codeforces
+    ('codeforces', 'implementation', '1'),
+    ('codeforces', 'math', '2'),
+    ('codeforces', 'greedy', '3'),
+    ('codeforces', 'dp', '4'),
+    ('codeforces', 'data structures', '5'),
+    ('codeforces', 'brute force', '6'),
+    ('codeforces', 'constructive algorithms', '7'),
+    ('codeforces', 'graphs', '8'),
+    ('codeforces', 'graph matchings', '8'),
+    ('codeforces', 'sortings', '9'),
+    ('codeforces', 'binary search', '10'),
+    ('codeforces', 'dfs and similar', '11'),
+    ('codeforces', 'trees', '12'),
+    ('codeforces', 'strings', '13'),
+    ('codeforces', 'string suffix structures', '13'),
+    ('codeforces', 'number theory', '14'),
+    ('codeforces', 'combinatorics', '15'),
+    ('codeforces', 'geometry', '16'),
+    ('codeforces', 'bitmasks', '17'),
+    ('codeforces', 'two pointers', '18'),
+    ('codeforces', 'dsu', '19'),
+    ('codeforces', 'shortest paths', '20'),
+    ('codeforces', 'probabilities', '21'),
+    ('codeforces', 'divide and conquer', '22'),
+    ('codeforces', 'hashing', '23'),
+    ('codeforces', 'games', '24'),
+    ('codeforces', 'flows', '25'),
+    ('codeforces', 'interactive', '26'),
+    ('codeforces', 'matrices', '27'),
+    ('codeforces', 'fft', '28'),
+    ('codeforces', 'ternary search', '29'),
+    ('codeforces', 'expression parsing', '30'),
+    ('codeforces', 'meet-in-the-middle', '31'),
+    ('codeforces', '2-sat', '32'),
+    ('codeforces', 'chinese remainder theorem', '33'),
+    ('codeforces', 'schedules', '34'),
+    ('codeforces', '*special', '35'),
+
+    -- yukicoder
+    ('yukicoder', '実装', '1'),
+    ('yukicoder', '数学', '2'),
+    ('yukicoder', '貪欲法', '3'),
+    ('yukicoder', 'dp', '4'),
+    ('yukicoder', '動的計画法', '4'),
+    ('yukicoder', 'データ構造', '5'),
+    ('yukicoder', '全探索', '6'),
+    ('yukicoder', '構築', '7'),
+    ('yukicoder', 'グラフ', '8'),
+    ('yukicoder', 'グラフ理論', '8'),
+    ('yukicoder', 'ソート', '9'),
+    ('yukicoder', '二分探索', '10'),
+    ('yukicoder', 'dfs', '11'),
+    ('yukicoder', '深さ優先探索', '11'),
+    ('yukicoder', '木', '12'),
+    ('yukicoder', '木構造', '12'),
+    ('yukicoder', '文字列', '13'),
+    ('yukicoder', '数論', '14'),
+    ('yukicoder', '整数論', '14'),
+    ('yukicoder', '組合せ', '15'),
+    ('yukicoder', '組み合わせ', '15'),
+    ('yukicoder', '幾何', '16'),
+    ('yukicoder', 'ビット演算', '17'),
+    ('yukicoder', 'bit演算', '17'),
+    ('yukicoder', '尺取り法', '18'),
+    ('yukicoder', 'unionfind', '19'),
+    ('yukicoder', 'union find', '19'),
+    ('yukicoder', '最短経路', '20'),
+    ('yukicoder', '確率', '21'),
+    ('yukicoder', '期待値', '21'),
+    ('yukicoder', '分割統治', '22'),
+    ('yukicoder', 'ハッシュ', '23'),
+    ('yukicoder', 'ゲーム', '24'),
+    ('yukicoder', 'フロー', '25'),
+    ('yukicoder', '最大流', '25'),
+    ('yukicoder', 'インタラクティブ', '26'),
+    ('yukicoder', '行列', '27'),
+    ('yukicoder', 'fft', '28'),
+    ('yukicoder', '畳み込み', '28'),
+    ('yukicoder', '三分探索', '29'),
+    ('yukicoder', '構文解析', '30'),
+    ('yukicoder', '半分全列挙', '31'),
+    ('yukicoder', '2-sat', '32'),
+    ('yukicoder', '中国剰余定理', '33'),
+    ('yukicoder', 'クエリ', '42'),
+    ('yukicoder', 'パリティ', '43'),
+    ('yukicoder', 'グリッド', '44'),
+    ('yukicoder', '累積和', '47'),
+    ('yukicoder', '二項係数', '49'),
+    ('yukicoder', '順列', '52'),
+    ('yukicoder', '浮動小数点', '54'),
+    ('yukicoder', 'セグメント木', '57'),
+    ('yukicoder', 'セグ木', '57'),
+    ('yukicoder', '数列', '58')
+ON CONFLICT (platform, alias) DO NOTHING;
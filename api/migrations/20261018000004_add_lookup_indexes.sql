@@ -0,0 +1,7 @@
+-- Problems are listed by platform and filtered or sorted by difficulty
+CREATE INDEX IF NOT EXISTS problems_platform_idx ON problems (platform);
+
+CREATE INDEX IF NOT EXISTS problems_difficulty_idx ON problems (difficulty);
+
+-- The primary key starts with contest_id, so it does not help to find the contest of a problem
+CREATE INDEX IF NOT EXISTS contest_problems_problem_id_idx ON contest_problems (problem_id);
@@ -0,0 +1,14 @@
+-- The state of the scheduled updates of each platform.
+-- `running_since` is set while a run holds the lock of the platform.
+CREATE TABLE IF NOT EXISTS
+    scheduler_jobs (
+        platform VARCHAR(255) PRIMARY KEY,
+        running_since BIGINT,
+        last_started_at BIGINT,
+        last_finished_at BIGINT,
+        last_succeeded BOOLEAN,
+        last_error TEXT,
+        problems INT NOT NULL DEFAULT 0,
+        contests INT NOT NULL DEFAULT 0,
+        skipped INT NOT NULL DEFAULT 0
+    );
@@ -0,0 +1,23 @@
+-- The history of the updates of the platforms, including the running ones.
+-- `triggered_by` is NULL for the runs started by the scheduler or the batch process.
+CREATE TABLE IF NOT EXISTS
+    ingestion_runs (
+        id BIGSERIAL PRIMARY KEY,
+        platform VARCHAR(255) NOT NULL,
+        mode VARCHAR(255) NOT NULL,
+        contest_id VARCHAR(255),
+        triggered_by VARCHAR(255),
+        started_at BIGINT NOT NULL,
+        finished_at BIGINT,
+        succeeded BOOLEAN,
+        error TEXT,
+        problems_inserted INT NOT NULL DEFAULT 0,
+        problems_updated INT NOT NULL DEFAULT 0,
+        contests_inserted INT NOT NULL DEFAULT 0,
+        contests_updated INT NOT NULL DEFAULT 0,
+        skipped INT NOT NULL DEFAULT 0,
+        FOREIGN KEY (triggered_by) REFERENCES internal_users (id)
+    );
+
+-- Runs are listed per platform in the order they started
+CREATE INDEX IF NOT EXISTS ingestion_runs_platform_started_at_idx ON ingestion_runs (platform, started_at);
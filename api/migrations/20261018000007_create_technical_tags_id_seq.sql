@@ -0,0 +1,15 @@
+-- The IDs of the tags created after the seed are taken from this sequence,
+-- so that concurrent creations never assign the same ID.
+CREATE SEQUENCE IF NOT EXISTS technical_tags_id_seq AS BIGINT;
+
+-- Continue from the largest numeric ID, including those assigned before the sequence existed.
+SELECT
+    setval(
+        'technical_tags_id_seq',
+        GREATEST(COALESCE(MAX(id::BIGINT), 0), 1),
+        COUNT(*) > 0
+    )
+FROM
+    technical_tags
+WHERE
+    id ~ '^[0-9]+$';
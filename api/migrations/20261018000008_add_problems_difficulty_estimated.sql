@@ -0,0 +1,3 @@
+-- Whether the difficulty of the problem was estimated in-house, rather than given by the platform
+-- or AtCoder Problems. Only such difficulties are replaced by the later estimates.
+ALTER TABLE problems ADD COLUMN IF NOT EXISTS difficulty_estimated BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- The submissions of a user are looked up by the handle regardless of its case,
+-- since the platforms accept the handles in any case but return them in their own.
+CREATE INDEX IF NOT EXISTS submissions_platform_lower_user_id_submission_date_idx ON submissions (platform, LOWER(user_id), submission_date);
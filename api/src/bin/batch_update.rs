@@ -1,16 +1,21 @@
 //! Batch Process to Retrieve and Update Database
 //! with All Problems/Contest Information from Various Programming Contest Sites
+//!
+//! By default, every platform is updated once and the process exits.
+//! With `--schedule`, the process keeps running and updates each platform at the cadence given by `SCHEDULES`.
 
 use anyhow::Result;
 use dotenv::dotenv;
 use env_logger;
 use std::env;
 use std::sync::Arc;
+use tokio::task::LocalSet;
 
-use api::domain::vo::platform;
+use api::config::HttpConfigs;
+use api::domain::scheduler::SCHEDULES;
 use api::infra::api::api_client;
-use api::infra::repository::initialize_pool::initialize_pool;
-use api::service::update;
+use api::infra::repository::initialize_pool::{initialize_pool, run_migrations};
+use api::service::scheduler::SchedulerUsecase;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -25,24 +30,46 @@ async fn main() -> Result<()> {
             .await
             .expect("Failed to initialize the connection pool"),
     );
-    let api_client = Arc::new(api_client::ApiClient::new());
+    run_migrations(&pool)
+        .await
+        .expect("Failed to apply the migrations");
+    let api_client = Arc::new(api_client::ApiClient::new(&HttpConfigs::from_env()));
+    let scheduler = Arc::new(SchedulerUsecase::new(api_client, pool));
+    scheduler.fail_stale_runs().await?;
 
-    let platforms = vec![
-        platform::Platform::Atcoder,
-        platform::Platform::Codeforces,
-        platform::Platform::Aoj,
-        platform::Platform::YOJ,
-        // platform::Platform::Yukicoder, // Takes a long time to fetch all problems.
-    ];
+    if env::args().any(|arg| arg == "--schedule") {
+        // The platforms are updated concurrently, each on its own schedule.
+        let local = LocalSet::new();
+        for schedule in SCHEDULES.iter() {
+            let scheduler = scheduler.clone();
+            local.spawn_local(async move { scheduler.run_on_schedule(schedule).await });
+        }
+        local.await;
+        return Ok(());
+    }
 
-    for p in platforms {
+    let mut failed_platforms = vec![];
+    for schedule in SCHEDULES.iter() {
+        let p = schedule.platform;
         log::info!("Start fetching problems from {:?}.", p);
-        let usecase = update::UpdateUsecase::new(api_client.clone(), pool.clone());
-        usecase.fetch_and_update(&p).await?;
+
+        // A failure on one platform should not prevent the others from being updated.
+        match scheduler.run_job(&p, schedule.mode).await {
+            Ok(Some(_)) => {}
+            Ok(None) => log::warn!("Skipped {:?}, which is being updated by another run.", p),
+            Err(e) => {
+                log::error!("Failed to update {:?}: {}", p, e);
+                failed_platforms.push(p);
+            }
+        }
         log::info!("Finished fetching problems from {:?}.", p);
     }
 
     log::info!("Batch process finished.");
 
+    if !failed_platforms.is_empty() {
+        anyhow::bail!("Failed to update {:?}", failed_platforms);
+    }
+
     Ok(())
 }
@@ -0,0 +1,61 @@
+//! Batch Process to Estimate the Difficulties of Problems
+//! from the Contest Results and Submissions Stored in the Database
+
+use anyhow::Result;
+use dotenv::dotenv;
+use std::env;
+use std::sync::Arc;
+
+use api::config::HttpConfigs;
+use api::infra::api::api_client;
+use api::infra::repository::initialize_pool::{initialize_pool, run_migrations};
+use api::service::estimation::{EstimationUsecase, ESTIMATION_PLATFORMS};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    env_logger::init();
+    log::info!("Estimation started.");
+
+    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set.");
+    let pool = Arc::new(
+        initialize_pool(db_url)
+            .await
+            .expect("Failed to initialize the connection pool"),
+    );
+    run_migrations(&pool)
+        .await
+        .expect("Failed to apply the migrations");
+    let api_client = Arc::new(api_client::ApiClient::new(&HttpConfigs::from_env()));
+    let usecase = EstimationUsecase::new(api_client, pool);
+
+    let mut failed_platforms = vec![];
+    for p in ESTIMATION_PLATFORMS {
+        log::info!("Start estimating difficulties of {:?}.", p);
+
+        let result = async {
+            let contests = usecase.fetch_contest_results(&p).await?;
+            log::info!("Fetched results of {} contests.", contests);
+            let problems = usecase.estimate_difficulties(&p).await?;
+            log::info!("Estimated difficulties of {} problems.", problems);
+            anyhow::Ok(())
+        }
+        .await;
+
+        // A failure on one platform should not prevent the others from being estimated.
+        if let Err(e) = result {
+            log::error!("Failed to estimate difficulties of {:?}: {:#}", p, e);
+            failed_platforms.push(p);
+        }
+        log::info!("Finished estimating difficulties of {:?}.", p);
+    }
+
+    log::info!("Estimation finished.");
+
+    if !failed_platforms.is_empty() {
+        anyhow::bail!("Failed to estimate difficulties of {:?}", failed_platforms);
+    }
+
+    Ok(())
+}
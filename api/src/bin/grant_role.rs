@@ -0,0 +1,53 @@
+//! Command to Grant a Role to an Internal User
+//!
+//! Every user signs up as a member, so the first admin has to be granted from here:
+//!
+//! ```sh
+//! cargo run --bin grant_role -- <user ID> <member|moderator|admin>
+//! ```
+//!
+//! The user ID is the `id` returned by `GET /internal/users/me` after signing in.
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use std::env;
+
+use api::domain::vo::user_role::UserRole;
+use api::infra::repository::initialize_pool::{initialize_pool, run_migrations};
+use api::infra::repository::user::UserRepository;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    env_logger::init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [user_id, role] = args.as_slice() else {
+        anyhow::bail!("Usage: grant_role <user ID> <member|moderator|admin>");
+    };
+    let role = role.parse::<UserRole>().map_err(anyhow::Error::msg)?;
+
+    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set.");
+    let pool = initialize_pool(db_url)
+        .await
+        .expect("Failed to initialize the connection pool");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to apply the migrations");
+
+    let user = pool.update_role(user_id, role).await.with_context(|| {
+        format!(
+            "Failed to grant {} to the user {}",
+            String::from(role),
+            user_id
+        )
+    })?;
+    log::info!(
+        "Granted {} to the user {}.",
+        String::from(user.role),
+        user.id
+    );
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use dotenv::dotenv;
 use once_cell::sync::Lazy;
-use std::env;
+use std::{env, path::PathBuf};
+
+const DEFAULT_HTTP_TIMEOUT_SECONDS: u64 = 30;
+const DEFAULT_HTTP_MAX_RETRIES: u32 = 3;
 
 #[derive(serde::Deserialize)]
 pub struct Configs {
@@ -11,9 +14,72 @@ pub struct Configs {
     pub jwt_secret: String,
     pub jwt_cookie_key: String,
 
+    // OIDC
+    pub google_client_id: String,
+    pub google_client_secret: String,
+    pub google_redirect_url: String,
+    pub github_client_id: String,
+    pub github_client_secret: String,
+    pub github_redirect_url: String,
+
     // API Server
     pub port: u16,
     pub host: String,
+
+    // HTTP
+    pub http: HttpConfigs,
+}
+
+/// The settings of the requests to the upstream servers.
+///
+/// The batch processes read only these, so they do not need the secrets of the API server.
+#[derive(serde::Deserialize)]
+pub struct HttpConfigs {
+    pub user_agent: String,
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    /// Where the bodies of conditional requests are cached. `None` disables the conditional requests.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl HttpConfigs {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+
+        Self {
+            user_agent: env::var("HTTP_USER_AGENT").unwrap_or_else(|_| {
+                format!(
+                    "aurora/{} (competitive programming problem aggregator)",
+                    env!("CARGO_PKG_VERSION")
+                )
+            }),
+            timeout_seconds: env::var("HTTP_TIMEOUT_SECONDS").map_or(
+                DEFAULT_HTTP_TIMEOUT_SECONDS,
+                |s| {
+                    s.parse()
+                        .expect("HTTP_TIMEOUT_SECONDS must be a valid number")
+                },
+            ),
+            max_retries: env::var("HTTP_MAX_RETRIES").map_or(DEFAULT_HTTP_MAX_RETRIES, |s| {
+                s.parse().expect("HTTP_MAX_RETRIES must be a valid number")
+            }),
+            // An empty HTTP_CACHE_DIR disables the cache.
+            cache_dir: match env::var("HTTP_CACHE_DIR") {
+                Ok(dir) if dir.is_empty() => None,
+                Ok(dir) => Some(PathBuf::from(dir)),
+                Err(_) => default_cache_dir(),
+            },
+        }
+    }
+}
+
+/// `$XDG_CACHE_HOME/aurora/http`, or `~/.cache/aurora/http`, which survive across runs unlike the temporary directory.
+fn default_cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("aurora").join("http"))
 }
 
 pub static CONFIG: Lazy<Configs> = Lazy::new(|| {
@@ -25,10 +91,23 @@ pub static CONFIG: Lazy<Configs> = Lazy::new(|| {
         jwt_secret: env::var("JWT_SECRET").expect("JWT_SECRET is not set."),
         jwt_cookie_key: env::var("JWT_COOKIE_KEY").expect("JWT_COOKIE_KEY is not set."),
 
+        google_client_id: env::var("GOOGLE_CLIENT_ID").expect("GOOGLE_CLIENT_ID is not set."),
+        google_client_secret: env::var("GOOGLE_CLIENT_SECRET")
+            .expect("GOOGLE_CLIENT_SECRET is not set."),
+        google_redirect_url: env::var("GOOGLE_REDIRECT_URL")
+            .expect("GOOGLE_REDIRECT_URL is not set."),
+        github_client_id: env::var("GITHUB_CLIENT_ID").expect("GITHUB_CLIENT_ID is not set."),
+        github_client_secret: env::var("GITHUB_CLIENT_SECRET")
+            .expect("GITHUB_CLIENT_SECRET is not set."),
+        github_redirect_url: env::var("GITHUB_REDIRECT_URL")
+            .expect("GITHUB_REDIRECT_URL is not set."),
+
         port: env::var("PORT")
             .unwrap_or(String::from("8080"))
             .parse()
             .expect("PORT must be a valid number"),
         host: env::var("HOST").unwrap_or(String::from("127.0.0.1")),
+
+        http: HttpConfigs::from_env(),
     }
 });
@@ -1,10 +1,18 @@
-use actix_web::{self, web, HttpResponse};
+use actix_web::cookie::{time::Duration, Cookie, SameSite};
+use actix_web::{self, web, HttpRequest, HttpResponse};
 
 use crate::config::CONFIG;
-use crate::utils::jwt;
+use crate::service::auth::SESSION_TTL_SECONDS;
+use crate::utils::{cookie, jwt};
 use crate::{domain::vo::providers::AuthProvider, service::auth::Authenticate};
 use std::sync::Arc;
 
+/// The cookie which binds the state of a login to the browser which started it.
+const LOGIN_STATE_COOKIE: &str = "login_state";
+
+/// The callbacks of all the providers are under this path.
+const AUTH_PATH: &str = "/api/auth";
+
 pub struct AuthController<U: Authenticate> {
     usecase: Arc<U>,
 }
@@ -17,37 +25,72 @@ impl<U: Authenticate> AuthController<U> {
     pub async fn get_authenticate_url(&self, path: web::Path<String>) -> HttpResponse {
         match AuthProvider::try_from(path.as_str()) {
             Ok(provider) => match self.usecase.get_authenticate_url(&provider).await {
-                Ok(url) => HttpResponse::Found()
-                    .append_header(("Location", url))
-                    .finish(),
+                Ok((url, state)) => {
+                    // SameSite=Strict would drop the cookie on the redirect back from the provider.
+                    let cookie = Cookie::build(LOGIN_STATE_COOKIE, state)
+                        .http_only(true)
+                        .secure(true)
+                        .same_site(SameSite::Lax)
+                        .path(AUTH_PATH)
+                        .max_age(Duration::seconds(SESSION_TTL_SECONDS))
+                        .finish();
+
+                    HttpResponse::Found()
+                        .append_header(("Location", url))
+                        .cookie(cookie)
+                        .finish()
+                }
                 Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
             },
             Err(_) => HttpResponse::BadRequest().body("Invalid provider is specified"),
         }
     }
 
+    /// Complete the login, only in the browser which started it.
+    ///
+    /// Otherwise an attacker could log a victim in to the attacker's account
+    /// by sending the victim the `code` and `state` of a login the attacker started.
     pub async fn handle_callback(
         &self,
+        req: HttpRequest,
         path: web::Path<String>,
         query: web::Query<AuthCallbackQuery>,
     ) -> HttpResponse {
-        match AuthProvider::try_from(path.as_str()) {
-            Ok(provider) => match self.usecase.handle_callback(&provider, &query.code).await {
-                Ok(user) => {
-                    let jwt = jwt::encode_jwt(&CONFIG.jwt_secret, &user.id).ok();
-                    let cookie =
-                        actix_web::cookie::Cookie::build(&CONFIG.jwt_cookie_key, jwt.unwrap())
-                            .http_only(true)
-                            .path("/")
-                            // .secure(true)
-                            .finish();
-                    let response = HttpResponse::Ok().cookie(cookie).json(user);
-
-                    response
+        let Ok(provider) = AuthProvider::try_from(path.as_str()) else {
+            return HttpResponse::BadRequest().body("Invalid provider is specified");
+        };
+        if cookie::get_cookie_value(&req, LOGIN_STATE_COOKIE).as_deref() != Some(&query.state) {
+            return HttpResponse::BadRequest().body("Invalid or expired login state");
+        }
+
+        match self
+            .usecase
+            .handle_callback(&provider, &query.code, &query.state)
+            .await
+        {
+            Ok(Some(user)) => match jwt::encode_jwt(&CONFIG.jwt_secret, &user.id) {
+                Ok(jwt) => {
+                    let cookie = Cookie::build(&CONFIG.jwt_cookie_key, jwt)
+                        .http_only(true)
+                        .secure(true)
+                        .same_site(SameSite::Lax)
+                        .path("/")
+                        .finish();
+
+                    let mut login_state = Cookie::build(LOGIN_STATE_COOKIE, "")
+                        .path(AUTH_PATH)
+                        .finish();
+                    login_state.make_removal();
+
+                    HttpResponse::Ok()
+                        .cookie(cookie)
+                        .cookie(login_state)
+                        .json(user)
                 }
                 Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
             },
-            Err(_) => HttpResponse::BadRequest().body("Invalid provider is specified"),
+            Ok(None) => HttpResponse::BadRequest().body("Invalid or expired login state"),
+            Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
         }
     }
 
@@ -56,7 +99,15 @@ impl<U: Authenticate> AuthController<U> {
 
         match self.usecase.get_user_info(&user_id).await {
             Ok(user_info) => HttpResponse::Ok().json(user_info),
-            Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
+            Err(e) => match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => {
+                    HttpResponse::NotFound().body("The user is not found")
+                }
+                _ => {
+                    log::error!("{:?}", e);
+                    HttpResponse::InternalServerError().body("Internal Server Error")
+                }
+            },
         }
     }
 }
@@ -64,4 +115,173 @@ impl<U: Authenticate> AuthController<U> {
 #[derive(serde::Deserialize)]
 pub struct AuthCallbackQuery {
     code: String,
+    state: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+    use anyhow::Result;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use crate::domain::{
+        user::{PublicUser, User},
+        vo::user_role::UserRole,
+    };
+
+    #[derive(Default)]
+    struct StubUsecase {
+        called_back: AtomicBool,
+    }
+
+    impl Authenticate for StubUsecase {
+        async fn get_authenticate_url(&self, _: &AuthProvider) -> Result<(String, String)> {
+            Ok((
+                "https://provider.example/auth".to_string(),
+                "state_1".to_string(),
+            ))
+        }
+
+        async fn handle_callback(
+            &self,
+            _: &AuthProvider,
+            _: &str,
+            _: &str,
+        ) -> Result<Option<User>> {
+            self.called_back.store(true, Ordering::SeqCst);
+            Ok(None)
+        }
+
+        async fn get_user_info(&self, user_id: &str) -> Result<PublicUser> {
+            Ok(User {
+                id: user_id.to_string(),
+                username: Some("alice".to_string()),
+                github_id: None,
+                github_username: None,
+                google_id: Some("google_1".to_string()),
+                google_email: Some("alice@example.com".to_string()),
+                atcoder_username: Some("alice".to_string()),
+                codeforces_username: None,
+                yukicoder_username: None,
+                aoj_username: None,
+                yoj_username: None,
+                role: UserRole::Admin,
+            }
+            .into())
+        }
+    }
+
+    fn app(
+        controller: Arc<AuthController<StubUsecase>>,
+    ) -> App<
+        impl actix_web::dev::ServiceFactory<
+            actix_web::dev::ServiceRequest,
+            Config = (),
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        let (login, callback, user) = (controller.clone(), controller.clone(), controller);
+        App::new()
+            .route(
+                "/api/auth/login/{provider}",
+                web::get().to(move |path| {
+                    let controller = login.clone();
+                    async move { controller.get_authenticate_url(path).await }
+                }),
+            )
+            .route(
+                "/api/auth/callback/{provider}",
+                web::get().to(move |req, path, query| {
+                    let controller = callback.clone();
+                    async move { controller.handle_callback(req, path, query).await }
+                }),
+            )
+            .route(
+                "/api/auth/user/{user_id}",
+                web::get().to(move |path| {
+                    let controller = user.clone();
+                    async move { controller.user_info(path).await }
+                }),
+            )
+    }
+
+    #[actix_web::test]
+    async fn test_login_binds_state_to_browser() {
+        let controller = Arc::new(AuthController::new(Arc::new(StubUsecase::default())));
+        let app = test::init_service(app(controller)).await;
+
+        let res = test::call_service(
+            &app,
+            test::TestRequest::get()
+                .uri("/api/auth/login/github")
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::FOUND);
+
+        let cookie = res
+            .response()
+            .cookies()
+            .find(|c| c.name() == LOGIN_STATE_COOKIE)
+            .unwrap();
+        assert_eq!(cookie.value(), "state_1");
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    }
+
+    #[actix_web::test]
+    async fn test_callback_rejects_state_of_another_browser() {
+        let usecase = Arc::new(StubUsecase::default());
+        let controller = Arc::new(AuthController::new(usecase.clone()));
+        let app = test::init_service(app(controller)).await;
+
+        // Without the cookie, as in a victim's browser given the attacker's code and state
+        let req = test::TestRequest::get()
+            .uri("/api/auth/callback/github?code=code_1&state=state_1")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/api/auth/callback/github?code=code_1&state=state_1")
+            .cookie(Cookie::new(LOGIN_STATE_COOKIE, "state_2"))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        assert!(!usecase.called_back.load(Ordering::SeqCst));
+
+        let req = test::TestRequest::get()
+            .uri("/api/auth/callback/github?code=code_1&state=state_1")
+            .cookie(Cookie::new(LOGIN_STATE_COOKIE, "state_1"))
+            .to_request();
+        test::call_service(&app, req).await;
+
+        assert!(usecase.called_back.load(Ordering::SeqCst));
+    }
+
+    #[actix_web::test]
+    async fn test_user_info_hides_private_fields() {
+        let controller = Arc::new(AuthController::new(Arc::new(StubUsecase::default())));
+        let app = test::init_service(app(controller)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/auth/user/user_1")
+            .to_request();
+        let user: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(user["id"], "user_1");
+        assert_eq!(user["atcoder_username"], "alice");
+        assert!(user.get("google_email").is_none());
+        assert!(user.get("google_id").is_none());
+        assert!(user.get("role").is_none());
+    }
 }
@@ -1,10 +1,37 @@
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
 
+use crate::domain::vo::phase::Phase;
 use crate::infra::repository::contest::Condition;
 use crate::service::contest::FetchContest;
 
+#[derive(Deserialize)]
+struct QueryParams {
+    /// Comma-separated platforms, e.g., `atcoder,codeforces`.
+    platform: Option<String>,
+    /// Comma-separated categories, e.g., `ABC,Div.2`.
+    category: Option<String>,
+    /// One of `before`, `coding` and `finished`.
+    phase: Option<String>,
+    /// The earliest start time of the contests in seconds.
+    from_start_time: Option<String>,
+    /// The latest start time of the contests in seconds.
+    to_start_time: Option<String>,
+    page: Option<String>,
+    per_page: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CalendarQueryParams {
+    platform: Option<String>,
+    category: Option<String>,
+}
+
+/// The maximum number of contests returned at once.
+const MAX_PER_PAGE: i32 = 500;
+
 pub struct ContestController<U: FetchContest> {
     usecase: Arc<U>,
 }
@@ -14,14 +41,70 @@ impl<U: FetchContest> ContestController<U> {
         Self { usecase }
     }
 
-    pub async fn contests(&self) -> HttpResponse {
-        let condition = Condition::default();
+    pub async fn contests(&self, query: web::Query<serde_json::Value>) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let phase = match params.phase.as_deref().map(Phase::from) {
+            Some(Phase::Unknown) => {
+                return HttpResponse::BadRequest().body("Invalid phase is specified")
+            }
+            phase => phase,
+        };
+
+        let condition = Condition {
+            platforms: split_list(params.platform.as_deref()),
+            categories: split_list(params.category.as_deref()),
+            phase,
+            from_start_time: params
+                .from_start_time
+                .as_deref()
+                .and_then(|s| s.parse().ok()),
+            to_start_time: params.to_start_time.as_deref().and_then(|s| s.parse().ok()),
+            page: params.page.as_deref().and_then(|s| s.parse().ok()),
+            per_page: params
+                .per_page
+                .as_deref()
+                .and_then(|s| s.parse().ok())
+                .map(|size: i32| size.clamp(1, MAX_PER_PAGE)),
+        };
+
         match self.usecase.fetch_contests(&condition).await {
             Ok(contests) => HttpResponse::Ok().json(contests),
             Err(e) => {
-                println!("{:?}", e);
-                return HttpResponse::InternalServerError().body("Internal Server Error");
+                log::error!("{:?}", e);
+                HttpResponse::InternalServerError().body("Internal Server Error")
             }
         }
     }
+
+    pub async fn calendar(&self, query: web::Query<serde_json::Value>) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<CalendarQueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let platforms = split_list(params.platform.as_deref());
+        let categories = split_list(params.category.as_deref());
+
+        match self.usecase.fetch_calendar(&platforms, &categories).await {
+            Ok(calendar) => HttpResponse::Ok()
+                .content_type("text/calendar; charset=utf-8")
+                .body(calendar),
+            Err(e) => {
+                log::error!("{:?}", e);
+                HttpResponse::InternalServerError().body("Internal Server Error")
+            }
+        }
+    }
+}
+
+/// Split a comma-separated query value, ignoring empty items.
+fn split_list(value: Option<&str>) -> Vec<&str> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
 }
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::{ingestion_run::IngestionRunError, vo::platform::Platform};
+use crate::middleware::AuthenticatedUser;
+use crate::service::scheduler::ManageIngestionRun;
+
+#[derive(Deserialize)]
+struct QueryParams {
+    platform: Option<String>,
+    limit: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RefreshBody {
+    platform: String,
+    /// The contest to refresh, such as `atcoder_abc300`. The whole platform is refreshed if omitted.
+    contest_id: Option<String>,
+}
+
+const DEFAULT_LIMIT: i64 = 50;
+
+/// The maximum number of runs listed at once.
+const MAX_LIMIT: i64 = 500;
+
+pub struct IngestionRunController<U: ManageIngestionRun> {
+    usecase: Arc<U>,
+}
+
+impl<U: ManageIngestionRun + 'static> IngestionRunController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    /// Start a refresh, and answer with the run without waiting for it to finish.
+    pub async fn refresh(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<RefreshBody>,
+    ) -> HttpResponse {
+        let platform = match body.platform.parse::<Platform>() {
+            Ok(platform) => platform,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+        let contest_id = body
+            .contest_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+
+        let run = match self
+            .usecase
+            .start_refresh(&user.id, &platform, contest_id)
+            .await
+        {
+            Ok(run) => run,
+            Err(e) => return error_response(e),
+        };
+
+        // Fetching a whole platform can take minutes, longer than a client would wait.
+        let usecase = Arc::clone(&self.usecase);
+        let started = run.clone();
+        actix_web::rt::spawn(async move {
+            if let Err(e) = usecase.finish_refresh(&started).await {
+                log::error!("Failed to update {:?}: {:#}", started.platform, e);
+            }
+        });
+
+        HttpResponse::Accepted().json(run)
+    }
+
+    pub async fn runs(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        query: web::Query<serde_json::Value>,
+    ) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let platform = match params.platform.as_deref().map(str::parse::<Platform>) {
+            Some(Ok(platform)) => Some(platform),
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+            None => None,
+        };
+        let limit = params
+            .limit
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, MAX_LIMIT);
+
+        match self
+            .usecase
+            .get_runs(&user.id, platform.as_ref(), limit)
+            .await
+        {
+            Ok(runs) => HttpResponse::Ok().json(runs),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn last_error(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        query: web::Query<serde_json::Value>,
+    ) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let platform = match params.platform.as_deref().map(str::parse::<Platform>) {
+            Some(Ok(platform)) => Some(platform),
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+            None => None,
+        };
+
+        match self
+            .usecase
+            .get_last_error(&user.id, platform.as_ref())
+            .await
+        {
+            Ok(run) => HttpResponse::Ok().json(run),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<IngestionRunError>() {
+        Some(e @ IngestionRunError::Forbidden) => HttpResponse::Forbidden().body(e.to_string()),
+        Some(e @ IngestionRunError::Locked) => HttpResponse::Conflict().body(e.to_string()),
+        Some(e @ IngestionRunError::NotFound) => HttpResponse::NotFound().body(e.to_string()),
+        None => {
+            log::error!("{:?}", error);
+            HttpResponse::InternalServerError().body("Internal Server Error")
+        }
+    }
+}
@@ -1,6 +1,16 @@
 pub mod auth;
 pub mod contest;
 pub mod health;
+pub mod ingestion_run;
 pub mod problem;
+pub mod problem_list;
+pub mod rating_history;
+pub mod recommendation;
+pub mod scheduler;
 pub mod services;
 pub mod submission;
+pub mod tag;
+pub mod tag_proposal;
+pub mod user;
+pub mod user_stats;
+pub mod virtual_contest;
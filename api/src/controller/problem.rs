@@ -4,8 +4,8 @@ use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 
 use crate::domain::vo::platform::Platform;
-use crate::infra::repository::problem::Condition;
-use crate::service::problem::FetchProblem;
+use crate::infra::repository::problem::{Condition, SearchCondition};
+use crate::service::problem::{FetchProblem, StatusUser};
 
 #[derive(Deserialize)]
 struct QueryParams {
@@ -15,8 +15,23 @@ struct QueryParams {
     page_size: Option<String>,
     from_difficulty: Option<String>,
     to_difficulty: Option<String>,
+    /// The handle on the platform whose status is attached to each problem.
+    user: Option<String>,
+    /// The internal user whose linked account is used instead of `user`.
+    internal_user_id: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct SearchQueryParams {
+    q: Option<String>,
+    platform: Option<String>,
+    page: Option<String>,
+    page_size: Option<String>,
+}
+
+/// The maximum number of problems returned by a search at once.
+const MAX_SEARCH_PAGE_SIZE: i32 = 100;
+
 pub struct ProblemController<U: FetchProblem> {
     usecase: Arc<U>,
 }
@@ -45,7 +60,29 @@ impl<U: FetchProblem> ProblemController<U> {
                 to_difficulty: params.to_difficulty.as_deref().and_then(|s| s.parse().ok()),
             };
 
-            match self.usecase.fetch_problems(&condition).await {
+            let user = match (&params.user, &params.internal_user_id) {
+                (Some(_), Some(_)) => {
+                    return HttpResponse::BadRequest()
+                        .body("Only one of user and internal_user_id can be specified")
+                }
+                (Some(handle), None) => Some(StatusUser::Handle(handle)),
+                (None, Some(user_id)) => Some(StatusUser::Internal(user_id)),
+                (None, None) => None,
+            };
+
+            let result = match user {
+                Some(user) => {
+                    let Ok(platform) = path.parse::<Platform>() else {
+                        return HttpResponse::BadRequest().body("Invalid platform is specified");
+                    };
+                    self.usecase
+                        .fetch_problems_with_status(&platform, &condition, &user)
+                        .await
+                }
+                None => self.usecase.fetch_problems(&condition).await,
+            };
+
+            match result {
                 Ok(problems) => HttpResponse::Ok().json(problems),
                 Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
             }
@@ -53,4 +90,31 @@ impl<U: FetchProblem> ProblemController<U> {
             HttpResponse::BadRequest().body("Invalid query")
         }
     }
+
+    pub async fn search(&self, query: web::Query<serde_json::Value>) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<SearchQueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let q = params.q.as_deref().map(str::trim).unwrap_or_default();
+        if q.is_empty() {
+            return HttpResponse::BadRequest().body("Search query is empty");
+        }
+
+        let condition = SearchCondition {
+            query: q,
+            platform: params.platform.as_deref(),
+            page: params.page.as_deref().and_then(|s| s.parse().ok()),
+            page_size: params
+                .page_size
+                .as_deref()
+                .and_then(|s| s.parse().ok())
+                .map(|size: i32| size.clamp(1, MAX_SEARCH_PAGE_SIZE)),
+        };
+
+        match self.usecase.search_problems(&condition).await {
+            Ok(problems) => HttpResponse::Ok().json(problems),
+            Err(_) => HttpResponse::InternalServerError().body("Internal Server Error"),
+        }
+    }
 }
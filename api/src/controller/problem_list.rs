@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use actix_web::{http::header, web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::problem_list::{ProblemListEntry, ProblemListError, ProblemListExport};
+use crate::middleware::AuthenticatedUser;
+use crate::service::problem_list::ManageProblemList;
+
+#[derive(Deserialize)]
+pub struct CreateBody {
+    name: String,
+    description: Option<String>,
+    /// Whether other users can see the list.
+    #[serde(default)]
+    is_public: bool,
+    #[serde(default)]
+    items: Vec<ProblemListEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBody {
+    name: String,
+    description: Option<String>,
+    is_public: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ItemsBody {
+    /// All the problems in the new order.
+    items: Vec<ProblemListEntry>,
+}
+
+pub struct ProblemListController<U: ManageProblemList> {
+    usecase: Arc<U>,
+}
+
+impl<U: ManageProblemList> ProblemListController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn lists(&self, user: web::ReqData<AuthenticatedUser>) -> HttpResponse {
+        match self.usecase.get_problem_lists(&user.id).await {
+            Ok(lists) => HttpResponse::Ok().json(lists),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn list(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        match self.usecase.get_problem_list(&user.id, path.as_str()).await {
+            Ok(list) => HttpResponse::Ok().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn create(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<CreateBody>,
+    ) -> HttpResponse {
+        let body = body.into_inner();
+        let content = ProblemListExport {
+            name: body.name.trim().to_string(),
+            description: body.description,
+            items: body.items,
+        };
+
+        match self
+            .usecase
+            .create_problem_list(&user.id, &content, body.is_public)
+            .await
+        {
+            Ok(list) => HttpResponse::Created().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn update(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<UpdateBody>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .update_problem_list(
+                &user.id,
+                path.as_str(),
+                body.name.trim(),
+                body.description.as_deref(),
+                body.is_public,
+            )
+            .await
+        {
+            Ok(list) => HttpResponse::Ok().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .delete_problem_list(&user.id, path.as_str())
+            .await
+        {
+            Ok(()) => HttpResponse::NoContent().finish(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn replace_items(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<ItemsBody>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .replace_items(&user.id, path.as_str(), &body.items)
+            .await
+        {
+            Ok(list) => HttpResponse::Ok().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn upsert_item(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<ProblemListEntry>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .upsert_item(&user.id, path.as_str(), &body)
+            .await
+        {
+            Ok(list) => HttpResponse::Ok().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn export(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .export_problem_list(&user.id, path.as_str())
+            .await
+        {
+            Ok(export) => HttpResponse::Ok()
+                .insert_header((
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"problem-list-{}.json\"",
+                        path.as_str()
+                    ),
+                ))
+                .json(export),
+            Err(e) => error_response(e),
+        }
+    }
+
+    /// Import an exported list as a new private list of the user.
+    pub async fn import(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<ProblemListExport>,
+    ) -> HttpResponse {
+        let mut content = body.into_inner();
+        content.name = content.name.trim().to_string();
+
+        match self
+            .usecase
+            .create_problem_list(&user.id, &content, false)
+            .await
+        {
+            Ok(list) => HttpResponse::Created().json(list),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<ProblemListError>() {
+        Some(e @ ProblemListError::NotFound) => HttpResponse::NotFound().body(e.to_string()),
+        Some(e @ ProblemListError::Forbidden) => HttpResponse::Forbidden().body(e.to_string()),
+        Some(e @ ProblemListError::Invalid(_)) => HttpResponse::BadRequest().body(e.to_string()),
+        None => {
+            log::error!("{:?}", error);
+            HttpResponse::InternalServerError().body("Internal Server Error")
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+
+use crate::service::rating_history::FetchRatingHistory;
+
+pub struct RatingHistoryController<U: FetchRatingHistory> {
+    usecase: Arc<U>,
+}
+
+impl<U: FetchRatingHistory> RatingHistoryController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn ratings(&self, path: web::Path<String>) -> HttpResponse {
+        match self.usecase.fetch_rating_history(path.as_str()).await {
+            Ok(changes) => HttpResponse::Ok().json(changes),
+            Err(e) => match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => {
+                    HttpResponse::NotFound().body("The user is not found")
+                }
+                _ => {
+                    log::error!("{:?}", e);
+                    HttpResponse::InternalServerError().body("Internal Server Error")
+                }
+            },
+        }
+    }
+}
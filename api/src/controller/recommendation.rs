@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::{recommendation::RecommendationLevel, vo::platform::Platform};
+use crate::service::recommendation::{Recommend, RecommendationCondition};
+
+#[derive(Deserialize)]
+struct QueryParams {
+    /// `easy`, `moderate` (default) or `hard`.
+    level: Option<String>,
+    platform: Option<String>,
+    /// The preferred algorithm.
+    algo_id: Option<String>,
+    /// The preferred technical tag.
+    tag_id: Option<String>,
+    limit: Option<String>,
+}
+
+const DEFAULT_LIMIT: usize = 10;
+
+/// The maximum number of problems recommended at once.
+const MAX_LIMIT: usize = 50;
+
+pub struct RecommendationController<U: Recommend> {
+    usecase: Arc<U>,
+}
+
+impl<U: Recommend> RecommendationController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn recommendations(
+        &self,
+        path: web::Path<String>,
+        query: web::Query<serde_json::Value>,
+    ) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let level = match params
+            .level
+            .as_deref()
+            .map(str::parse::<RecommendationLevel>)
+        {
+            Some(Ok(level)) => level,
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+            None => RecommendationLevel::Moderate,
+        };
+
+        let platform = match params.platform.as_deref().map(str::parse::<Platform>) {
+            Some(Ok(platform)) => Some(platform),
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+            None => None,
+        };
+
+        let condition = RecommendationCondition {
+            level,
+            platform: platform.as_ref(),
+            algo_id: params.algo_id.as_deref(),
+            technical_tag_id: params.tag_id.as_deref(),
+            limit: params
+                .limit
+                .as_deref()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_LIMIT)
+                .clamp(1, MAX_LIMIT),
+        };
+
+        match self.usecase.recommend(path.as_str(), &condition).await {
+            Ok(recommendations) => HttpResponse::Ok().json(recommendations),
+            Err(e) => match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => {
+                    HttpResponse::NotFound().body("The user is not found")
+                }
+                _ => {
+                    log::error!("{:?}", e);
+                    HttpResponse::InternalServerError().body("Internal Server Error")
+                }
+            },
+        }
+    }
+}
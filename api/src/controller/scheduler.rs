@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use actix_web::HttpResponse;
+
+use crate::service::scheduler::FetchJobStatus;
+
+pub struct SchedulerController<U: FetchJobStatus> {
+    usecase: Arc<U>,
+}
+
+impl<U: FetchJobStatus> SchedulerController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn statuses(&self) -> HttpResponse {
+        match self.usecase.fetch_job_statuses().await {
+            Ok(statuses) => HttpResponse::Ok().json(statuses),
+            Err(e) => {
+                log::error!("{:?}", e);
+                HttpResponse::InternalServerError().body("Internal Server Error")
+            }
+        }
+    }
+}
@@ -1,19 +1,46 @@
 use super::{
-    auth::AuthController, contest::ContestController, problem::ProblemController,
-    submission::SubmissionController,
+    auth::AuthController, contest::ContestController, ingestion_run::IngestionRunController,
+    problem::ProblemController, problem_list::ProblemListController,
+    rating_history::RatingHistoryController, recommendation::RecommendationController,
+    scheduler::SchedulerController, submission::SubmissionController, tag::TagController,
+    tag_proposal::TagProposalController, user::UserController, user_stats::UserStatsController,
+    virtual_contest::VirtualContestController,
 };
 use crate::service::{
-    auth::Authenticate, contest::FetchContest, problem::FetchProblem, submission::FetchSubmission,
+    auth::Authenticate,
+    contest::FetchContest,
+    problem::FetchProblem,
+    problem_list::ManageProblemList,
+    rating_history::FetchRatingHistory,
+    recommendation::Recommend,
+    scheduler::{FetchJobStatus, ManageIngestionRun},
+    submission::FetchSubmission,
+    tag::FetchTag,
+    tag_proposal::ManageTagProposal,
+    user::ManageUser,
+    user_stats::FetchUserStats,
+    virtual_contest::ManageVirtualContest,
 };
 use actix_web::web;
 use std::sync::Arc;
 
+#[allow(clippy::too_many_arguments)]
 pub fn config_services(
     cfg: &mut web::ServiceConfig,
     submission_controller: Arc<SubmissionController<impl FetchSubmission + 'static>>,
     problem_controller: Arc<ProblemController<impl FetchProblem + 'static>>,
     contest_controller: Arc<ContestController<impl FetchContest + 'static>>,
-    // auth_controller: Arc<AuthController<impl Authenticate + 'static>>,
+    user_controller: Arc<UserController<impl ManageUser + 'static>>,
+    auth_controller: Arc<AuthController<impl Authenticate + 'static>>,
+    tag_proposal_controller: Arc<TagProposalController<impl ManageTagProposal + 'static>>,
+    tag_controller: Arc<TagController<impl FetchTag + 'static>>,
+    virtual_contest_controller: Arc<VirtualContestController<impl ManageVirtualContest + 'static>>,
+    problem_list_controller: Arc<ProblemListController<impl ManageProblemList + 'static>>,
+    recommendation_controller: Arc<RecommendationController<impl Recommend + 'static>>,
+    user_stats_controller: Arc<UserStatsController<impl FetchUserStats + 'static>>,
+    rating_history_controller: Arc<RatingHistoryController<impl FetchRatingHistory + 'static>>,
+    scheduler_controller: Arc<SchedulerController<impl FetchJobStatus + 'static>>,
+    ingestion_run_controller: Arc<IngestionRunController<impl ManageIngestionRun + 'static>>,
 ) {
     cfg.service(
         web::scope("/api")
@@ -35,6 +62,14 @@ pub fn config_services(
                     }
                 })),
             )
+            // This has to be registered before `/problems/{platform}`, which would match `search` as well.
+            .service(web::resource("/problems/search").route(web::get().to({
+                let controller = Arc::clone(&problem_controller);
+                move |query| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.search(query).await }
+                }
+            })))
             .service(web::resource("/problems/{platform}").route(web::get().to({
                 let controller = Arc::clone(&problem_controller);
                 move |path, query| {
@@ -42,36 +77,356 @@ pub fn config_services(
                     async move { controller.problems(path, query).await }
                 }
             })))
+            .service(
+                web::resource("/contests/calendar.ics").route(web::get().to({
+                    let controller = Arc::clone(&contest_controller);
+                    move |query| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.calendar(query).await }
+                    }
+                })),
+            )
             .service(web::resource("/contests").route(web::get().to({
                 let controller = Arc::clone(&contest_controller);
+                move |query| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.contests(query).await }
+                }
+            })))
+            .service(web::resource("/algorithms").route(web::get().to({
+                let controller = Arc::clone(&tag_controller);
+                move |req, query| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.algorithms(req, query).await }
+                }
+            })))
+            .service(web::resource("/tags").route(web::get().to({
+                let controller = Arc::clone(&tag_controller);
+                move |req, query| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.tags(req, query).await }
+                }
+            })))
+            .service(
+                web::resource("/recommendations/{user_id}").route(web::get().to({
+                    let controller = Arc::clone(&recommendation_controller);
+                    move |path, query| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.recommendations(path, query).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/users/{user_id}/submissions").route(web::get().to({
+                    let controller = Arc::clone(&user_controller);
+                    move |path| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.submissions(path).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/users/{user_id}/stats").route(web::get().to({
+                    let controller = Arc::clone(&user_stats_controller);
+                    move |path| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.stats(path).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/users/{user_id}/ratings").route(web::get().to({
+                    let controller = Arc::clone(&rating_history_controller);
+                    move |path| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.ratings(path).await }
+                    }
+                })),
+            )
+            .service(web::resource("/scheduler/status").route(web::get().to({
+                let controller = Arc::clone(&scheduler_controller);
                 move || {
                     let controller = Arc::clone(&controller);
-                    async move { controller.contests().await }
+                    async move { controller.statuses().await }
+                }
+            })))
+            .service(web::resource("/internal/users/me").route(web::get().to({
+                let controller = Arc::clone(&user_controller);
+                move |user| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.me(user).await }
+                }
+            })))
+            .service(
+                web::resource("/internal/users/me/accounts/{platform}")
+                    .route(web::post().to({
+                        let controller = Arc::clone(&user_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.link_account(user, path, body).await }
+                        }
+                    }))
+                    .route(web::delete().to({
+                        let controller = Arc::clone(&user_controller);
+                        move |user, path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.unlink_account(user, path).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/tag-proposals")
+                    .route(web::get().to({
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |query| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.proposals(query).await }
+                        }
+                    }))
+                    .route(web::post().to({
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |user, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.propose(user, body).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/tag-proposals/{proposal_id}/history").route(
+                    web::get().to({
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.history(path).await }
+                        }
+                    }),
+                ),
+            )
+            .service(
+                web::resource("/internal/tag-proposals/{proposal_id}/vote").route(web::post().to(
+                    {
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.vote(user, path, body).await }
+                        }
+                    },
+                )),
+            )
+            .service(
+                web::resource("/internal/tag-proposals/{proposal_id}/approve").route(
+                    web::post().to({
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.review(user, path, body, true).await }
+                        }
+                    }),
+                ),
+            )
+            .service(
+                web::resource("/internal/tag-proposals/{proposal_id}/reject").route(
+                    web::post().to({
+                        let controller = Arc::clone(&tag_proposal_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.review(user, path, body, false).await }
+                        }
+                    }),
+                ),
+            )
+            .service(web::resource("/internal/tags").route(web::post().to({
+                let controller = Arc::clone(&tag_proposal_controller);
+                move |user, body| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.create_tag(user, body).await }
+                }
+            })))
+            .service(
+                web::resource("/internal/virtual-contests")
+                    .route(web::get().to({
+                        let controller = Arc::clone(&virtual_contest_controller);
+                        move |user| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.contests(user).await }
+                        }
+                    }))
+                    .route(web::post().to({
+                        let controller = Arc::clone(&virtual_contest_controller);
+                        move |user, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.create(user, body).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/virtual-contests/{contest_id}").route(web::get().to({
+                    let controller = Arc::clone(&virtual_contest_controller);
+                    move |user, path| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.contest(user, path).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/internal/virtual-contests/{contest_id}/standings").route(
+                    web::get().to({
+                        let controller = Arc::clone(&virtual_contest_controller);
+                        move |user, path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.standings(user, path).await }
+                        }
+                    }),
+                ),
+            )
+            .service(
+                web::resource("/internal/virtual-contests/{contest_id}/participants").route(
+                    web::post().to({
+                        let controller = Arc::clone(&virtual_contest_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.add_participant(user, path, body).await }
+                        }
+                    }),
+                ),
+            )
+            .service(
+                web::resource("/internal/virtual-contests/{contest_id}/participants/{user_id}")
+                    .route(web::delete().to({
+                        let controller = Arc::clone(&virtual_contest_controller);
+                        move |user, path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.remove_participant(user, path).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/problem-lists")
+                    .route(web::get().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.lists(user).await }
+                        }
+                    }))
+                    .route(web::post().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.create(user, body).await }
+                        }
+                    })),
+            )
+            // This has to be registered before `/internal/problem-lists/{list_id}`, which would match `import` as well.
+            .service(
+                web::resource("/internal/problem-lists/import").route(web::post().to({
+                    let controller = Arc::clone(&problem_list_controller);
+                    move |user, body| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.import(user, body).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/internal/problem-lists/{list_id}")
+                    .route(web::get().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.list(user, path).await }
+                        }
+                    }))
+                    .route(web::put().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.update(user, path, body).await }
+                        }
+                    }))
+                    .route(web::delete().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, path| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.delete(user, path).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/problem-lists/{list_id}/items")
+                    .route(web::put().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.replace_items(user, path, body).await }
+                        }
+                    }))
+                    .route(web::post().to({
+                        let controller = Arc::clone(&problem_list_controller);
+                        move |user, path, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.upsert_item(user, path, body).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/problem-lists/{list_id}/export").route(web::get().to({
+                    let controller = Arc::clone(&problem_list_controller);
+                    move |user, path| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.export(user, path).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/internal/admin/ingestion-runs")
+                    .route(web::get().to({
+                        let controller = Arc::clone(&ingestion_run_controller);
+                        move |user, query| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.runs(user, query).await }
+                        }
+                    }))
+                    .route(web::post().to({
+                        let controller = Arc::clone(&ingestion_run_controller);
+                        move |user, body| {
+                            let controller = Arc::clone(&controller);
+                            async move { controller.refresh(user, body).await }
+                        }
+                    })),
+            )
+            .service(
+                web::resource("/internal/admin/ingestion-runs/last-error").route(web::get().to({
+                    let controller = Arc::clone(&ingestion_run_controller);
+                    move |user, query| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.last_error(user, query).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/auth/login/{provider}").route(web::get().to({
+                    let controller = Arc::clone(&auth_controller);
+                    move |provider| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.get_authenticate_url(provider).await }
+                    }
+                })),
+            )
+            .service(
+                web::resource("/auth/callback/{provider}").route(web::get().to({
+                    let controller = Arc::clone(&auth_controller);
+                    move |req, path, query| {
+                        let controller = Arc::clone(&controller);
+                        async move { controller.handle_callback(req, path, query).await }
+                    }
+                })),
+            )
+            .service(web::resource("/auth/user/{user_id}").route(web::get().to({
+                let controller = Arc::clone(&auth_controller);
+                move |path| {
+                    let controller = Arc::clone(&controller);
+                    async move { controller.user_info(path).await }
                 }
-            }))), // .service(
-                  //     web::resource("/auth/login/{provider}").route(web::get().to({
-                  //         let controller = Arc::clone(&auth_controller);
-                  //         move |provider| {
-                  //             let controller = Arc::clone(&controller);
-                  //             async move { controller.get_authenticate_url(provider).await }
-                  //         }
-                  //     })),
-                  // )
-                  // .service(
-                  //     web::resource("/auth/callback/{provider}").route(web::get().to({
-                  //         let controller = Arc::clone(&auth_controller);
-                  //         move |path, query| {
-                  //             let controller = Arc::clone(&controller);
-                  //             async move { controller.handle_callback(path, query).await }
-                  //         }
-                  //     })),
-                  // )
-                  // .service(web::resource("/auth/user/{user_id}").route(web::get().to({
-                  //     let controller = Arc::clone(&auth_controller);
-                  //     move |path| {
-                  //         let controller = Arc::clone(&controller);
-                  //         async move { controller.user_info(path).await }
-                  //     }
-                  // }))),
+            }))),
     );
 }
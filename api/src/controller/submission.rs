@@ -4,7 +4,7 @@ use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 
 use crate::{
-    domain::vo::platform::Platform,
+    domain::{submission::SubmissionError, vo::platform::Platform},
     service::submission::{FetchSubmission, PageCondition},
 };
 
@@ -29,11 +29,14 @@ impl<U: FetchSubmission> SubmissionController<U> {
     }
 
     pub async fn recent_submissions(&self, platform: web::Path<String>) -> HttpResponse {
-        let platform = Platform::from(platform.as_str());
+        let platform = match platform.parse::<Platform>() {
+            Ok(platform) => platform,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
 
         match self.usecase.fetch_recent_submissions(&platform).await {
             Ok(submissions) => HttpResponse::Ok().json(submissions),
-            Err(_) => HttpResponse::InternalServerError().finish(),
+            Err(e) => error_response(e),
         }
     }
 
@@ -42,7 +45,10 @@ impl<U: FetchSubmission> SubmissionController<U> {
         path: web::Path<(String, String)>,
         query: web::Query<serde_json::Value>,
     ) -> HttpResponse {
-        let platform = Platform::from(path.0.as_str());
+        let platform = match path.0.parse::<Platform>() {
+            Ok(platform) => platform,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
         let user_id = path.1.as_str();
 
         let submissions = match platform {
@@ -76,28 +82,156 @@ impl<U: FetchSubmission> SubmissionController<U> {
             }
             Platform::Codeforces | Platform::Aoj => {
                 if let Ok(params) = serde_json::from_value::<OtherQueryParams>(query.into_inner()) {
+                    let (page, size) = match (
+                        params.page.as_deref().map(str::parse::<u32>).transpose(),
+                        params.size.as_deref().map(str::parse::<u32>).transpose(),
+                    ) {
+                        (Ok(page), Ok(size)) => (page, size),
+                        _ => {
+                            return HttpResponse::BadRequest()
+                                .body("Invalid query: page and size must be numbers")
+                        }
+                    };
+
                     self.usecase
                         .fetch_user_submissions(
                             &platform,
                             &PageCondition::Other {
                                 user: user_id,
-                                page: params.page.map(|s| s.parse().unwrap()),
-                                size: params.size.map(|s| s.parse().unwrap()),
+                                page,
+                                size,
                             },
                         )
                         .await
                 } else {
-                    return HttpResponse::BadRequest().finish();
+                    return HttpResponse::BadRequest().body("Invalid query");
                 }
             }
-            _ => {
-                unimplemented!("Unsupported platform");
-            }
+            _ => Err(SubmissionError::UnsupportedPlatform(platform).into()),
         };
 
         match submissions {
             Ok(submissions) => HttpResponse::Ok().json(submissions),
-            Err(_) => HttpResponse::InternalServerError().finish(),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<SubmissionError>() {
+        Some(e @ SubmissionError::UnsupportedPlatform(_)) => {
+            HttpResponse::BadRequest().body(e.to_string())
+        }
+        None => {
+            log::error!("{:?}", error);
+            HttpResponse::InternalServerError().body("Internal Server Error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+    use anyhow::Result;
+    use std::sync::Mutex;
+
+    use crate::domain::submission::Submission;
+
+    /// Records the pages requested, and supports only the platforms of the real usecase.
+    #[derive(Default)]
+    struct StubUsecase {
+        pages: Mutex<Vec<(Option<u32>, Option<u32>)>>,
+    }
+
+    impl FetchSubmission for StubUsecase {
+        async fn fetch_recent_submissions(&self, platform: &Platform) -> Result<Vec<Submission>> {
+            match platform {
+                Platform::Atcoder | Platform::Codeforces | Platform::Aoj => Ok(vec![]),
+                _ => Err(SubmissionError::UnsupportedPlatform(*platform).into()),
+            }
         }
+
+        async fn fetch_user_submissions(
+            &self,
+            _: &Platform,
+            condition: &PageCondition<'_>,
+        ) -> Result<Vec<Submission>> {
+            if let PageCondition::Other { page, size, .. } = condition {
+                self.pages.lock().unwrap().push((*page, *size));
+            }
+            Ok(vec![])
+        }
+    }
+
+    fn app(
+        controller: Arc<SubmissionController<StubUsecase>>,
+    ) -> App<
+        impl actix_web::dev::ServiceFactory<
+            actix_web::dev::ServiceRequest,
+            Config = (),
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        let (recent, user) = (controller.clone(), controller);
+        App::new()
+            .route(
+                "/submissions/recent/{platform}",
+                web::get().to(move |path| {
+                    let controller = recent.clone();
+                    async move { controller.recent_submissions(path).await }
+                }),
+            )
+            .route(
+                "/submissions/{platform}/{user_id}",
+                web::get().to(move |path, query| {
+                    let controller = user.clone();
+                    async move { controller.user_submissions(path, query).await }
+                }),
+            )
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_requests_are_rejected() {
+        let controller = Arc::new(SubmissionController::new(Arc::new(StubUsecase::default())));
+        let app = test::init_service(app(controller)).await;
+
+        for uri in [
+            "/submissions/recent/unknown",
+            "/submissions/recent/yukicoder",
+            "/submissions/unknown/alice",
+            "/submissions/yukicoder/alice",
+            "/submissions/codeforces/alice?page=first",
+            "/submissions/aoj/alice?page=0&size=-1",
+            "/submissions/atcoder/alice?from_second=now",
+        ] {
+            let res =
+                test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", uri);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_pages_are_passed_to_usecase() {
+        let usecase = Arc::new(StubUsecase::default());
+        let controller = Arc::new(SubmissionController::new(usecase.clone()));
+        let app = test::init_service(app(controller)).await;
+
+        for uri in [
+            "/submissions/recent/aoj",
+            "/submissions/codeforces/alice?page=2&size=10",
+            "/submissions/aoj/alice",
+        ] {
+            let res =
+                test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(res.status(), StatusCode::OK, "{}", uri);
+        }
+
+        assert_eq!(
+            *usecase.pages.lock().unwrap(),
+            vec![(Some(2), Some(10)), (None, None)]
+        );
     }
 }
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::vo::locale::Locale;
+use crate::service::tag::FetchTag;
+
+#[derive(Deserialize)]
+struct QueryParams {
+    algo_id: Option<String>,
+    /// `en` or `ja`. This takes precedence over the `Accept-Language` header.
+    lang: Option<String>,
+}
+
+pub struct TagController<U: FetchTag> {
+    usecase: Arc<U>,
+}
+
+impl<U: FetchTag> TagController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn algorithms(
+        &self,
+        req: HttpRequest,
+        query: web::Query<serde_json::Value>,
+    ) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+        let locale = match resolve_locale(&req, params.lang.as_deref()) {
+            Ok(locale) => locale,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+
+        match self.usecase.fetch_algorithms(locale).await {
+            Ok(algorithms) => HttpResponse::Ok().json(algorithms),
+            Err(e) => {
+                log::error!("{:?}", e);
+                HttpResponse::InternalServerError().body("Internal Server Error")
+            }
+        }
+    }
+
+    pub async fn tags(
+        &self,
+        req: HttpRequest,
+        query: web::Query<serde_json::Value>,
+    ) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+        let locale = match resolve_locale(&req, params.lang.as_deref()) {
+            Ok(locale) => locale,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+
+        match self
+            .usecase
+            .fetch_tags(params.algo_id.as_deref(), locale)
+            .await
+        {
+            Ok(tags) => HttpResponse::Ok().json(tags),
+            Err(e) => {
+                log::error!("{:?}", e);
+                HttpResponse::InternalServerError().body("Internal Server Error")
+            }
+        }
+    }
+}
+
+/// Choose the locale by the `lang` parameter, then by the `Accept-Language` header.
+/// English is used if neither is given or the header has no supported language.
+fn resolve_locale(req: &HttpRequest, lang: Option<&str>) -> Result<Locale, String> {
+    if let Some(lang) = lang {
+        return lang.parse();
+    }
+
+    Ok(req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default())
+}
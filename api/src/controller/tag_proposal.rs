@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::{tag_proposal::TagProposalError, vo::proposal_status::ProposalStatus};
+use crate::middleware::AuthenticatedUser;
+use crate::service::tag_proposal::ManageTagProposal;
+
+#[derive(Deserialize)]
+struct QueryParams {
+    status: Option<String>,
+    problem_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ProposeBody {
+    problem_id: String,
+    technical_tag_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct VoteBody {
+    /// `true` for an up vote, `false` for a down vote.
+    up: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ReviewBody {
+    comment: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateTagBody {
+    en_name: String,
+    ja_name: String,
+    algorithm_id: String,
+}
+
+pub struct TagProposalController<U: ManageTagProposal> {
+    usecase: Arc<U>,
+}
+
+impl<U: ManageTagProposal> TagProposalController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn proposals(&self, query: web::Query<serde_json::Value>) -> HttpResponse {
+        let Ok(params) = serde_json::from_value::<QueryParams>(query.into_inner()) else {
+            return HttpResponse::BadRequest().body("Invalid query");
+        };
+
+        let status = match params.status.as_deref().map(str::parse::<ProposalStatus>) {
+            Some(Ok(status)) => Some(status),
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e),
+            None => None,
+        };
+
+        match self
+            .usecase
+            .get_proposals(status, params.problem_id.as_deref())
+            .await
+        {
+            Ok(proposals) => HttpResponse::Ok().json(proposals),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn history(&self, path: web::Path<String>) -> HttpResponse {
+        match self.usecase.get_proposal_history(path.as_str()).await {
+            Ok(events) => HttpResponse::Ok().json(events),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn propose(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<ProposeBody>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .propose(&user.id, &body.problem_id, &body.technical_tag_id)
+            .await
+        {
+            Ok(proposal) => HttpResponse::Created().json(proposal),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn vote(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<VoteBody>,
+    ) -> HttpResponse {
+        match self.usecase.vote(&user.id, path.as_str(), body.up).await {
+            Ok(proposal) => HttpResponse::Ok().json(proposal),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn review(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<ReviewBody>,
+        approve: bool,
+    ) -> HttpResponse {
+        let comment = body
+            .comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+
+        match self
+            .usecase
+            .review(&user.id, path.as_str(), approve, comment)
+            .await
+        {
+            Ok(proposal) => HttpResponse::Ok().json(proposal),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn create_tag(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<CreateTagBody>,
+    ) -> HttpResponse {
+        let (en_name, ja_name) = (body.en_name.trim(), body.ja_name.trim());
+        if en_name.is_empty() || ja_name.is_empty() {
+            return HttpResponse::BadRequest().body("Tag names must not be empty");
+        }
+
+        match self
+            .usecase
+            .create_tag(&user.id, en_name, ja_name, &body.algorithm_id)
+            .await
+        {
+            Ok(id) => HttpResponse::Created().json(serde_json::json!({ "id": id })),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<TagProposalError>() {
+        Some(e @ TagProposalError::NotFound) => HttpResponse::NotFound().body(e.to_string()),
+        Some(e @ (TagProposalError::Duplicated | TagProposalError::AlreadyReviewed)) => {
+            HttpResponse::Conflict().body(e.to_string())
+        }
+        Some(e @ TagProposalError::Forbidden) => HttpResponse::Forbidden().body(e.to_string()),
+        None => {
+            log::error!("{:?}", error);
+            HttpResponse::InternalServerError().body("Internal Server Error")
+        }
+    }
+}
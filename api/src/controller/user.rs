@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::{ingest::IngestError, user::UserError, vo::platform::Platform};
+use crate::middleware::AuthenticatedUser;
+use crate::service::user::ManageUser;
+
+#[derive(Deserialize)]
+pub struct LinkAccountBody {
+    handle: String,
+}
+
+pub struct UserController<U: ManageUser> {
+    usecase: Arc<U>,
+}
+
+impl<U: ManageUser> UserController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn me(&self, user: web::ReqData<AuthenticatedUser>) -> HttpResponse {
+        match self.usecase.get_user(&user.id).await {
+            Ok(user) => HttpResponse::Ok().json(user),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn link_account(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<LinkAccountBody>,
+    ) -> HttpResponse {
+        let Ok(platform) = path.parse::<Platform>() else {
+            return HttpResponse::BadRequest().body("Invalid platform is specified");
+        };
+
+        match self
+            .usecase
+            .link_account(&user.id, &platform, body.handle.trim())
+            .await
+        {
+            Ok(Some(user)) => HttpResponse::Ok().json(user),
+            Ok(None) => HttpResponse::NotFound().body("The handle does not exist on the platform"),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn unlink_account(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        let Ok(platform) = path.parse::<Platform>() else {
+            return HttpResponse::BadRequest().body("Invalid platform is specified");
+        };
+
+        match self.usecase.unlink_account(&user.id, &platform).await {
+            Ok(user) => HttpResponse::Ok().json(user),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn submissions(&self, path: web::Path<String>) -> HttpResponse {
+        match self.usecase.fetch_user_submissions(path.as_str()).await {
+            Ok(submissions) => HttpResponse::Ok().json(submissions),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    if let Some(e @ UserError::InvalidHandle { .. }) = error.downcast_ref::<UserError>() {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+    // The platform refuses an unknown handle, such as one renamed after it was linked.
+    if let Some(IngestError::Rejected { comment, .. }) = error.downcast_ref::<IngestError>() {
+        return HttpResponse::BadRequest().body(comment.clone());
+    }
+    if let Some(sqlx::Error::RowNotFound) = error.downcast_ref::<sqlx::Error>() {
+        return HttpResponse::NotFound().body("The user is not found");
+    }
+
+    log::error!("{:?}", error);
+    HttpResponse::InternalServerError().body("Internal Server Error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+    use anyhow::Result;
+
+    use crate::domain::{submission::Submission, user::User};
+
+    /// Fails the submissions of each user in its own way.
+    struct StubUsecase;
+
+    impl ManageUser for StubUsecase {
+        async fn get_user(&self, _: &str) -> Result<User> {
+            unreachable!()
+        }
+
+        async fn link_account(&self, _: &str, _: &Platform, _: &str) -> Result<Option<User>> {
+            unreachable!()
+        }
+
+        async fn unlink_account(&self, _: &str, _: &Platform) -> Result<User> {
+            unreachable!()
+        }
+
+        async fn fetch_user_submissions(&self, user_id: &str) -> Result<Vec<Submission>> {
+            match user_id {
+                "alice" => Ok(vec![]),
+                "unknown" => Err(sqlx::Error::RowNotFound.into()),
+                "invalid" => Err(UserError::InvalidHandle {
+                    platform: Platform::Atcoder,
+                    handle: "a/b".to_string(),
+                }
+                .into()),
+                "renamed" => Err(IngestError::Rejected {
+                    url: "https://codeforces.com/api/user.status".to_string(),
+                    comment: "handle: User with handle renamed not found".to_string(),
+                }
+                .into()),
+                _ => Err(anyhow::anyhow!("Connection refused")),
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_submissions_errors() {
+        let controller = Arc::new(UserController::new(Arc::new(StubUsecase)));
+        let app = test::init_service(App::new().route(
+            "/users/{user_id}/submissions",
+            web::get().to(move |path| {
+                let controller = controller.clone();
+                async move { controller.submissions(path).await }
+            }),
+        ))
+        .await;
+
+        for (user_id, status) in [
+            ("alice", StatusCode::OK),
+            ("unknown", StatusCode::NOT_FOUND),
+            ("invalid", StatusCode::BAD_REQUEST),
+            ("renamed", StatusCode::BAD_REQUEST),
+            ("down", StatusCode::INTERNAL_SERVER_ERROR),
+        ] {
+            let uri = format!("/users/{user_id}/submissions");
+            let res =
+                test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+            assert_eq!(res.status(), status, "{}", user_id);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+
+use crate::service::user_stats::FetchUserStats;
+
+pub struct UserStatsController<U: FetchUserStats> {
+    usecase: Arc<U>,
+}
+
+impl<U: FetchUserStats> UserStatsController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn stats(&self, path: web::Path<String>) -> HttpResponse {
+        match self.usecase.fetch_user_stats(path.as_str()).await {
+            Ok(stats) => HttpResponse::Ok().json(stats),
+            Err(e) => match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => {
+                    HttpResponse::NotFound().body("The user is not found")
+                }
+                _ => {
+                    log::error!("{:?}", e);
+                    HttpResponse::InternalServerError().body("Internal Server Error")
+                }
+            },
+        }
+    }
+}
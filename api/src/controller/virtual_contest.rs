@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::domain::{virtual_contest::VirtualContestError, vo::scoring_rule::ScoringRule};
+use crate::infra::repository::virtual_contest::NewVirtualContest;
+use crate::middleware::AuthenticatedUser;
+use crate::service::virtual_contest::ManageVirtualContest;
+
+#[derive(Deserialize)]
+pub struct CreateBody {
+    title: String,
+    /// In Unix time seconds.
+    start_time: i64,
+    /// In seconds.
+    duration: i64,
+    /// `icpc` or `atcoder`.
+    scoring: String,
+    problem_ids: Vec<String>,
+    /// The internal user IDs to invite.
+    #[serde(default)]
+    participant_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ParticipantBody {
+    user_id: String,
+}
+
+pub struct VirtualContestController<U: ManageVirtualContest> {
+    usecase: Arc<U>,
+}
+
+impl<U: ManageVirtualContest> VirtualContestController<U> {
+    pub fn new(usecase: Arc<U>) -> Self {
+        Self { usecase }
+    }
+
+    pub async fn contests(&self, user: web::ReqData<AuthenticatedUser>) -> HttpResponse {
+        match self.usecase.get_virtual_contests(&user.id).await {
+            Ok(contests) => HttpResponse::Ok().json(contests),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn contest(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .get_virtual_contest(&user.id, path.as_str())
+            .await
+        {
+            Ok(contest) => HttpResponse::Ok().json(contest),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn create(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        body: web::Json<CreateBody>,
+    ) -> HttpResponse {
+        let scoring = match body.scoring.parse::<ScoringRule>() {
+            Ok(scoring) => scoring,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        };
+
+        let contest = NewVirtualContest {
+            title: body.title.trim(),
+            owner_id: &user.id,
+            start_time: body.start_time,
+            duration: body.duration,
+            scoring,
+            problem_ids: &body.problem_ids,
+            participant_ids: &body.participant_ids,
+        };
+
+        match self.usecase.create_virtual_contest(&contest).await {
+            Ok(contest) => HttpResponse::Created().json(contest),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn add_participant(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+        body: web::Json<ParticipantBody>,
+    ) -> HttpResponse {
+        match self
+            .usecase
+            .add_participant(&user.id, path.as_str(), &body.user_id)
+            .await
+        {
+            Ok(contest) => HttpResponse::Ok().json(contest),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn remove_participant(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<(String, String)>,
+    ) -> HttpResponse {
+        let (contest_id, participant_id) = path.into_inner();
+
+        match self
+            .usecase
+            .remove_participant(&user.id, &contest_id, &participant_id)
+            .await
+        {
+            Ok(contest) => HttpResponse::Ok().json(contest),
+            Err(e) => error_response(e),
+        }
+    }
+
+    pub async fn standings(
+        &self,
+        user: web::ReqData<AuthenticatedUser>,
+        path: web::Path<String>,
+    ) -> HttpResponse {
+        match self.usecase.get_standings(&user.id, path.as_str()).await {
+            Ok(standings) => HttpResponse::Ok().json(standings),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> HttpResponse {
+    match error.downcast_ref::<VirtualContestError>() {
+        Some(e @ VirtualContestError::NotFound) => HttpResponse::NotFound().body(e.to_string()),
+        Some(e @ VirtualContestError::Forbidden) => HttpResponse::Forbidden().body(e.to_string()),
+        Some(e @ VirtualContestError::Invalid(_)) => HttpResponse::BadRequest().body(e.to_string()),
+        None => {
+            log::error!("{:?}", error);
+            HttpResponse::InternalServerError().body("Internal Server Error")
+        }
+    }
+}
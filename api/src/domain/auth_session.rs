@@ -0,0 +1,11 @@
+use super::vo::providers::AuthProvider;
+
+/// A login attempt that has been redirected to the provider and not yet completed.
+/// The CSRF token is sent to the provider as `state` and identifies the session on callback.
+pub struct AuthSession {
+    pub csrf_token: String,
+    pub provider: AuthProvider,
+    pub nonce: String,
+    pub pkce_verifier: String,
+    pub created_at: i64,
+}
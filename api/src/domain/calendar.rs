@@ -0,0 +1,138 @@
+//! iCalendar (RFC 5545) feed of contests
+//!
+//! Each contest with a known schedule becomes a `VEVENT` linking to the contest page.
+//! Contests without a start time or a duration, such as AOJ volumes, are left out.
+
+use chrono::DateTime;
+
+use super::contest::Contest;
+
+const PRODUCT_ID: &str = "-//aurora//Contest Calendar//EN";
+const CALENDAR_NAME: &str = "Programming Contests";
+const UID_DOMAIN: &str = "aurora";
+
+/// Lines longer than this (in octets, without the line break) must be folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Render the contests as an iCalendar stream, stamped with `now` (in seconds).
+pub fn to_icalendar(contests: &[Contest], now: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(CALENDAR_NAME)),
+    ];
+
+    for contest in contests {
+        let (Some(start), Some(duration)) = (contest.start_time_seconds, contest.duration_seconds)
+        else {
+            continue;
+        };
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@{UID_DOMAIN}", contest.id),
+            format!("DTSTAMP:{}", format_time(now)),
+            format!("DTSTART:{}", format_time(start)),
+            format!("DTEND:{}", format_time(start + duration)),
+            format!("SUMMARY:{}", escape_text(&contest.name)),
+            format!("CATEGORIES:{}", escape_text(&contest.category)),
+            format!("DESCRIPTION:{}", escape_text(&contest.url)),
+            format!("URL:{}", contest.url),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Format the time in UTC, e.g., `20240101T120000Z`.
+fn format_time(seconds: i64) -> String {
+    DateTime::from_timestamp(seconds, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escape a TEXT value.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Fold a content line so that no line exceeds 75 octets, without splitting a UTF-8 character.
+/// Continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::vo::platform::Platform;
+
+    #[test]
+    fn test_to_icalendar() {
+        let contests = vec![
+            Contest::reconstruct(
+                "abc300".to_string(),
+                "AtCoder Beginner Contest 300".to_string(),
+                "ABC".to_string(),
+                Platform::Atcoder,
+                "before".to_string(),
+                Some(1_682_769_600),
+                Some(6_000),
+                vec![],
+            ),
+            Contest::reconstruct(
+                "volume0".to_string(),
+                "Volume 0".to_string(),
+                "Volume 0".to_string(),
+                Platform::Aoj,
+                "finished".to_string(),
+                None,
+                None,
+                vec![],
+            ),
+        ];
+
+        let calendar = to_icalendar(&contests, 1_682_000_000);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("UID:atcoder_abc300@aurora\r\n"));
+        assert!(calendar.contains("DTSTART:20230429T120000Z\r\n"));
+        assert!(calendar.contains("DTEND:20230429T134000Z\r\n"));
+        assert!(calendar.contains("URL:https://atcoder.jp/contests/abc300\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(escape_text("a,b;c\\d"), "a\\,b\\;c\\\\d");
+
+        let line = format!("SUMMARY:{}", "あ".repeat(30));
+        let folded = fold_line(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,230 @@
+//! Difficulty calibration
+//!
+//! This module maps the difficulty indicators of each platform onto the AtCoder difficulty scale,
+//! so that a difficulty means the same thing regardless of the platform.
+//!
+//! - AtCoder: the difficulty estimated by AtCoder Problems (kenkoooo) is used as is.
+//! - Codeforces: the problem rating is mapped through calibration anchors.
+//! - yukicoder: the level given by the author is mapped through calibration anchors.
+//! - AOJ: the number of users who solved the problem is mapped through calibration anchors.
+//! - YOJ: no indicator is available.
+//!
+//! Difficulties estimated in-house from contest results (see `domain::estimation`) are on the scale
+//! of the platform's rating, and are mapped in the same way.
+//!
+//! The anchors are pairs of (platform value, AtCoder difficulty) and the values between them
+//! are linearly interpolated. Values outside the anchors are clamped and marked as low confidence.
+
+use super::{
+    estimation::DifficultyEstimate,
+    vo::{difficulty_confidence::DifficultyConfidence, platform::Platform},
+};
+
+/// A difficulty on the AtCoder scale with its confidence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizedDifficulty {
+    pub value: f64,
+    pub confidence: DifficultyConfidence,
+}
+
+/// Codeforces problem rating to AtCoder difficulty.
+const CF_RATING_ANCHORS: [(f64, f64); 8] = [
+    (800.0, 100.0),
+    (1200.0, 700.0),
+    (1600.0, 1300.0),
+    (1900.0, 1700.0),
+    (2100.0, 2000.0),
+    (2400.0, 2400.0),
+    (2800.0, 2900.0),
+    (3500.0, 3700.0),
+];
+
+/// yukicoder level to AtCoder difficulty.
+const YUKI_LEVEL_ANCHORS: [(f64, f64); 10] = [
+    (1.0, 100.0),
+    (1.5, 300.0),
+    (2.0, 500.0),
+    (2.5, 800.0),
+    (3.0, 1200.0),
+    (3.5, 1600.0),
+    (4.0, 2000.0),
+    (4.5, 2400.0),
+    (5.0, 2800.0),
+    (6.0, 3600.0),
+];
+
+/// Number of AOJ users who solved the problem to AtCoder difficulty.
+/// The values between them are interpolated on the logarithm of the number.
+const AOJ_SOLVED_USER_ANCHORS: [(f64, f64); 7] = [
+    (10.0, 2400.0),
+    (30.0, 2000.0),
+    (100.0, 1600.0),
+    (300.0, 1200.0),
+    (1000.0, 800.0),
+    (3000.0, 400.0),
+    (10000.0, 100.0),
+];
+
+/// AOJ problems with fewer submissions do not tell anything about their difficulty.
+const AOJ_MIN_SUBMISSIONS: i32 = 20;
+
+/// Linearly interpolate `x` between the anchors sorted by the platform value.
+///
+/// Returns the interpolated value and whether `x` is within the anchors.
+fn interpolate(anchors: &[(f64, f64)], x: f64) -> (f64, bool) {
+    let (first_x, first_y) = anchors[0];
+    let (last_x, last_y) = anchors[anchors.len() - 1];
+    if x < first_x {
+        return (first_y, false);
+    }
+    if x > last_x {
+        return (last_y, false);
+    }
+
+    for window in anchors.windows(2) {
+        let ((x0, y0), (x1, y1)) = (window[0], window[1]);
+        if x <= x1 {
+            return (y0 + (y1 - y0) * (x - x0) / (x1 - x0), true);
+        }
+    }
+
+    (last_y, true)
+}
+
+fn from_anchors(
+    anchors: &[(f64, f64)],
+    x: f64,
+    confidence: DifficultyConfidence,
+) -> NormalizedDifficulty {
+    let (value, in_range) = interpolate(anchors, x);
+
+    NormalizedDifficulty {
+        value: value.round(),
+        confidence: if in_range {
+            confidence
+        } else {
+            DifficultyConfidence::Low
+        },
+    }
+}
+
+/// Clip a low difficulty as AtCoder Problems does, so that it stays positive.
+pub fn clip(difficulty: f64) -> f64 {
+    if difficulty >= 400.0 {
+        difficulty.round()
+    } else {
+        (400.0 / f64::exp(1.0 - difficulty / 400.0)).round()
+    }
+}
+
+/// The difficulty estimated by AtCoder Problems, already clipped.
+pub fn from_atcoder(
+    difficulty: Option<f64>,
+    is_experimental: Option<bool>,
+) -> Option<NormalizedDifficulty> {
+    difficulty.map(|value| NormalizedDifficulty {
+        value,
+        confidence: if is_experimental.unwrap_or(false) {
+            DifficultyConfidence::Medium
+        } else {
+            DifficultyConfidence::High
+        },
+    })
+}
+
+/// The difficulty estimated in-house from the contest results of the platform.
+///
+/// These are less reliable than the estimates by AtCoder Problems,
+/// as only the participants whose submissions are stored are taken into account.
+pub fn from_estimate(
+    platform: Platform,
+    estimate: &DifficultyEstimate,
+) -> Option<NormalizedDifficulty> {
+    let confidence = if estimate.is_experimental {
+        DifficultyConfidence::Low
+    } else {
+        DifficultyConfidence::Medium
+    };
+
+    match platform {
+        Platform::Atcoder => Some(NormalizedDifficulty {
+            value: clip(estimate.difficulty),
+            confidence,
+        }),
+        Platform::Codeforces => Some(from_anchors(
+            &CF_RATING_ANCHORS,
+            estimate.difficulty,
+            confidence,
+        )),
+        _ => None,
+    }
+}
+
+pub fn from_cf_rating(rating: Option<f64>) -> Option<NormalizedDifficulty> {
+    rating.map(|rating| from_anchors(&CF_RATING_ANCHORS, rating, DifficultyConfidence::Medium))
+}
+
+pub fn from_yuki_level(level: Option<f64>) -> Option<NormalizedDifficulty> {
+    level
+        .filter(|level| *level > 0.0)
+        .map(|level| from_anchors(&YUKI_LEVEL_ANCHORS, level, DifficultyConfidence::Low))
+}
+
+pub fn from_aoj_statistics(
+    solved_user: Option<i32>,
+    submissions: Option<i32>,
+) -> Option<NormalizedDifficulty> {
+    if submissions? < AOJ_MIN_SUBMISSIONS {
+        return None;
+    }
+
+    // Problems nobody has solved are treated as solved by one user, the hardest end of the scale.
+    let solved_user = solved_user?.max(1) as f64;
+    let anchors =
+        AOJ_SOLVED_USER_ANCHORS.map(|(solved_user, difficulty)| (solved_user.ln(), difficulty));
+    Some(from_anchors(
+        &anchors,
+        solved_user.ln(),
+        DifficultyConfidence::Low,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration() {
+        // Exactly on and between the anchors
+        assert_eq!(from_cf_rating(Some(2400.0)).unwrap().value, 2400.0);
+        assert_eq!(from_cf_rating(Some(1400.0)).unwrap().value, 1000.0);
+        assert_eq!(
+            from_cf_rating(Some(1400.0)).unwrap().confidence,
+            DifficultyConfidence::Medium
+        );
+        assert_eq!(from_yuki_level(Some(3.0)).unwrap().value, 1200.0);
+        assert_eq!(
+            from_aoj_statistics(Some(100), Some(500)).unwrap().value,
+            1600.0
+        );
+
+        // Outside the anchors
+        let clamped = from_cf_rating(Some(4000.0)).unwrap();
+        assert_eq!(clamped.value, 3700.0);
+        assert_eq!(clamped.confidence, DifficultyConfidence::Low);
+        assert_eq!(
+            from_aoj_statistics(Some(0), Some(500)).unwrap().value,
+            2400.0
+        );
+
+        // Not enough information
+        assert_eq!(from_cf_rating(None), None);
+        assert_eq!(from_yuki_level(Some(0.0)), None);
+        assert_eq!(from_aoj_statistics(Some(10), Some(10)), None);
+
+        assert_eq!(
+            from_atcoder(Some(1234.0), Some(true)).unwrap().confidence,
+            DifficultyConfidence::Medium
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use super::vo::platform::Platform;
+
+/// The participation of a user in a rated contest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContestResult {
+    /// The ID of the contest, formatted as `<platform>_<raw_contest_id>` like `Contest::id`.
+    pub contest_id: String,
+    pub platform: Platform,
+    pub user_id: String,
+    /// The rating of the user just before the contest, on the platform's own scale.
+    pub rating: i32,
+}
+
+impl ContestResult {
+    pub fn reconstruct(
+        platform: Platform,
+        raw_contest_id: &str,
+        user_id: &str,
+        rating: i32,
+    ) -> Self {
+        Self {
+            contest_id: format!("{}_{}", String::from(platform), raw_contest_id),
+            platform,
+            user_id: user_id.to_string(),
+            rating,
+        }
+    }
+}
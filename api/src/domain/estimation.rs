@@ -0,0 +1,139 @@
+//! Difficulty estimation
+//!
+//! The difficulty of a problem is estimated from the results of the contest where it appeared,
+//! in the same way as AtCoder Problems does.
+//!
+//! Logistic regression is performed on pairs of (x, y), where:
+//! - `x` represents the rating of a participant just before the contest
+//! - `y` indicates whether the participant solved the problem within the contest
+//!
+//! The difficulty is the rating at which the probability of solving the problem is 0.5.
+//! It is on the scale of the rating used, so it has to be calibrated for platforms other than AtCoder.
+
+/// Samples fewer than this do not give any estimate.
+const MIN_SAMPLES: usize = 20;
+
+/// Estimates from fewer samples than this are marked as experimental.
+const MIN_STABLE_SAMPLES: usize = 100;
+
+/// Ratings are scaled by this value, the unit of AtCoder's rating formulas.
+const RATING_SCALE: f64 = 400.0;
+
+/// L2 regularization on the slope, which keeps it finite when the samples are perfectly separated.
+const SLOPE_REGULARIZATION: f64 = 0.1;
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub rating: f64,
+    pub solved: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyEstimate {
+    /// The estimated difficulty on the scale of the ratings of the samples.
+    pub difficulty: f64,
+    pub is_experimental: bool,
+}
+
+/// Estimate the difficulty of a problem from the samples of the participants.
+///
+/// `None` is returned if there are too few samples, if nobody or everybody solved the problem,
+/// or if stronger participants are not more likely to solve it.
+pub fn estimate(samples: &[Sample]) -> Option<DifficultyEstimate> {
+    if samples.len() < MIN_SAMPLES
+        || samples.iter().all(|s| s.solved)
+        || samples.iter().all(|s| !s.solved)
+    {
+        return None;
+    }
+
+    let mean = samples.iter().map(|s| s.rating).sum::<f64>() / samples.len() as f64;
+    let xs: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| {
+            (
+                (s.rating - mean) / RATING_SCALE,
+                if s.solved { 1.0 } else { 0.0 },
+            )
+        })
+        .collect();
+
+    // Newton's method on P(solved) = sigmoid(slope * x + intercept)
+    let (mut slope, mut intercept) = (1.0, 0.0);
+    for _ in 0..MAX_ITERATIONS {
+        let (mut g_slope, mut g_intercept) = (SLOPE_REGULARIZATION * slope, 0.0);
+        let (mut h_ss, mut h_si, mut h_ii) = (SLOPE_REGULARIZATION, 0.0, 0.0);
+        for (x, y) in xs.iter() {
+            let p = 1.0 / (1.0 + f64::exp(-(slope * x + intercept)));
+            let w = p * (1.0 - p);
+            g_slope += (p - y) * x;
+            g_intercept += p - y;
+            h_ss += w * x * x;
+            h_si += w * x;
+            h_ii += w;
+        }
+
+        let det = h_ss * h_ii - h_si * h_si;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        let d_slope = (h_ii * g_slope - h_si * g_intercept) / det;
+        let d_intercept = (h_ss * g_intercept - h_si * g_slope) / det;
+        slope -= d_slope;
+        intercept -= d_intercept;
+
+        if d_slope.abs() < TOLERANCE && d_intercept.abs() < TOLERANCE {
+            break;
+        }
+    }
+
+    if !slope.is_finite() || !intercept.is_finite() || slope <= 0.0 {
+        return None;
+    }
+
+    Some(DifficultyEstimate {
+        difficulty: (mean - intercept / slope * RATING_SCALE).round(),
+        is_experimental: samples.len() < MIN_STABLE_SAMPLES,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        // Participants solve the problem with the probability of the model with difficulty 1500,
+        // decided by a deterministic pseudo-random sequence.
+        let samples: Vec<Sample> = (0..300)
+            .map(|i| {
+                let rating = (i * 10) as f64;
+                let p = 1.0 / (1.0 + f64::exp(-(rating - 1500.0) / 200.0));
+                Sample {
+                    rating,
+                    solved: ((i * 37) % 100) as f64 / 100.0 < p,
+                }
+            })
+            .collect();
+        let result = estimate(&samples).unwrap();
+        assert!((result.difficulty - 1500.0).abs() < 100.0);
+        assert!(!result.is_experimental);
+
+        assert!(estimate(&samples[100..160]).unwrap().is_experimental);
+
+        // Too few samples
+        assert_eq!(estimate(&samples[140..150]), None);
+        // Nobody solved it
+        let unsolved = vec![
+            Sample {
+                rating: 1000.0,
+                solved: false
+            };
+            30
+        ];
+        assert_eq!(estimate(&unsolved), None);
+    }
+}
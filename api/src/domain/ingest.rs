@@ -0,0 +1,139 @@
+//! Errors and Reports of the Ingestion Pipeline
+//!
+//! The ingestion pipeline fetches problems and contests from each platform and stores them in the database.
+//! A malformed upstream record is skipped and recorded in `IngestReport`,
+//! so that a single record does not abort the whole run.
+
+use std::fmt;
+
+use super::vo::platform::Platform;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngestError {
+    /// The upstream server could not be reached.
+    Network { url: String, message: String },
+
+    /// The upstream API refused the request and explained why, such as for an unknown handle.
+    Rejected { url: String, comment: String },
+
+    /// The upstream API refused the request because too many requests were sent.
+    RateLimited { url: String },
+
+    /// The upstream response or one of its fields could not be decoded.
+    Decode { target: String, message: String },
+
+    /// The problem does not belong to any known contest.
+    OrphanProblem {
+        problem_id: String,
+        contest_id: Option<String>,
+    },
+
+    /// The records could not be written to the database.
+    DbWrite { table: String, message: String },
+
+    /// The contest to refresh is not listed by the platform.
+    ContestNotFound { contest_id: String },
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::Network { url, message } => {
+                write!(f, "Failed to fetch {url}: {message}")
+            }
+            IngestError::Rejected { url, comment } => {
+                write!(f, "The request to {url} was rejected: {comment}")
+            }
+            IngestError::RateLimited { url } => {
+                write!(f, "The request to {url} exceeded the rate limit")
+            }
+            IngestError::Decode { target, message } => {
+                write!(f, "Failed to decode {target}: {message}")
+            }
+            IngestError::OrphanProblem {
+                problem_id,
+                contest_id: Some(contest_id),
+            } => write!(
+                f,
+                "Problem {problem_id} refers to unknown contest {contest_id}"
+            ),
+            IngestError::OrphanProblem {
+                problem_id,
+                contest_id: None,
+            } => write!(f, "Problem {problem_id} does not belong to any contest"),
+            IngestError::DbWrite { table, message } => {
+                write!(f, "Failed to write to {table}: {message}")
+            }
+            IngestError::ContestNotFound { contest_id } => {
+                write!(f, "Contest {contest_id} is not found on the platform")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+impl IngestError {
+    /// Whether the same request may succeed later, as opposed to a record which is malformed upstream.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            IngestError::Network { .. } | IngestError::RateLimited { .. }
+        )
+    }
+}
+
+/// Summary of a single ingestion run for a platform.
+#[derive(Debug)]
+pub struct IngestReport {
+    pub platform: Platform,
+
+    /// The number of problems written to the database.
+    pub problems: usize,
+
+    /// The number of the written problems which were new. The others were updated.
+    pub problems_inserted: usize,
+
+    /// The number of contests written to the database.
+    pub contests: usize,
+
+    /// The number of the written contests which were new. The others were updated.
+    pub contests_inserted: usize,
+
+    /// The records skipped during the run and the reasons why.
+    pub skipped: Vec<IngestError>,
+}
+
+impl IngestReport {
+    pub fn new(platform: Platform) -> Self {
+        Self {
+            platform,
+            problems: 0,
+            problems_inserted: 0,
+            contests: 0,
+            contests_inserted: 0,
+            skipped: vec![],
+        }
+    }
+}
+
+impl fmt::Display for IngestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}: stored {} problems ({} new) and {} contests ({} new), skipped {} records",
+            self.platform,
+            self.problems,
+            self.problems_inserted,
+            self.contests,
+            self.contests_inserted,
+            self.skipped.len()
+        )?;
+
+        for error in &self.skipped {
+            write!(f, "\n  - {error}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+//! History of the Ingestion Runs
+//!
+//! Every update of a platform, whether scheduled or triggered by an admin, is recorded as a run,
+//! so that admins can see how long the updates take, what they wrote and why they failed.
+
+use serde::Serialize;
+use std::fmt;
+
+use super::{scheduler::UpdateMode, vo::platform::Platform};
+
+/// What a run updates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunMode {
+    /// All the problems and contests of the platform.
+    Full,
+    /// The problems newer than the stored watermark.
+    Incremental,
+    /// A single contest and its problems.
+    Contest,
+}
+
+impl From<UpdateMode> for RunMode {
+    fn from(value: UpdateMode) -> Self {
+        match value {
+            UpdateMode::Full => RunMode::Full,
+            UpdateMode::Incremental => RunMode::Incremental,
+        }
+    }
+}
+
+impl std::str::FromStr for RunMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "full" => Ok(RunMode::Full),
+            "incremental" => Ok(RunMode::Incremental),
+            "contest" => Ok(RunMode::Contest),
+            _ => Err(format!("Invalid run mode: {}", value)),
+        }
+    }
+}
+
+impl From<RunMode> for String {
+    fn from(value: RunMode) -> Self {
+        match value {
+            RunMode::Full => "full".to_string(),
+            RunMode::Incremental => "incremental".to_string(),
+            RunMode::Contest => "contest".to_string(),
+        }
+    }
+}
+
+/// A run of the update of a platform. Times are in Unix time seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngestionRun {
+    pub id: i64,
+    pub platform: Platform,
+    pub mode: RunMode,
+    /// The contest updated by a `Contest` run.
+    pub contest_id: Option<String>,
+    /// The admin who triggered the run, or `None` for the scheduled runs.
+    pub triggered_by: Option<String>,
+    pub started_at: i64,
+    /// `None` while the run is in progress, as well as `succeeded`.
+    pub finished_at: Option<i64>,
+    pub succeeded: Option<bool>,
+    pub error: Option<String>,
+    pub problems_inserted: i32,
+    pub problems_updated: i32,
+    pub contests_inserted: i32,
+    pub contests_updated: i32,
+    /// The number of malformed records skipped.
+    pub skipped: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngestionRunError {
+    /// The user is not an admin.
+    Forbidden,
+
+    /// Another run holds the lock of the platform.
+    Locked,
+
+    /// No run matches the request.
+    NotFound,
+}
+
+impl fmt::Display for IngestionRunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestionRunError::Forbidden => write!(f, "Only admins can manage ingestion runs"),
+            IngestionRunError::Locked => {
+                write!(f, "The platform is being updated by another run")
+            }
+            IngestionRunError::NotFound => write!(f, "The ingestion run is not found"),
+        }
+    }
+}
+
+impl std::error::Error for IngestionRunError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_mode() {
+        for (v, s) in [
+            (RunMode::Full, "full"),
+            (RunMode::Incremental, "incremental"),
+            (RunMode::Contest, "contest"),
+        ] {
+            assert_eq!(s.parse::<RunMode>(), Ok(v));
+            assert_eq!(String::from(v), s);
+        }
+        assert!("partial".parse::<RunMode>().is_err());
+    }
+}
@@ -1,5 +1,20 @@
+pub mod auth_session;
+pub mod calendar;
+pub mod calibration;
 pub mod contest;
+pub mod contest_result;
+pub mod estimation;
+pub mod ingest;
+pub mod ingestion_run;
 pub mod problem;
+pub mod problem_list;
+pub mod rating_history;
+pub mod recommendation;
+pub mod scheduler;
 pub mod submission;
+pub mod tag_proposal;
+pub mod taxonomy;
 pub mod user;
+pub mod user_stats;
+pub mod virtual_contest;
 pub mod vo;
@@ -1,4 +1,10 @@
-use super::vo::platform::Platform;
+use super::{
+    calibration::NormalizedDifficulty,
+    vo::{
+        difficulty_confidence::DifficultyConfidence, platform::Platform,
+        problem_status::ProblemStatus,
+    },
+};
 
 #[derive(Clone, Debug, PartialEq, sqlx::FromRow, serde::Serialize, serde::Deserialize)]
 pub struct Problem {
@@ -95,7 +101,9 @@ pub struct Problem {
     /// - Aoj: None
     pub raw_point: Option<f64>,
 
-    /// The value of this field is estimated using the following method:
+    /// The difficulty of the problem on the AtCoder scale.
+    ///
+    /// For AtCoder, the value of this field is estimated using the following method:
     ///
     /// Logistic regression is performed on pairs of (x, y), where:
     /// - `x` represents the AtCoder internal rating
@@ -103,6 +111,12 @@ pub struct Problem {
     ///
     /// The `Difficulty` is defined as the AtCoder internal rating at which the probability of solving the problem within the time limit is 0.5.
     /// This method is similar to the approach used by AtCoder Problems.
+    ///
+    /// The problems which AtCoder Problems has not modeled yet are estimated in-house in the same way.
+    /// See `domain::estimation` for details.
+    ///
+    /// For the other platforms, the platform's own indicator is mapped onto the same scale.
+    /// See `domain::calibration` for details.
     pub difficulty: Option<f64>,
 
     /// Whether the estimated difficulty is experimental or not.
@@ -111,6 +125,11 @@ pub struct Problem {
     /// Otherwise, the value of this field is `false`.
     pub is_experimental: Option<bool>,
 
+    /// How reliable `difficulty` is as an AtCoder-equivalent value.
+    ///
+    /// `None` if the difficulty is unknown.
+    pub difficulty_confidence: Option<DifficultyConfidence>,
+
     /// The tags of the problem.
     pub tags: Vec<String>,
 
@@ -131,6 +150,13 @@ pub struct Problem {
 
     /// The success rate of the problem.
     pub success_rate: Option<f64>,
+
+    /// The status of the problem for the requested user.
+    ///
+    /// This is only set when the problems are listed for a user, and never stored.
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ProblemStatus>,
 }
 
 impl Problem {
@@ -146,6 +172,7 @@ impl Problem {
         raw_point: Option<f64>,
         difficulty: Option<f64>,
         is_experimental: Option<bool>,
+        difficulty_confidence: Option<DifficultyConfidence>,
         tags: Vec<String>,
         url: String,
         solver_count: Option<i32>,
@@ -164,11 +191,13 @@ impl Problem {
             raw_point,
             difficulty,
             is_experimental,
+            difficulty_confidence,
             tags,
             url,
             solver_count,
             submissions,
             success_rate,
+            status: None,
         }
     }
 
@@ -179,7 +208,7 @@ impl Problem {
         raw_index: &str,
         raw_name: &str,
         raw_point: Option<f64>,
-        difficulty: Option<f64>,
+        difficulty: Option<NormalizedDifficulty>,
         category: String,
         is_experimental: Option<bool>,
         raw_tags: Vec<String>,
@@ -213,13 +242,15 @@ impl Problem {
             platform,
             category,
             raw_point,
-            difficulty,
+            difficulty: difficulty.map(|d| d.value),
             is_experimental,
+            difficulty_confidence: difficulty.map(|d| d.confidence),
             tags: raw_tags,
             url: String::from(raw_url),
             solver_count: raw_solver_count,
             submissions: raw_submissions,
             success_rate,
+            status: None,
         }
     }
 
@@ -235,6 +266,7 @@ impl Problem {
         raw_point: Option<f64>,
         difficulty: Option<f64>,
         is_experimental: Option<bool>,
+        difficulty_confidence: Option<DifficultyConfidence>,
         tags: Vec<String>,
         url: String,
         solver_count: Option<i32>,
@@ -253,11 +285,13 @@ impl Problem {
             raw_point,
             difficulty,
             is_experimental,
+            difficulty_confidence,
             tags,
             url,
             solver_count,
             submissions,
             success_rate,
+            status: None,
         }
     }
 }
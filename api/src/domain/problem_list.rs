@@ -0,0 +1,154 @@
+//! Personal Problem Lists
+//!
+//! Users make named lists of problems across platforms (e.g., a "DP training" set),
+//! ordered and annotated as they like.
+//! A list is private to the owner unless it is shared, in which case any user can see it.
+//!
+//! The progress on a list is computed for the user who sees it, from their linked accounts.
+//! Lists are exported and imported as `ProblemListExport`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{problem::Problem, vo::problem_status::ProblemStatus};
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct ProblemList {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether other users can see the list.
+    pub is_public: bool,
+    pub item_count: i64,
+    /// In Unix time seconds.
+    pub created_at: i64,
+    /// In Unix time seconds.
+    pub updated_at: i64,
+}
+
+/// A problem in a list, given by a client or exported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemListEntry {
+    pub problem_id: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct ProblemListItem {
+    #[sqlx(flatten)]
+    pub problem: Problem,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProblemListProgress {
+    pub total: usize,
+    pub accepted: usize,
+    /// Submitted, but not accepted yet.
+    pub tried: usize,
+}
+
+impl ProblemListProgress {
+    /// Count the statuses of the problems in the list. See `Problem::status`.
+    pub fn from_items(items: &[ProblemListItem]) -> Self {
+        items.iter().fold(
+            ProblemListProgress {
+                total: items.len(),
+                ..Default::default()
+            },
+            |mut progress, item| {
+                match item.problem.status {
+                    Some(ProblemStatus::Accepted) => progress.accepted += 1,
+                    Some(ProblemStatus::Tried) => progress.tried += 1,
+                    _ => {}
+                }
+                progress
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemListDetail {
+    #[serde(flatten)]
+    pub list: ProblemList,
+    /// In the order of the list.
+    pub items: Vec<ProblemListItem>,
+    /// The progress of the user who requested the list.
+    pub progress: ProblemListProgress,
+}
+
+/// The portable form of a list, which contains nothing specific to the owner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemListExport {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub items: Vec<ProblemListEntry>,
+}
+
+impl ProblemListExport {
+    pub fn new(list: &ProblemList, items: &[ProblemListItem]) -> Self {
+        Self {
+            name: list.name.clone(),
+            description: list.description.clone(),
+            items: items
+                .iter()
+                .map(|item| ProblemListEntry {
+                    problem_id: item.problem.id.clone(),
+                    note: item.note.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProblemListError {
+    /// The list or the problem does not exist.
+    NotFound,
+
+    /// The user is not allowed to see or change the list.
+    Forbidden,
+
+    /// The request is not valid, with the reason.
+    Invalid(String),
+}
+
+impl fmt::Display for ProblemListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemListError::NotFound => write!(f, "The problem list or problem is not found"),
+            ProblemListError::Forbidden => {
+                write!(f, "The problem list is not accessible to the user")
+            }
+            ProblemListError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ProblemListError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_roundtrip() {
+        let json = r#"{"name":"DP training","items":[{"problem_id":"atcoder_dp_A"},{"problem_id":"codeforces_1000_A","note":"Knapsack"}]}"#;
+
+        let export: ProblemListExport = serde_json::from_str(json).unwrap();
+        assert_eq!(export.description, None);
+        assert_eq!(export.items[0].note, None);
+        assert_eq!(export.items[1].note.as_deref(), Some("Knapsack"));
+
+        let exported = serde_json::to_string(&export).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProblemListExport>(&exported).unwrap(),
+            export
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Rating History
+//!
+//! The rating changes of the linked AtCoder and Codeforces accounts by rated contests.
+
+use serde::Serialize;
+use std::fmt;
+
+use super::{calibration, vo::platform::Platform};
+
+/// Platforms whose rating histories can be fetched.
+pub const RATING_PLATFORMS: [Platform; 2] = [Platform::Atcoder, Platform::Codeforces];
+
+/// A change of the rating of a user by a rated contest.
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct RatingChange {
+    /// The ID of the contest, formatted as `<platform>_<raw_contest_id>` like `Contest::id`.
+    pub contest_id: String,
+    pub platform: Platform,
+    /// The handle of the user on the platform.
+    pub user_id: String,
+    pub contest_name: String,
+    pub rank: i32,
+    /// The ratings on the platform's own scale.
+    pub old_rating: i32,
+    pub new_rating: i32,
+    /// Only AtCoder provides the performance.
+    pub performance: Option<i32>,
+    /// When the rating was updated in Unix time seconds.
+    pub rated_at: i64,
+}
+
+impl RatingChange {
+    #[allow(clippy::too_many_arguments)]
+    pub fn reconstruct(
+        platform: Platform,
+        raw_contest_id: &str,
+        user_id: &str,
+        contest_name: &str,
+        rank: i32,
+        old_rating: i32,
+        new_rating: i32,
+        performance: Option<i32>,
+        rated_at: i64,
+    ) -> Self {
+        Self {
+            contest_id: format!("{}_{}", String::from(platform), raw_contest_id),
+            platform,
+            user_id: user_id.to_string(),
+            contest_name: contest_name.to_string(),
+            rank,
+            old_rating,
+            new_rating,
+            performance,
+            rated_at,
+        }
+    }
+}
+
+/// The strength of a user on the AtCoder scale, used as the difficulty the user is expected to solve half of the time.
+///
+/// The latest rating of each platform is converted to the AtCoder scale and the highest one is taken.
+/// `None` is returned if the user has no rating.
+pub fn rated_strength(changes: &[RatingChange]) -> Option<f64> {
+    RATING_PLATFORMS
+        .iter()
+        .filter_map(|platform| {
+            let latest = changes
+                .iter()
+                .filter(|c| c.platform == *platform)
+                .max_by_key(|c| c.rated_at)?;
+            let rating = latest.new_rating as f64;
+
+            match platform {
+                Platform::Atcoder => Some(rating),
+                _ => calibration::from_cf_rating(Some(rating)).map(|d| d.value),
+            }
+        })
+        .max_by(|a, b| a.total_cmp(b))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RatingHistoryError {
+    /// The rating history of the platform cannot be fetched.
+    UnsupportedPlatform(Platform),
+}
+
+impl fmt::Display for RatingHistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingHistoryError::UnsupportedPlatform(platform) => {
+                write!(f, "The rating history of {:?} is not supported", platform)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RatingHistoryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rated_strength() {
+        assert_eq!(rated_strength(&[]), None);
+
+        let changes = [
+            RatingChange::reconstruct(
+                Platform::Atcoder,
+                "abc300",
+                "alice",
+                "ABC300",
+                10,
+                0,
+                1500,
+                Some(1800),
+                100,
+            ),
+            RatingChange::reconstruct(
+                Platform::Atcoder,
+                "abc301",
+                "alice",
+                "ABC301",
+                20,
+                1500,
+                1400,
+                Some(1200),
+                200,
+            ),
+        ];
+        assert_eq!(rated_strength(&changes), Some(1400.0));
+    }
+}
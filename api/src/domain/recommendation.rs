@@ -0,0 +1,138 @@
+//! Problem Recommendation
+//!
+//! The strength of a user is on the AtCoder scale. It is taken from the ratings of the user,
+//! or estimated from the difficulties of the problems the user has recently solved.
+//! The probability that the user solves a problem is then predicted in the same way as AtCoder Problems,
+//! and the unsolved problems whose probability falls into the requested level are recommended.
+
+use serde::Serialize;
+
+use super::problem::Problem;
+
+/// The number of the most recently solved problems considered for the strength.
+pub const RECENT_ACCEPTED: usize = 100;
+
+/// The strength is the mean difficulty of this number of the hardest recently solved problems.
+const STRENGTH_SAMPLES: usize = 10;
+
+/// The strength assumed for users who have not solved any problem with a known difficulty.
+pub const DEFAULT_STRENGTH: f64 = 400.0;
+
+/// How much harder a problem is than the strength of a user,
+/// for the odds of solving it to become a sixth, as in AtCoder Problems.
+const RATING_SCALE: f64 = 400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecommendationLevel {
+    Easy,
+    Moderate,
+    Hard,
+}
+
+impl RecommendationLevel {
+    /// The range of the probability of solving the recommended problems.
+    fn probability_range(&self) -> (f64, f64) {
+        match self {
+            RecommendationLevel::Easy => (0.7, 0.9),
+            RecommendationLevel::Moderate => (0.45, 0.7),
+            RecommendationLevel::Hard => (0.2, 0.45),
+        }
+    }
+
+    /// The range of the difficulty of the recommended problems for the strength.
+    pub fn difficulty_range(&self, strength: f64) -> (f64, f64) {
+        let (low, high) = self.probability_range();
+        (
+            difficulty_for(strength, high),
+            difficulty_for(strength, low),
+        )
+    }
+
+    /// The probability which the recommended problems are the closest to.
+    pub fn target_probability(&self) -> f64 {
+        let (low, high) = self.probability_range();
+        (low + high) / 2.0
+    }
+}
+
+impl std::str::FromStr for RecommendationLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "easy" => Ok(RecommendationLevel::Easy),
+            "moderate" => Ok(RecommendationLevel::Moderate),
+            "hard" => Ok(RecommendationLevel::Hard),
+            _ => Err(format!("Invalid level: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    #[serde(flatten)]
+    pub problem: Problem,
+    pub solve_probability: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendations {
+    /// The strength of the user on the AtCoder scale, from the ratings or the solved problems.
+    pub strength: f64,
+    /// `false` if the user has neither a rating nor a solved problem and `DEFAULT_STRENGTH` is used.
+    pub is_estimated: bool,
+    pub level: RecommendationLevel,
+    /// The best match first.
+    pub problems: Vec<Recommendation>,
+}
+
+/// Estimate the strength of a user from the difficulties of the recently solved problems.
+///
+/// `None` is returned if no difficulty is given.
+pub fn estimate_strength(difficulties: &[f64]) -> Option<f64> {
+    let mut difficulties = difficulties.to_vec();
+    difficulties.sort_by(|a, b| b.total_cmp(a));
+    difficulties.truncate(STRENGTH_SAMPLES);
+
+    if difficulties.is_empty() {
+        return None;
+    }
+
+    Some(difficulties.iter().sum::<f64>() / difficulties.len() as f64)
+}
+
+/// The probability that a user of the strength solves a problem of the difficulty.
+pub fn solve_probability(strength: f64, difficulty: f64) -> f64 {
+    1.0 / (1.0 + 6.0_f64.powf((difficulty - strength) / RATING_SCALE))
+}
+
+/// The inverse of `solve_probability`.
+fn difficulty_for(strength: f64, probability: f64) -> f64 {
+    strength + RATING_SCALE * ((1.0 - probability) / probability).log(6.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_strength() {
+        assert_eq!(estimate_strength(&[]), None);
+        assert_eq!(estimate_strength(&[800.0, 1200.0]), Some(1000.0));
+
+        let difficulties: Vec<f64> = (1..=20).map(|d| d as f64 * 100.0).collect();
+        // The mean of 1100, ..., 2000
+        assert_eq!(estimate_strength(&difficulties), Some(1550.0));
+    }
+
+    #[test]
+    fn test_difficulty_range() {
+        assert!((solve_probability(1200.0, 1200.0) - 0.5).abs() < 1e-9);
+
+        let (from, to) = RecommendationLevel::Moderate.difficulty_range(1200.0);
+        assert!((solve_probability(1200.0, from) - 0.7).abs() < 1e-9);
+        assert!((solve_probability(1200.0, to) - 0.45).abs() < 1e-9);
+        assert!(from < 1200.0 && 1200.0 < to);
+    }
+}
@@ -0,0 +1,153 @@
+//! Scheduled Updates of the Platforms
+//!
+//! The scheduler updates each platform at its own cadence.
+//! A run takes the lock of its platform first, so that two runs never write the same platform at once,
+//! and records its result as the status of the platform.
+
+use serde::Serialize;
+
+use super::vo::platform::Platform;
+
+/// How the problems of a platform are fetched. See `UpdateUsecase`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum UpdateMode {
+    /// Fetch all the problems and contests.
+    Full,
+    /// Fetch only the problems newer than the stored watermark.
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    pub platform: Platform,
+    pub mode: UpdateMode,
+    /// The interval between the starts of two runs in seconds.
+    pub interval_seconds: i64,
+}
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+pub const SCHEDULES: [Schedule; 5] = [
+    // The files of AtCoder Problems are fetched with conditional requests,
+    // so a run costs little unless a contest has been added.
+    Schedule {
+        platform: Platform::Atcoder,
+        mode: UpdateMode::Full,
+        interval_seconds: 10 * MINUTE,
+    },
+    Schedule {
+        platform: Platform::Codeforces,
+        mode: UpdateMode::Full,
+        interval_seconds: HOUR,
+    },
+    // Fetching all yukicoder problems takes a long time,
+    // so only the problems newer than the last run are fetched.
+    Schedule {
+        platform: Platform::Yukicoder,
+        mode: UpdateMode::Incremental,
+        interval_seconds: HOUR,
+    },
+    Schedule {
+        platform: Platform::Aoj,
+        mode: UpdateMode::Full,
+        interval_seconds: DAY,
+    },
+    Schedule {
+        platform: Platform::YOJ,
+        mode: UpdateMode::Full,
+        interval_seconds: DAY,
+    },
+];
+
+/// A lock held longer than this is regarded as left by a crashed run, and is taken over.
+pub const LOCK_TIMEOUT_SECONDS: i64 = 6 * HOUR;
+
+/// The state of the scheduled updates of a platform. Times are in Unix time seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobStatus {
+    pub platform: Platform,
+    /// When the running update started, if any.
+    pub running_since: Option<i64>,
+    pub last_started_at: Option<i64>,
+    pub last_finished_at: Option<i64>,
+    /// `None` if the platform has never been updated.
+    pub last_succeeded: Option<bool>,
+    pub last_error: Option<String>,
+    /// The numbers of problems and contests written and records skipped by the last run.
+    pub problems: i32,
+    pub contests: i32,
+    pub skipped: i32,
+}
+
+impl JobStatus {
+    /// The status of a platform which has never been updated.
+    pub fn new(platform: Platform) -> Self {
+        Self {
+            platform,
+            running_since: None,
+            last_started_at: None,
+            last_finished_at: None,
+            last_succeeded: None,
+            last_error: None,
+            problems: 0,
+            contests: 0,
+            skipped: 0,
+        }
+    }
+}
+
+/// The result of a finished run, recorded as the status of the platform and in the history of the runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRun {
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// `None` if the run succeeded.
+    pub error: Option<String>,
+    pub problems: i32,
+    /// Of `problems`, the number of those which were new. The others were updated.
+    pub problems_inserted: i32,
+    pub contests: i32,
+    /// Of `contests`, the number of those which were new. The others were updated.
+    pub contests_inserted: i32,
+    pub skipped: i32,
+}
+
+/// Whether the platform should be updated now.
+///
+/// A failed run is retried at the next interval as well, not to hammer a platform which is down.
+pub fn is_due(schedule: &Schedule, status: &JobStatus, now: i64) -> bool {
+    if status
+        .running_since
+        .is_some_and(|since| now < since + LOCK_TIMEOUT_SECONDS)
+    {
+        return false;
+    }
+
+    match status.last_started_at {
+        Some(started_at) => started_at + schedule.interval_seconds <= now,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let schedule = SCHEDULES[0];
+        let mut status = JobStatus::new(schedule.platform);
+        assert!(is_due(&schedule, &status, 1000));
+
+        status.last_started_at = Some(1000);
+        assert!(!is_due(&schedule, &status, 1000 + 10 * MINUTE - 1));
+        assert!(is_due(&schedule, &status, 1000 + 10 * MINUTE));
+
+        status.running_since = Some(2000);
+        assert!(!is_due(&schedule, &status, 2000 + LOCK_TIMEOUT_SECONDS - 1));
+        // The lock left by a crashed run
+        assert!(is_due(&schedule, &status, 2000 + LOCK_TIMEOUT_SECONDS));
+    }
+}
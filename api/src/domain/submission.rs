@@ -1,4 +1,5 @@
 use serde::Serialize;
+use std::fmt;
 
 use super::vo::{language::Language, platform::Platform, verdict::Verdict};
 
@@ -12,7 +13,7 @@ pub struct Submission {
     /// - Yukicoder: "yukicoder_111111"
     /// - Aoj: "aoj_222222"
     /// - YOJ: "yoj_333333"
-    id: String,
+    pub id: String,
 
     /// The identifier of the submission, specific to the platform.
     /// - Atcoder: 123456
@@ -20,37 +21,37 @@ pub struct Submission {
     /// - Yukicoder: 111111
     /// - Aoj: 222222
     /// - YOJ: 333333
-    raw_id: String,
+    pub raw_id: String,
 
     /// The identifier of the user who made the submission, specific to the platform.
-    user_id: String,
+    pub user_id: String,
 
     /// The programming language used for the submission.
-    language: String,
+    pub language: String,
 
     /// The raw representation of the programming language used for the submission as returned by the platform.
-    raw_language: String,
+    pub raw_language: String,
 
     /// The platform from which the submission was made.
-    platform: Platform,
+    pub platform: Platform,
 
     /// The result of the submission.
-    verdict: Verdict,
+    pub verdict: Verdict,
 
     /// The time taken to execute the submission in milliseconds.
-    execution_time: Option<u64>,
+    pub execution_time: Option<u64>,
 
     /// Optional memory usage of the submission in kilobytes.
-    memory: Option<u64>,
+    pub memory: Option<u64>,
 
     /// Optional size of the submitted code in bytes.
-    code_size: Option<u64>, // in bytes
+    pub code_size: Option<u64>, // in bytes
 
     /// The date and time when the submission was made in Unix time seconds.
-    submission_date: u64,
+    pub submission_date: u64,
 
     /// problem related to the submission
-    problem: ProblemInfo,
+    pub problem: ProblemInfo,
 }
 
 impl Submission {
@@ -98,15 +99,64 @@ impl Submission {
             },
         }
     }
+
+    /// Reconstructs a Submission instance from a row stored in the database.
+    pub fn reconstruct_from_db(
+        id: String,
+        raw_id: String,
+        user_id: String,
+        language: String,
+        raw_language: String,
+        platform: Platform,
+        verdict: Verdict,
+        execution_time: Option<u64>,
+        memory: Option<u64>,
+        code_size: Option<u64>,
+        submission_date: u64,
+        problem: ProblemInfo,
+    ) -> Self {
+        Self {
+            id,
+            raw_id,
+            user_id,
+            language,
+            raw_language,
+            platform,
+            verdict,
+            execution_time,
+            memory,
+            code_size,
+            submission_date,
+            problem,
+        }
+    }
 }
 
 /// Minimal information about a problem related to a submission.
-/// Intended for use only when the submission data is fetched from the platform.
+/// Intended for use only when the submission data is fetched from the platform or the database.
 #[derive(Clone, Debug, PartialEq, Serialize)]
-pub(self) struct ProblemInfo {
-    contest_id: Option<String>,
-    index: Option<String>,
-    name: Option<String>,
-    raw_point: Option<f64>,
-    difficulty: Option<f64>,
+pub struct ProblemInfo {
+    pub contest_id: Option<String>,
+    pub index: Option<String>,
+    pub name: Option<String>,
+    pub raw_point: Option<f64>,
+    pub difficulty: Option<f64>,
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmissionError {
+    /// The submissions of the platform cannot be fetched.
+    UnsupportedPlatform(Platform),
+}
+
+impl fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmissionError::UnsupportedPlatform(platform) => {
+                write!(f, "The submissions of {:?} are not supported", platform)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubmissionError {}
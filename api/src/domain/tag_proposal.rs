@@ -0,0 +1,95 @@
+//! Tag Proposals by the Community
+//!
+//! Most AtCoder and AOJ problems come without any tag, so users can propose technical tags for problems.
+//! Other users vote on the pending proposals, and a moderator approves or rejects them.
+//! An approved proposal attaches the tag to the problem.
+//!
+//! Every action on a proposal is recorded as a `TagProposalEvent` for the audit.
+
+use std::fmt;
+
+use super::vo::proposal_status::ProposalStatus;
+
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct TagProposal {
+    pub id: String,
+    pub problem_id: String,
+    pub technical_tag_id: String,
+    pub tag_en_name: String,
+    pub tag_ja_name: String,
+    pub proposer_id: String,
+    pub status: ProposalStatus,
+    /// The number of up votes minus that of down votes.
+    pub score: i64,
+    /// In Unix time seconds.
+    pub created_at: i64,
+    pub reviewer_id: Option<String>,
+    pub reviewed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct TagProposalEvent {
+    pub id: i64,
+    pub proposal_id: String,
+    pub actor_id: String,
+    /// One of `TagProposalAction`.
+    pub action: String,
+    pub comment: Option<String>,
+    /// In Unix time seconds.
+    pub created_at: i64,
+}
+
+/// The actions recorded in the history of a proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagProposalAction {
+    Propose,
+    VoteUp,
+    VoteDown,
+    Approve,
+    Reject,
+}
+
+impl From<TagProposalAction> for String {
+    fn from(value: TagProposalAction) -> Self {
+        match value {
+            TagProposalAction::Propose => "propose".to_string(),
+            TagProposalAction::VoteUp => "vote_up".to_string(),
+            TagProposalAction::VoteDown => "vote_down".to_string(),
+            TagProposalAction::Approve => "approve".to_string(),
+            TagProposalAction::Reject => "reject".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagProposalError {
+    /// The proposal, the problem or the tag does not exist.
+    NotFound,
+
+    /// The tag is already attached to the problem or proposed for it.
+    Duplicated,
+
+    /// The proposal has already been reviewed.
+    AlreadyReviewed,
+
+    /// The user is not allowed to review proposals.
+    Forbidden,
+}
+
+impl fmt::Display for TagProposalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagProposalError::NotFound => write!(f, "The proposal, problem or tag is not found"),
+            TagProposalError::Duplicated => {
+                write!(
+                    f,
+                    "The tag is already attached to or proposed for the problem"
+                )
+            }
+            TagProposalError::AlreadyReviewed => write!(f, "The proposal is already reviewed"),
+            TagProposalError::Forbidden => write!(f, "Only moderators can review proposals"),
+        }
+    }
+}
+
+impl std::error::Error for TagProposalError {}
@@ -0,0 +1,171 @@
+//! Tag Taxonomy
+//!
+//! Technical tags are grouped into algorithms, forming a two-level hierarchy.
+//! The names are shown in the locale requested by the client,
+//! and each tag carries the number of problems with it on each platform.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::vo::{
+    locale::Locale,
+    technique_tag::{Algorithm, TechnicalTag},
+};
+
+/// The number of problems with the tag on the platform.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TagProblemCount {
+    pub technical_tag_id: String,
+    pub platform: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalizedTag {
+    pub id: String,
+    pub name: String,
+    pub algorithm_id: String,
+    pub algorithm_name: String,
+    /// The number of problems with the tag by platform. Platforms without any are omitted.
+    pub problem_counts: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalizedAlgorithm {
+    pub id: String,
+    pub name: String,
+    pub tags: Vec<LocalizedTag>,
+}
+
+fn algorithm_name(algorithm: &Algorithm, locale: Locale) -> &str {
+    match locale {
+        Locale::En => &algorithm.name,
+        Locale::Ja => &algorithm.ja_name,
+    }
+}
+
+/// Name the tags in the locale and attach their problem counts.
+pub fn localize_tags(
+    tags: &[TechnicalTag],
+    algorithms: &[Algorithm],
+    counts: &[TagProblemCount],
+    locale: Locale,
+) -> Vec<LocalizedTag> {
+    let algorithms: HashMap<&str, &Algorithm> =
+        algorithms.iter().map(|a| (a.id.as_str(), a)).collect();
+
+    let mut counts_by_tag: HashMap<&str, BTreeMap<String, i64>> = HashMap::new();
+    for count in counts {
+        counts_by_tag
+            .entry(count.technical_tag_id.as_str())
+            .or_default()
+            .insert(count.platform.clone(), count.count);
+    }
+
+    tags.iter()
+        .map(|tag| LocalizedTag {
+            id: tag.id.clone(),
+            name: match locale {
+                Locale::En => tag.en_name.clone(),
+                Locale::Ja => tag.ja_name.clone(),
+            },
+            algorithm_id: tag.algorithm_id.clone(),
+            algorithm_name: algorithms
+                .get(tag.algorithm_id.as_str())
+                .map_or(tag.algorithm_name.as_str(), |a| algorithm_name(a, locale))
+                .to_string(),
+            problem_counts: counts_by_tag
+                .get(tag.id.as_str())
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Group the localized tags under their algorithms, keeping the order of both.
+/// Algorithms without any tag are kept with an empty list.
+pub fn build_hierarchy(
+    algorithms: &[Algorithm],
+    tags: Vec<LocalizedTag>,
+    locale: Locale,
+) -> Vec<LocalizedAlgorithm> {
+    let mut tags_by_algorithm: HashMap<String, Vec<LocalizedTag>> = HashMap::new();
+    for tag in tags {
+        tags_by_algorithm
+            .entry(tag.algorithm_id.clone())
+            .or_default()
+            .push(tag);
+    }
+
+    algorithms
+        .iter()
+        .map(|algorithm| LocalizedAlgorithm {
+            id: algorithm.id.clone(),
+            name: algorithm_name(algorithm, locale).to_string(),
+            tags: tags_by_algorithm.remove(&algorithm.id).unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_hierarchy() {
+        let algorithms = vec![
+            Algorithm {
+                id: "4".to_string(),
+                name: "DP".to_string(),
+                ja_name: "動的計画法".to_string(),
+            },
+            Algorithm {
+                id: "5".to_string(),
+                name: "Data Structures".to_string(),
+                ja_name: "データ構造".to_string(),
+            },
+        ];
+        let tags = vec![
+            TechnicalTag {
+                id: "55".to_string(),
+                en_name: "Speed Up DP".to_string(),
+                ja_name: "DPの高速化".to_string(),
+                algorithm_id: "4".to_string(),
+                algorithm_name: "DP".to_string(),
+            },
+            TechnicalTag {
+                id: "4".to_string(),
+                en_name: "DP".to_string(),
+                ja_name: "動的計画法".to_string(),
+                algorithm_id: "4".to_string(),
+                algorithm_name: "DP".to_string(),
+            },
+        ];
+        let counts = vec![
+            TagProblemCount {
+                technical_tag_id: "4".to_string(),
+                platform: "codeforces".to_string(),
+                count: 10,
+            },
+            TagProblemCount {
+                technical_tag_id: "4".to_string(),
+                platform: "yukicoder".to_string(),
+                count: 3,
+            },
+        ];
+
+        let localized = localize_tags(&tags, &algorithms, &counts, Locale::Ja);
+        assert_eq!(localized[0].name, "DPの高速化");
+        assert_eq!(localized[0].algorithm_name, "動的計画法");
+        assert!(localized[0].problem_counts.is_empty());
+        assert_eq!(localized[1].problem_counts["codeforces"], 10);
+
+        let hierarchy = build_hierarchy(&algorithms, localized, Locale::Ja);
+        assert_eq!(hierarchy.len(), 2);
+        assert_eq!(hierarchy[0].name, "動的計画法");
+        let tag_ids: Vec<_> = hierarchy[0].tags.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(tag_ids, vec!["55", "4"]);
+        assert!(hierarchy[1].tags.is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt;
+
+use super::vo::{platform::Platform, user_role::UserRole};
+
 #[derive(sqlx::FromRow, serde::Serialize, serde::Deserialize)]
 pub struct User {
     pub id: String,
@@ -15,4 +19,63 @@ pub struct User {
     pub yukicoder_username: Option<String>,
     pub aoj_username: Option<String>,
     pub yoj_username: Option<String>,
+
+    pub role: UserRole,
 }
+
+impl User {
+    /// Returns the username linked to the platform, if any.
+    pub fn platform_username(&self, platform: &Platform) -> Option<&str> {
+        match platform {
+            Platform::Atcoder => self.atcoder_username.as_deref(),
+            Platform::Codeforces => self.codeforces_username.as_deref(),
+            Platform::Yukicoder => self.yukicoder_username.as_deref(),
+            Platform::Aoj => self.aoj_username.as_deref(),
+            Platform::YOJ => self.yoj_username.as_deref(),
+        }
+    }
+}
+
+/// The profile of a user shown to anyone, without the external accounts nor the role.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct PublicUser {
+    pub id: String,
+    pub username: Option<String>,
+    pub atcoder_username: Option<String>,
+    pub codeforces_username: Option<String>,
+    pub yukicoder_username: Option<String>,
+    pub aoj_username: Option<String>,
+    pub yoj_username: Option<String>,
+}
+
+impl From<User> for PublicUser {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            atcoder_username: user.atcoder_username,
+            codeforces_username: user.codeforces_username,
+            yukicoder_username: user.yukicoder_username,
+            aoj_username: user.aoj_username,
+            yoj_username: user.yoj_username,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserError {
+    /// The handle contains characters which the platform does not use.
+    InvalidHandle { platform: Platform, handle: String },
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserError::InvalidHandle { platform, handle } => {
+                write!(f, "Invalid handle on {:?}: {}", platform, handle)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
@@ -0,0 +1,121 @@
+//! Statistics of a User
+//!
+//! The statistics are aggregated from the stored submissions of all the linked accounts.
+//! Days are counted in UTC.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::vo::{difficulty_band::DifficultyBand, platform::Platform};
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserStats {
+    /// The number of consecutive days with an accepted submission up to today.
+    ///
+    /// The streak is kept until the end of today, even if nothing has been accepted yet today.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// The number of distinct problems accepted on each platform.
+    pub accepted_by_platform: Vec<PlatformCount>,
+    /// The number of distinct accepted problems in each band, from the easiest.
+    pub accepted_by_difficulty: Vec<DifficultyBandCount>,
+    /// The number of distinct accepted problems whose difficulty is unknown.
+    pub accepted_without_difficulty: u32,
+    /// The most used language first.
+    pub languages: Vec<LanguageCount>,
+    /// When the statistics were computed in Unix time seconds.
+    pub computed_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlatformCount {
+    pub platform: Platform,
+    pub accepted: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyBandCount {
+    pub band: DifficultyBand,
+    pub accepted: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageCount {
+    /// The normalized language. See `Language`.
+    pub language: String,
+    pub submissions: u32,
+    /// The number of distinct problems accepted in the language.
+    pub accepted: u32,
+}
+
+/// The statistics stored in the database, in JSON.
+#[derive(Debug, sqlx::FromRow)]
+pub struct CachedUserStats {
+    /// Identifies the state of the stored submissions the statistics were computed from.
+    pub fingerprint: String,
+    pub stats: String,
+}
+
+/// The day of a time in Unix time seconds, counted from 1970-01-01.
+pub fn day_of(seconds: i64) -> i64 {
+    seconds.div_euclid(SECONDS_PER_DAY)
+}
+
+/// The current and the longest streaks of the days with an accepted submission.
+pub fn streaks(days: &BTreeSet<i64>, today: i64) -> (u32, u32) {
+    let mut longest = 0;
+    let mut length = 0;
+    let mut previous: Option<i64> = None;
+    for &day in days {
+        length = match previous {
+            Some(previous) if previous + 1 == day => length + 1,
+            _ => 1,
+        };
+        longest = longest.max(length);
+        previous = Some(day);
+    }
+
+    let current = match previous {
+        Some(last) if last == today || last + 1 == today => length,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaks() {
+        assert_eq!(streaks(&BTreeSet::new(), 100), (0, 0));
+
+        let days = BTreeSet::from([10, 11, 12, 20, 21]);
+        assert_eq!(streaks(&days, 21), (2, 3));
+        // Nothing accepted yet today
+        assert_eq!(streaks(&days, 22), (2, 3));
+        assert_eq!(streaks(&days, 23), (0, 3));
+    }
+
+    #[test]
+    fn test_difficulty_band() {
+        assert_eq!(
+            DifficultyBand::from_difficulty(-300.0),
+            DifficultyBand::Gray
+        );
+        assert_eq!(DifficultyBand::from_difficulty(399.9), DifficultyBand::Gray);
+        assert_eq!(
+            DifficultyBand::from_difficulty(400.0),
+            DifficultyBand::Brown
+        );
+        assert_eq!(
+            DifficultyBand::from_difficulty(1599.0),
+            DifficultyBand::Cyan
+        );
+        assert_eq!(DifficultyBand::from_difficulty(4000.0), DifficultyBand::Red);
+    }
+}
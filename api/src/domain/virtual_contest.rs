@@ -0,0 +1,295 @@
+//! Virtual Contests
+//!
+//! A virtual contest is a private contest made of any stored problems, possibly across platforms.
+//! Its participants are internal users, and their submissions with the linked accounts
+//! during the contest make the standings, by the ICPC or AtCoder rule.
+//!
+//! Only the owner and the participants can see a virtual contest.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::vo::{platform::Platform, scoring_rule::ScoringRule, verdict::Verdict};
+
+/// The penalty (in seconds) for a wrong submission before the accepted one by the ICPC rule.
+const ICPC_PENALTY_SECONDS: i64 = 20 * 60;
+
+/// The penalty (in seconds) for a wrong submission before the accepted one by the AtCoder rule.
+const ATCODER_PENALTY_SECONDS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct VirtualContest {
+    pub id: String,
+    pub title: String,
+    pub owner_id: String,
+    /// In Unix time seconds.
+    pub start_time: i64,
+    /// In seconds.
+    pub duration: i64,
+    pub scoring: ScoringRule,
+    /// In the order shown in the standings.
+    pub problem_ids: Vec<String>,
+    /// The internal user IDs, including the owner.
+    pub participant_ids: Vec<String>,
+    /// In Unix time seconds.
+    pub created_at: i64,
+}
+
+impl VirtualContest {
+    pub fn end_time(&self) -> i64 {
+        self.start_time + self.duration
+    }
+
+    /// Whether the user can see the contest.
+    pub fn is_member(&self, user_id: &str) -> bool {
+        self.owner_id == user_id || self.participant_ids.iter().any(|id| id == user_id)
+    }
+}
+
+/// A judged submission of a participant to a problem of the contest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    /// The position of the problem in the contest.
+    pub problem: usize,
+    /// In Unix time seconds.
+    pub submitted_at: i64,
+    pub accepted: bool,
+}
+
+impl Attempt {
+    /// Submissions not judged yet or rejected before the judge (e.g., compile errors) are not counted.
+    pub fn counts(verdict: &Verdict) -> bool {
+        !matches!(
+            verdict,
+            Verdict::CompileError
+                | Verdict::Waiting
+                | Verdict::WaitingRejudge
+                | Verdict::Testing
+                | Verdict::Skipped
+                | Verdict::Unknown
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StandingsProblem {
+    pub id: String,
+    pub title: String,
+    pub platform: Platform,
+    pub url: String,
+    /// The score for solving the problem.
+    pub point: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ProblemResult {
+    pub accepted: bool,
+    pub score: f64,
+    /// The number of wrong submissions before the first accepted one (or all of them if not accepted).
+    pub wrong_attempts: u32,
+    /// The elapsed seconds from the start to the first accepted submission.
+    pub accepted_after: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StandingsRow {
+    /// Participants with the same score and penalty share the rank.
+    pub rank: usize,
+    pub user_id: String,
+    pub username: Option<String>,
+    /// The number of solved problems by the ICPC rule, or the sum of the points by the AtCoder rule.
+    pub score: f64,
+    /// In seconds.
+    pub penalty: i64,
+    /// In the order of the problems.
+    pub results: Vec<ProblemResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Standings {
+    pub contest: VirtualContest,
+    pub problems: Vec<StandingsProblem>,
+    /// The best first.
+    pub rows: Vec<StandingsRow>,
+}
+
+/// Compute the results, the score and the penalty of a participant.
+///
+/// Only the attempts within the contest are counted.
+/// `points` are the scores of the problems, in the order of the problems.
+pub fn score_participant(
+    contest: &VirtualContest,
+    points: &[f64],
+    attempts: &[Attempt],
+) -> (Vec<ProblemResult>, f64, i64) {
+    let mut attempts: Vec<&Attempt> = attempts
+        .iter()
+        .filter(|a| contest.start_time <= a.submitted_at && a.submitted_at < contest.end_time())
+        .filter(|a| a.problem < points.len())
+        .collect();
+    attempts.sort_by_key(|a| a.submitted_at);
+
+    let mut results = vec![ProblemResult::default(); points.len()];
+    for attempt in attempts {
+        let result = &mut results[attempt.problem];
+        if result.accepted {
+            continue;
+        }
+
+        if attempt.accepted {
+            result.accepted = true;
+            result.score = points[attempt.problem];
+            result.accepted_after = Some(attempt.submitted_at - contest.start_time);
+        } else {
+            result.wrong_attempts += 1;
+        }
+    }
+
+    // Wrong submissions to the problems not solved are not penalized.
+    let solved: Vec<&ProblemResult> = results.iter().filter(|r| r.accepted).collect();
+    let wrong_attempts: i64 = solved.iter().map(|r| r.wrong_attempts as i64).sum();
+    let elapsed = solved.iter().filter_map(|r| r.accepted_after);
+
+    let (score, penalty) = match contest.scoring {
+        ScoringRule::Icpc => (
+            solved.len() as f64,
+            elapsed.sum::<i64>() + ICPC_PENALTY_SECONDS * wrong_attempts,
+        ),
+        ScoringRule::Atcoder => (
+            solved.iter().map(|r| r.score).sum(),
+            elapsed.max().unwrap_or_default() + ATCODER_PENALTY_SECONDS * wrong_attempts,
+        ),
+    };
+
+    (results, score, penalty)
+}
+
+/// Sort the rows, the best first, and rank them.
+pub fn rank_rows(rows: &mut [StandingsRow]) {
+    rows.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.penalty.cmp(&b.penalty))
+            .then_with(|| a.user_id.cmp(&b.user_id))
+    });
+
+    for i in 0..rows.len() {
+        rows[i].rank = if i > 0
+            && rows[i].score == rows[i - 1].score
+            && rows[i].penalty == rows[i - 1].penalty
+        {
+            rows[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VirtualContestError {
+    /// The contest, the problem or the user does not exist.
+    NotFound,
+
+    /// The user is not allowed to see or change the contest.
+    Forbidden,
+
+    /// The request is not valid, with the reason.
+    Invalid(String),
+}
+
+impl fmt::Display for VirtualContestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VirtualContestError::NotFound => {
+                write!(f, "The virtual contest, problem or user is not found")
+            }
+            VirtualContestError::Forbidden => {
+                write!(f, "The virtual contest is not accessible to the user")
+            }
+            VirtualContestError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for VirtualContestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_contest(scoring: ScoringRule) -> VirtualContest {
+        VirtualContest {
+            id: "1".to_string(),
+            title: "Virtual".to_string(),
+            owner_id: "owner".to_string(),
+            start_time: 1000,
+            duration: 3600,
+            scoring,
+            problem_ids: vec!["a".to_string(), "b".to_string()],
+            participant_ids: vec!["owner".to_string()],
+            created_at: 0,
+        }
+    }
+
+    fn attempt(problem: usize, submitted_at: i64, accepted: bool) -> Attempt {
+        Attempt {
+            problem,
+            submitted_at,
+            accepted,
+        }
+    }
+
+    #[test]
+    fn test_score_participant() {
+        let attempts = vec![
+            attempt(0, 1600, true),
+            attempt(0, 1300, false),
+            // After the first accepted submission
+            attempt(0, 1700, false),
+            attempt(1, 1900, false),
+            // Before the start and after the end
+            attempt(1, 900, true),
+            attempt(1, 4600, true),
+        ];
+
+        let (results, score, penalty) =
+            score_participant(&build_contest(ScoringRule::Icpc), &[1.0, 1.0], &attempts);
+        assert_eq!(score, 1.0);
+        assert_eq!(penalty, 600 + 20 * 60);
+        assert_eq!(results[0].accepted_after, Some(600));
+        assert_eq!(results[0].wrong_attempts, 1);
+        assert!(!results[1].accepted);
+        assert_eq!(results[1].wrong_attempts, 1);
+
+        let (_, score, penalty) = score_participant(
+            &build_contest(ScoringRule::Atcoder),
+            &[300.0, 500.0],
+            &attempts,
+        );
+        assert_eq!(score, 300.0);
+        assert_eq!(penalty, 600 + 5 * 60);
+    }
+
+    #[test]
+    fn test_rank_rows() {
+        let row = |user_id: &str, score: f64, penalty: i64| StandingsRow {
+            rank: 0,
+            user_id: user_id.to_string(),
+            username: None,
+            score,
+            penalty,
+            results: vec![],
+        };
+        let mut rows = vec![
+            row("a", 1.0, 100),
+            row("b", 2.0, 500),
+            row("c", 2.0, 300),
+            row("d", 1.0, 100),
+        ];
+
+        rank_rows(&mut rows);
+        let ranks: Vec<_> = rows.iter().map(|r| (r.user_id.as_str(), r.rank)).collect();
+        assert_eq!(ranks, vec![("c", 1), ("b", 2), ("a", 3), ("d", 3)]);
+    }
+}
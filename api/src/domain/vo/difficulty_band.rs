@@ -0,0 +1,35 @@
+/// `DifficultyBand` is a value object that represents the color of a difficulty on the AtCoder scale.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DifficultyBand {
+    Gray,
+    Brown,
+    Green,
+    Cyan,
+    Blue,
+    Yellow,
+    Orange,
+    Red,
+}
+
+impl DifficultyBand {
+    /// All the bands from the easiest.
+    pub const ALL: [DifficultyBand; 8] = [
+        DifficultyBand::Gray,
+        DifficultyBand::Brown,
+        DifficultyBand::Green,
+        DifficultyBand::Cyan,
+        DifficultyBand::Blue,
+        DifficultyBand::Yellow,
+        DifficultyBand::Orange,
+        DifficultyBand::Red,
+    ];
+
+    /// Each band spans 400 from gray (below 400) to red (2800 and above).
+    pub fn from_difficulty(difficulty: f64) -> Self {
+        let index = (difficulty / 400.0).floor().clamp(0.0, 7.0) as usize;
+        Self::ALL[index]
+    }
+}
@@ -0,0 +1,44 @@
+use std::convert::From;
+
+/// `DifficultyConfidence` is a value object that represents how reliable a difficulty is
+/// as an AtCoder-equivalent value.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Decode,
+    sqlx::Encode,
+)]
+#[sqlx(rename_all = "lowercase")]
+pub enum DifficultyConfidence {
+    /// Estimated from AtCoder contest results.
+    High,
+    /// Estimated from few results, or mapped from a platform rating calibrated against AtCoder.
+    Medium,
+    /// Mapped from a rough indicator such as an author-given level or solve statistics.
+    Low,
+}
+
+impl From<DifficultyConfidence> for String {
+    fn from(value: DifficultyConfidence) -> Self {
+        match value {
+            DifficultyConfidence::High => "high".to_string(),
+            DifficultyConfidence::Medium => "medium".to_string(),
+            DifficultyConfidence::Low => "low".to_string(),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for DifficultyConfidence {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        <&str as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &<sqlx::Postgres as sqlx::Database>::TypeInfo) -> bool {
+        <&str as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
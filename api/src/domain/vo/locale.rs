@@ -0,0 +1,68 @@
+/// `Locale` is a value object that represents the language in which names are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// Choose the supported locale preferred the most in an `Accept-Language` header,
+    /// e.g., `ja,en-US;q=0.9,en;q=0.8`.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.trim().split(';');
+                let locale = parts.next()?.parse::<Locale>().ok()?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f64>().ok())?;
+                Some((locale, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            // The first one wins among the locales of the same quality.
+            .fold(
+                None,
+                |best: Option<(Locale, f64)>, (locale, quality)| match best {
+                    Some((_, best_quality)) if best_quality >= quality => best,
+                    _ => Some((locale, quality)),
+                },
+            )
+            .map(|(locale, _)| locale)
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    /// Parse a language tag, ignoring its region (e.g., `en-US`).
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let language = value.split(['-', '_']).next().unwrap_or_default();
+        match language.to_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "ja" => Ok(Locale::Ja),
+            _ => Err(format!("Unsupported language: {}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept_language() {
+        assert_eq!(
+            Locale::from_accept_language("ja,en-US;q=0.9,en;q=0.8"),
+            Some(Locale::Ja)
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, en;q=0.5, ja;q=0.7"),
+            Some(Locale::Ja)
+        );
+        assert_eq!(Locale::from_accept_language("en-GB, ja"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("ja;q=0, fr"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+}
@@ -1,7 +1,15 @@
 pub mod category;
+pub mod difficulty_band;
+pub mod difficulty_confidence;
 pub mod language;
+pub mod locale;
 pub mod phase;
 pub mod platform;
+pub mod problem_status;
+pub mod proposal_status;
 pub mod providers;
+pub mod scoring_rule;
 pub mod technique_tag;
+pub mod user_role;
 pub mod verdict;
+pub mod watermark;
@@ -1,6 +1,7 @@
 use std::convert::From;
 
 /// `Phase` is a value object that represents the phase of the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Phase {
     Before,
     Finished,
@@ -8,6 +9,19 @@ pub enum Phase {
     Unknown,
 }
 
+impl Phase {
+    /// Decide the phase of a contest from its schedule at `now` (in seconds).
+    pub fn from_schedule(start_time_seconds: i64, duration_seconds: i64, now: i64) -> Self {
+        if now < start_time_seconds {
+            Phase::Before
+        } else if now < start_time_seconds + duration_seconds {
+            Phase::Coding
+        } else {
+            Phase::Finished
+        }
+    }
+}
+
 impl From<&str> for Phase {
     fn from(value: &str) -> Self {
         match value.to_lowercase().as_str() {
@@ -29,3 +43,16 @@ impl From<Phase> for String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_schedule() {
+        assert_eq!(Phase::from_schedule(100, 50, 99), Phase::Before);
+        assert_eq!(Phase::from_schedule(100, 50, 100), Phase::Coding);
+        assert_eq!(Phase::from_schedule(100, 50, 149), Phase::Coding);
+        assert_eq!(Phase::from_schedule(100, 50, 150), Phase::Finished);
+    }
+}
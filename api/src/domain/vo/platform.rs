@@ -6,6 +6,7 @@ use std::convert::From;
     Copy,
     PartialEq,
     Eq,
+    Hash,
     serde::Serialize,
     serde::Deserialize,
     sqlx::Decode,
@@ -22,13 +23,23 @@ pub enum Platform {
 
 impl From<&str> for Platform {
     fn from(value: &str) -> Self {
+        value.parse().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Unlike `From<&str>`, this does not panic on an invalid platform.
+/// Use this to parse a platform given by a client.
+impl std::str::FromStr for Platform {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "atcoder" => Platform::Atcoder,
-            "codeforces" => Platform::Codeforces,
-            "yukicoder" => Platform::Yukicoder,
-            "aoj" => Platform::Aoj,
-            "yosupo_online_judge" => Platform::YOJ,
-            _ => panic!("Invalid platform: {}", value),
+            "atcoder" => Ok(Platform::Atcoder),
+            "codeforces" => Ok(Platform::Codeforces),
+            "yukicoder" => Ok(Platform::Yukicoder),
+            "aoj" => Ok(Platform::Aoj),
+            "yosupo_online_judge" => Ok(Platform::YOJ),
+            _ => Err(format!("Invalid platform: {}", value)),
         }
     }
 }
@@ -71,5 +82,7 @@ mod tests {
             assert_eq!(Platform::from(s), v);
             assert_eq!(String::from(v), s);
         }
+
+        assert!("unknown".parse::<Platform>().is_err());
     }
 }
@@ -0,0 +1,10 @@
+/// `ProblemStatus` is a value object that represents how far a user has got with a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ProblemStatus {
+    /// At least one submission has been accepted.
+    Accepted,
+    /// Submitted, but never accepted.
+    Tried,
+    /// Never submitted.
+    Untouched,
+}
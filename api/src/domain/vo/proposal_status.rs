@@ -0,0 +1,55 @@
+use std::convert::From;
+
+/// `ProposalStatus` is a value object that represents the state of a proposal by the community.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Decode,
+    sqlx::Encode,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    /// Waiting for the review by a moderator.
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl From<ProposalStatus> for String {
+    fn from(value: ProposalStatus) -> Self {
+        match value {
+            ProposalStatus::Pending => "pending".to_string(),
+            ProposalStatus::Approved => "approved".to_string(),
+            ProposalStatus::Rejected => "rejected".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for ProposalStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(ProposalStatus::Pending),
+            "approved" => Ok(ProposalStatus::Approved),
+            "rejected" => Ok(ProposalStatus::Rejected),
+            _ => Err(format!("Invalid proposal status: {}", value)),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for ProposalStatus {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        <&str as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &<sqlx::Postgres as sqlx::Database>::TypeInfo) -> bool {
+        <&str as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
@@ -27,3 +27,12 @@ impl TryFrom<&String> for AuthProvider {
         }
     }
 }
+
+impl From<AuthProvider> for String {
+    fn from(value: AuthProvider) -> Self {
+        match value {
+            AuthProvider::Google => "google".to_string(),
+            AuthProvider::Github => "github".to_string(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::convert::From;
+
+/// `ScoringRule` is a value object that represents how the standings of a virtual contest are made.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Decode,
+    sqlx::Encode,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ScoringRule {
+    /// Ranked by the number of solved problems, then by the penalty time
+    /// (the elapsed time of each accepted submission plus 20 minutes for each wrong one before it).
+    Icpc,
+    /// Ranked by the sum of the points, then by the penalty time
+    /// (the elapsed time of the last accepted submission plus 5 minutes for each wrong one).
+    Atcoder,
+}
+
+impl From<ScoringRule> for String {
+    fn from(value: ScoringRule) -> Self {
+        match value {
+            ScoringRule::Icpc => "icpc".to_string(),
+            ScoringRule::Atcoder => "atcoder".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for ScoringRule {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "icpc" => Ok(ScoringRule::Icpc),
+            "atcoder" => Ok(ScoringRule::Atcoder),
+            _ => Err(format!("Invalid scoring rule: {}", value)),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for ScoringRule {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        <&str as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &<sqlx::Postgres as sqlx::Database>::TypeInfo) -> bool {
+        <&str as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
@@ -5,5 +5,15 @@ pub struct TechnicalTag {
     pub id: String,
     pub en_name: String,
     pub ja_name: String,
+    pub algorithm_id: String,
     pub algorithm_name: String,
 }
+
+/// The parent category of technical tags.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Algorithm {
+    pub id: String,
+    /// The name in English.
+    pub name: String,
+    pub ja_name: String,
+}
@@ -0,0 +1,62 @@
+use std::convert::From;
+
+/// `UserRole` is a value object that represents what an internal user is allowed to do.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Decode,
+    sqlx::Encode,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum UserRole {
+    Member,
+    /// Reviews the contributions of the community, such as tag proposals.
+    Moderator,
+    /// Operates the service. Admins can moderate as well.
+    Admin,
+}
+
+impl UserRole {
+    pub fn can_moderate(&self) -> bool {
+        matches!(self, UserRole::Moderator | UserRole::Admin)
+    }
+}
+
+impl From<UserRole> for String {
+    fn from(value: UserRole) -> Self {
+        match value {
+            UserRole::Member => "member".to_string(),
+            UserRole::Moderator => "moderator".to_string(),
+            UserRole::Admin => "admin".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for UserRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "member" => Ok(UserRole::Member),
+            "moderator" => Ok(UserRole::Moderator),
+            "admin" => Ok(UserRole::Admin),
+            _ => Err(format!("Invalid user role: {}", value)),
+        }
+    }
+}
+
+impl sqlx::Type<sqlx::Postgres> for UserRole {
+    fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
+        <&str as sqlx::Type<sqlx::Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &<sqlx::Postgres as sqlx::Database>::TypeInfo) -> bool {
+        <&str as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+    }
+}
@@ -0,0 +1,19 @@
+/// `Watermark` records how far an incremental crawl of a platform has progressed.
+///
+/// Items are ordered by `(seconds, id)`, and every item at or before the watermark
+/// has already been stored. The `id` breaks ties between items published in the same second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+pub struct Watermark {
+    /// The publication time of the last stored item in Unix time seconds.
+    pub seconds: i64,
+
+    /// The platform-specific identifier of the last stored item.
+    /// - Yukicoder: ProblemId (not the problem number)
+    pub id: i64,
+}
+
+impl Watermark {
+    pub fn new(seconds: i64, id: i64) -> Self {
+        Self { seconds, id }
+    }
+}
@@ -2,19 +2,21 @@ use super::external::{
     AojChallenges, AojChallengesAndRelatedContests, AojProblem, AojSubmission, AojVolume,
     AojVolumesChallengesList,
 };
+use crate::domain::calibration;
+use crate::domain::ingest::IngestError;
 use crate::domain::vo::platform::Platform;
 use crate::domain::vo::verdict::Verdict;
 use crate::domain::{contest::Contest, problem::Problem, submission::Submission};
 use crate::infra::api::api_client::ApiClient;
-use crate::utils::api::get_json;
-use anyhow::{Context, Ok, Result};
+use crate::utils::api::{exists, get_json};
+use anyhow::Result;
 use url::Url;
 
-const AOJ_URL: &'static str = "https://judgeapi.u-aizu.ac.jp";
-
 #[trait_variant::make]
 pub trait AojAPIClient: Send + Sync {
-    async fn get_aoj_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)>;
+    async fn get_aoj_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError>;
     async fn get_aoj_user_submissions(
         &self,
         user_id: &str,
@@ -22,6 +24,7 @@ pub trait AojAPIClient: Send + Sync {
         size: Option<u32>,
     ) -> Result<Vec<Submission>>;
     async fn get_aoj_recent_submissions(&self) -> Result<Vec<Submission>>;
+    async fn aoj_user_exists(&self, user_id: &str) -> Result<bool>;
 }
 
 impl ApiClient {
@@ -31,7 +34,10 @@ impl ApiClient {
         page: Option<u32>,
         size: Option<u32>,
     ) -> Result<Vec<AojSubmission>> {
-        let mut url = Url::parse(&format!("{AOJ_URL}/submission_records/users/{user_id}")).unwrap();
+        let mut url = Url::parse(&format!(
+            "{}/submission_records/users/{user_id}",
+            self.base_urls.aoj
+        ))?;
         {
             let mut query_pairs = url.query_pairs_mut();
             if let Some(page) = page {
@@ -48,17 +54,14 @@ impl ApiClient {
     }
 
     async fn fetch_aoj_recent_submissions(&self) -> Result<Vec<AojSubmission>> {
-        let url = format!("{AOJ_URL}/submission_records/recent");
-        let submissions: Vec<AojSubmission> = get_json(&url, &self.client)
-            .await
-            .with_context(|| format!(""))
-            .unwrap();
+        let url = format!("{}/submission_records/recent", self.base_urls.aoj);
+        let submissions: Vec<AojSubmission> = get_json(&url, &self.client).await?;
 
         Ok(submissions)
     }
 
-    async fn fetch_aoj_volumes_challenges_list(&self) -> Result<Vec<u16>> {
-        let url = format!("{AOJ_URL}/problems/filters");
+    async fn fetch_aoj_volumes_challenges_list(&self) -> Result<Vec<u16>, IngestError> {
+        let url = format!("{}/problems/filters", self.base_urls.aoj);
         let list: AojVolumesChallengesList = get_json(&url, &self.client).await?;
 
         let (volume_ids, _) = (list.volumes, list.large_cls);
@@ -66,8 +69,8 @@ impl ApiClient {
         Ok(volume_ids)
     }
 
-    async fn fetch_aoj_large_cls_middle_cls(&self) -> Result<Vec<(String, String)>> {
-        let url = format!("{AOJ_URL}/challenges");
+    async fn fetch_aoj_large_cls_middle_cls(&self) -> Result<Vec<(String, String)>, IngestError> {
+        let url = format!("{}/challenges", self.base_urls.aoj);
         let challenges: AojChallenges = get_json(&url, &self.client).await?;
 
         let pairs: Vec<(String, String)> = challenges
@@ -75,9 +78,8 @@ impl ApiClient {
             .iter()
             .flat_map(|l| {
                 l.middle_cls
-                    .as_ref()
-                    .unwrap()
                     .iter()
+                    .flatten()
                     .map(move |m| (l.id.clone(), m.id.clone()))
             })
             .collect();
@@ -85,8 +87,11 @@ impl ApiClient {
         Ok(pairs)
     }
 
-    async fn fetch_aoj_problems_by_volume_id(&self, volume_id: u16) -> Result<Vec<AojProblem>> {
-        let url = format!("{AOJ_URL}/problems/volumes/{volume_id}");
+    async fn fetch_aoj_problems_by_volume_id(
+        &self,
+        volume_id: u16,
+    ) -> Result<Vec<AojProblem>, IngestError> {
+        let url = format!("{}/problems/volumes/{volume_id}", self.base_urls.aoj);
         let volume: AojVolume = get_json(&url, &self.client).await?;
         let problems = volume.problems;
 
@@ -97,8 +102,11 @@ impl ApiClient {
         &self,
         large_cl: &str,
         middle_cl: &str,
-    ) -> Result<Vec<(u16, String, Vec<AojProblem>)>> {
-        let url = format!("{AOJ_URL}/challenges/cl/{large_cl}/{middle_cl}");
+    ) -> Result<Vec<(u16, String, Vec<AojProblem>)>, IngestError> {
+        let url = format!(
+            "{}/challenges/cl/{large_cl}/{middle_cl}",
+            self.base_urls.aoj
+        );
         let chanllenges: AojChallengesAndRelatedContests = get_json(&url, &self.client).await?;
 
         // 年度と問題のペアを作成
@@ -123,14 +131,28 @@ impl ApiClient {
         Ok(pair)
     }
 
-    async fn build_aoj_problems_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
+    /// Fetch the problems by volume and by challenge, and build them with their contests.
+    ///
+    /// A volume or a challenge whose list cannot be decoded is skipped,
+    /// but a network failure aborts the whole build.
+    async fn build_aoj_problems_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
         let mut all_problems: Vec<Problem> = vec![];
         let mut all_contests: Vec<Contest> = vec![];
+        let mut skipped: Vec<IngestError> = vec![];
 
         // volume 内の問題を取得
         let volume_ids: Vec<u16> = self.fetch_aoj_volumes_challenges_list().await?;
         for vol_id in volume_ids {
-            let raw_problems_in_vol = self.fetch_aoj_problems_by_volume_id(vol_id).await?;
+            let raw_problems_in_vol = match self.fetch_aoj_problems_by_volume_id(vol_id).await {
+                Ok(raw_problems) => raw_problems,
+                Err(e @ IngestError::Decode { .. }) => {
+                    skipped.push(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
 
             let problems_in_vol = raw_problems_in_vol
                 .iter()
@@ -145,9 +167,17 @@ impl ApiClient {
         // large_cl, middle_cl から問題を取得
         let pairs = self.fetch_aoj_large_cls_middle_cls().await?;
         for pair in pairs {
-            let raw_year_problems = self
+            let raw_year_problems = match self
                 .fetch_aoj_challenges_by_large_cl_middle_cl(&pair.0, &pair.1)
-                .await?;
+                .await
+            {
+                Ok(raw_year_problems) => raw_year_problems,
+                Err(e @ IngestError::Decode { .. }) => {
+                    skipped.push(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
 
             for (idx, (year, title, problems)) in raw_year_problems.iter().enumerate() {
                 let problems = problems
@@ -162,15 +192,17 @@ impl ApiClient {
             }
         }
 
-        Ok((all_problems, all_contests))
+        Ok((all_problems, all_contests, skipped))
     }
 }
 
 impl AojAPIClient for ApiClient {
-    async fn get_aoj_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (problems, contests) = self.build_aoj_problems_contests().await?;
+    async fn get_aoj_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        let (problems, contests, skipped) = self.build_aoj_problems_contests().await?;
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 
     async fn get_aoj_user_submissions(
@@ -197,6 +229,13 @@ impl AojAPIClient for ApiClient {
 
         Ok(submissions)
     }
+
+    async fn aoj_user_exists(&self, user_id: &str) -> Result<bool> {
+        let url = format!("{}/users/{user_id}", self.base_urls.aoj);
+        let exists = exists(&url, &self.client).await?;
+
+        Ok(exists)
+    }
 }
 
 fn map_status_to_verdict(status: u16) -> Verdict {
@@ -243,7 +282,7 @@ fn build_problem_from_vol(vol_id: u16, p: &AojProblem) -> Problem {
         &p.id,
         &p.name,
         None,
-        None,
+        calibration::from_aoj_statistics(Some(p.solved_user), Some(p.submissions)),
         format!("Volume {}", vol_id),
         None,
         vec![],
@@ -270,7 +309,7 @@ fn build_problen_from_cl(
         &p.id,
         &p.name,
         None,
-        None,
+        calibration::from_aoj_statistics(Some(p.solved_user), Some(p.submissions)),
         String::from(large_cl),
         None,
         vec![],
@@ -1,14 +1,71 @@
 use std::sync::Arc;
+use tokio::time::Duration;
+
+use crate::{
+    config::HttpConfigs,
+    infra::api::cf::signature::CodeforcesCredentials,
+    utils::{
+        http::{HttpClient, HttpConfig},
+        rate_limit::RateLimiter,
+    },
+};
+
+/// Codeforces API allows one request per two seconds.
+const CODEFORCES_REQUEST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The endpoints of the upstream servers, without a trailing slash.
+///
+/// They are replaced with a local server in the tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseUrls {
+    /// The static files of AtCoder Problems, such as `contests.json`.
+    pub atcoder_resources: String,
+    /// The API of AtCoder Problems, which serves the submissions.
+    pub atcoder_statistics: String,
+    pub atcoder: String,
+    pub codeforces: String,
+    pub yukicoder: String,
+    pub aoj: String,
+    /// The URL of `categories.toml` itself, since Library Checker has no API.
+    pub yoj_categories: String,
+}
+
+impl Default for BaseUrls {
+    fn default() -> Self {
+        Self {
+            atcoder_resources: "https://kenkoooo.com/atcoder/resources".to_string(),
+            atcoder_statistics: "https://kenkoooo.com/atcoder/atcoder-api/v3".to_string(),
+            atcoder: "https://atcoder.jp".to_string(),
+            codeforces: "https://codeforces.com/api".to_string(),
+            yukicoder: "https://yukicoder.me/api/v1".to_string(),
+            aoj: "https://judgeapi.u-aizu.ac.jp".to_string(),
+            yoj_categories: "https://raw.githubusercontent.com/yosupo06/library-checker-problems/master/categories.toml".to_string(),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct ApiClient {
-    pub client: Arc<reqwest::Client>,
+    pub client: Arc<HttpClient>,
+    /// Shared by all the clones, so that the limit holds for the whole process.
+    pub cf_limiter: Arc<RateLimiter>,
+    /// The requests to Codeforces are signed if the credentials are given.
+    pub cf_credentials: Option<CodeforcesCredentials>,
+    pub base_urls: BaseUrls,
 }
 
 impl ApiClient {
-    pub fn new() -> Self {
+    pub fn new(http_configs: &HttpConfigs) -> Self {
         Self {
-            client: Arc::new(reqwest::Client::new()),
+            client: Arc::new(HttpClient::new(HttpConfig::from(http_configs))),
+            cf_limiter: Arc::new(RateLimiter::new(1, CODEFORCES_REQUEST_INTERVAL)),
+            cf_credentials: CodeforcesCredentials::from_env(),
+            base_urls: BaseUrls::default(),
         }
     }
+
+    pub fn with_base_urls(mut self, base_urls: BaseUrls) -> Self {
+        self.base_urls = base_urls;
+        self
+    }
 }
@@ -1,10 +1,15 @@
-use anyhow::{Ok, Result};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
 use crate::{
     domain::{
+        calibration::{self, NormalizedDifficulty},
         contest::Contest,
+        contest_result::ContestResult,
+        ingest::IngestError,
         problem::Problem,
+        rating_history::RatingChange,
         submission::Submission,
         vo::{
             category::{AtcoderCategory, ContestCategory},
@@ -13,47 +18,59 @@ use crate::{
             verdict::Verdict,
         },
     },
-    utils::api::get_json,
+    utils::api::{exists, get_json, get_json_if_modified},
 };
 
 use super::{
     classifier::classify_contest,
-    external::{AtcoderContest, AtcoderProblem, AtcoderSubmission, Estimation},
+    external::{
+        AtcoderContest, AtcoderContestResult, AtcoderProblem, AtcoderRatingChange,
+        AtcoderSubmission, Estimation,
+    },
 };
 use crate::infra::api::api_client::ApiClient;
 
-const ATCODER_INFORMATION_URL: &'static str = "https://kenkoooo.com/atcoder/resources";
-const ATCODER_STATISTICS_URL: &'static str = "https://kenkoooo.com/atcoder/atcoder-api/v3";
-
 #[trait_variant::make]
 pub trait AtcoderAPIClient: Send + Sync {
-    async fn get_atcoder_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)>;
+    async fn get_atcoder_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError>;
     async fn get_atcoder_recent_submissions(&self) -> Result<Vec<Submission>>;
     async fn get_atcoder_user_submissions(
         &self,
         user: &str,
         from_second: Option<u64>,
     ) -> Result<Vec<Submission>>;
+    async fn atcoder_user_exists(&self, user: &str) -> Result<bool>;
+    async fn get_atcoder_contest_results(
+        &self,
+        raw_contest_id: &str,
+    ) -> Result<Vec<ContestResult>, IngestError>;
+    async fn get_atcoder_rating_history(
+        &self,
+        user: &str,
+    ) -> Result<Vec<RatingChange>, IngestError>;
 }
 
 impl ApiClient {
-    async fn fetch_atcoder_contests(&self) -> Result<Vec<AtcoderContest>> {
-        let url = format!("{ATCODER_INFORMATION_URL}/contests.json");
-        let contests = get_json::<Vec<AtcoderContest>>(&url, &self.client).await?;
+    async fn fetch_atcoder_contests(&self) -> Result<Vec<AtcoderContest>, IngestError> {
+        let url = format!("{}/contests.json", self.base_urls.atcoder_resources);
+        let contests = get_json_if_modified::<Vec<AtcoderContest>>(&url, &self.client).await?;
 
         Ok(contests)
     }
 
-    async fn fetch_atcoder_problems(&self) -> Result<Vec<AtcoderProblem>> {
-        let url = format!("{ATCODER_INFORMATION_URL}/merged-problems.json");
-        let problems = get_json::<Vec<AtcoderProblem>>(&url, &self.client).await?;
+    async fn fetch_atcoder_problems(&self) -> Result<Vec<AtcoderProblem>, IngestError> {
+        let url = format!("{}/merged-problems.json", self.base_urls.atcoder_resources);
+        let problems = get_json_if_modified::<Vec<AtcoderProblem>>(&url, &self.client).await?;
 
         Ok(problems)
     }
 
-    async fn fetch_atcoder_estimations(&self) -> Result<HashMap<String, Estimation>> {
-        let url = format!("{ATCODER_INFORMATION_URL}/problem-models.json");
-        let estimations = get_json::<HashMap<String, Estimation>>(&url, &self.client).await?;
+    async fn fetch_atcoder_estimations(&self) -> Result<HashMap<String, Estimation>, IngestError> {
+        let url = format!("{}/problem-models.json", self.base_urls.atcoder_resources);
+        let estimations =
+            get_json_if_modified::<HashMap<String, Estimation>>(&url, &self.client).await?;
 
         Ok(estimations)
     }
@@ -61,7 +78,7 @@ impl ApiClient {
     /// Fetch the most recent submissions from the AtCoder API.
     /// Retrieves up to 1,000 of the latest submissions.
     async fn fetch_atcoder_recent_submissions(&self) -> Result<Vec<AtcoderSubmission>> {
-        let url = format!("{ATCODER_STATISTICS_URL}/recent");
+        let url = format!("{}/recent", self.base_urls.atcoder_statistics);
         let submissions = get_json::<Vec<AtcoderSubmission>>(&url, &self.client).await?;
 
         Ok(submissions)
@@ -73,7 +90,8 @@ impl ApiClient {
         from_second: Option<u64>,
     ) -> Result<Vec<AtcoderSubmission>> {
         let url = format!(
-            "{ATCODER_STATISTICS_URL}/user/submissions?user={user}&from_second={}",
+            "{}/user/submissions?user={user}&from_second={}",
+            self.base_urls.atcoder_statistics,
             from_second.unwrap_or(0)
         );
         let submissions = get_json::<Vec<AtcoderSubmission>>(&url, &self.client).await?;
@@ -81,7 +99,9 @@ impl ApiClient {
         Ok(submissions)
     }
 
-    async fn build_atcoder_problems_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
+    async fn build_atcoder_problems_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
         let raw_problems = self.fetch_atcoder_problems().await?;
         let raw_contests = self.fetch_atcoder_contests().await?;
         let estimations = self.fetch_atcoder_estimations().await?;
@@ -93,10 +113,23 @@ impl ApiClient {
 
         let mut c_to_p_map: HashMap<String, Vec<Problem>> = HashMap::new();
         let mut problems: Vec<Problem> = vec![];
+        let mut skipped: Vec<IngestError> = vec![];
         raw_problems.iter().for_each(|p| {
+            let Some(raw_contest) = c_id_c_map.get(&p.contest_id) else {
+                skipped.push(IngestError::OrphanProblem {
+                    problem_id: p.id.clone(),
+                    contest_id: Some(p.contest_id.clone()),
+                });
+                return;
+            };
+
             let (diff, is_experimental) = clip_difficulty(estimations.get(&p.id));
-            let raw_contest = c_id_c_map.get(&p.contest_id).unwrap();
-            let problem = build_problem(p, raw_contest, diff, is_experimental);
+            let problem = build_problem(
+                p,
+                raw_contest,
+                calibration::from_atcoder(diff, is_experimental),
+                is_experimental,
+            );
             c_to_p_map
                 .entry(p.contest_id.clone())
                 .or_insert_with(Vec::new)
@@ -110,15 +143,17 @@ impl ApiClient {
             contests.push(contest);
         });
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 }
 
 impl AtcoderAPIClient for ApiClient {
-    async fn get_atcoder_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (problems, contests) = self.build_atcoder_problems_contests().await?;
+    async fn get_atcoder_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        let (problems, contests, skipped) = self.build_atcoder_problems_contests().await?;
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 
     async fn get_atcoder_recent_submissions(&self) -> Result<Vec<Submission>> {
@@ -146,22 +181,86 @@ impl AtcoderAPIClient for ApiClient {
 
         Ok(submissions)
     }
+
+    /// AtCoder Problems (kenkoooo) does not provide user information,
+    /// so we check the profile page on AtCoder.
+    async fn atcoder_user_exists(&self, user: &str) -> Result<bool> {
+        let url = format!("{}/users/{user}", self.base_urls.atcoder);
+        let exists = exists(&url, &self.client).await?;
+
+        Ok(exists)
+    }
+
+    /// Participants without a rating (i.e., in their first rated contest) are excluded,
+    /// while the unrated participants above the rating limit of the contest are included.
+    async fn get_atcoder_contest_results(
+        &self,
+        raw_contest_id: &str,
+    ) -> Result<Vec<ContestResult>, IngestError> {
+        let url = format!(
+            "{}/contests/{raw_contest_id}/results/json",
+            self.base_urls.atcoder
+        );
+        let results = get_json::<Vec<AtcoderContestResult>>(&url, &self.client).await?;
+
+        Ok(results
+            .iter()
+            .filter(|r| r.competitions > 0 && r.old_rating > 0)
+            .map(|r| {
+                ContestResult::reconstruct(
+                    Platform::Atcoder,
+                    raw_contest_id,
+                    &r.user_screen_name,
+                    r.old_rating,
+                )
+            })
+            .collect())
+    }
+
+    /// Only the rated participations are returned, oldest first.
+    async fn get_atcoder_rating_history(
+        &self,
+        user: &str,
+    ) -> Result<Vec<RatingChange>, IngestError> {
+        let url = format!("{}/users/{user}/history/json", self.base_urls.atcoder);
+        let history = get_json::<Vec<AtcoderRatingChange>>(&url, &self.client).await?;
+
+        history
+            .iter()
+            .filter(|h| h.is_rated)
+            .map(|h| build_rating_change(user, h))
+            .collect()
+    }
+}
+
+fn build_rating_change(user: &str, h: &AtcoderRatingChange) -> Result<RatingChange, IngestError> {
+    let raw_contest_id = h.contest_screen_name.split('.').next().unwrap_or_default();
+    let rated_at = DateTime::parse_from_rfc3339(&h.end_time)
+        .map_err(|e| IngestError::Decode {
+            target: format!("the end time of {raw_contest_id}"),
+            message: e.to_string(),
+        })?
+        .timestamp();
+
+    Ok(RatingChange::reconstruct(
+        Platform::Atcoder,
+        raw_contest_id,
+        user,
+        &h.contest_name,
+        h.place,
+        h.old_rating,
+        h.new_rating,
+        Some(h.performance),
+        rated_at,
+    ))
 }
 
 fn clip_difficulty(estimation: Option<&Estimation>) -> (Option<f64>, Option<bool>) {
     match estimation {
-        Some(estimation) => {
-            if let Some(difficulty) = estimation.difficulty {
-                let clipped_diff = if difficulty >= 400.0 {
-                    difficulty.round()
-                } else {
-                    (400.0 / f64::exp(1.0 - difficulty / 400.0)).round()
-                };
-                (Some(clipped_diff), estimation.is_experimental)
-            } else {
-                (None, estimation.is_experimental)
-            }
-        }
+        Some(estimation) => (
+            estimation.difficulty.map(calibration::clip),
+            estimation.is_experimental,
+        ),
         None => (None, None),
     }
 }
@@ -169,7 +268,7 @@ fn clip_difficulty(estimation: Option<&Estimation>) -> (Option<f64>, Option<bool
 fn build_problem(
     p: &AtcoderProblem,
     c: &AtcoderContest,
-    difficulty: Option<f64>,
+    difficulty: Option<NormalizedDifficulty>,
     is_experimental: Option<bool>,
 ) -> Problem {
     let category = classify_contest(&c);
@@ -207,7 +306,11 @@ fn build_contest(c: &AtcoderContest, ps: &Vec<Problem>) -> Contest {
         c.title.to_string(),
         String::from(classify_contest(&c)),
         platform::Platform::Atcoder,
-        String::from(Phase::Finished),
+        String::from(Phase::from_schedule(
+            c.start_epoch_second,
+            c.duration_second,
+            Utc::now().timestamp(),
+        )),
         Some(c.start_epoch_second),
         Some(c.duration_second),
         ps.clone(),
@@ -121,3 +121,48 @@ pub struct AtcoderSubmission {
 
     pub execution_time: Option<u64>, // ms
 }
+
+/// `AtcoderContestResult` is a struct that contains the result of a participant in a contest.
+///
+/// For the raw data, see:
+/// [AtCoder Contest Results JSON](https://atcoder.jp/contests/abc300/results/json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtcoderContestResult {
+    pub is_rated: bool,
+
+    pub place: u64,
+
+    pub old_rating: i32,
+
+    pub new_rating: i32,
+
+    pub competitions: u64,
+
+    pub user_screen_name: String,
+}
+
+/// `AtcoderRatingChange` is a struct that contains a rated participation of a user.
+///
+/// For the raw data, see:
+/// [AtCoder User History JSON](https://atcoder.jp/users/chokudai/history/json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AtcoderRatingChange {
+    pub is_rated: bool,
+
+    pub place: i32,
+
+    pub old_rating: i32,
+
+    pub new_rating: i32,
+
+    pub performance: i32,
+
+    /// The host of the contest, such as `abc300.contest.atcoder.jp`.
+    pub contest_screen_name: String,
+
+    pub contest_name: String,
+
+    pub end_time: String,
+}
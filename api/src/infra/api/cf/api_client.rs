@@ -1,31 +1,38 @@
-use anyhow::{Ok, Result};
+use anyhow::Result;
+use chrono::Utc;
 use std::collections::HashMap;
-use url::Url;
 
 use crate::{
     domain::{
+        calibration,
         contest::Contest,
+        contest_result::ContestResult,
+        ingest::IngestError,
         problem::Problem,
+        rating_history::RatingChange,
         submission::Submission,
-        vo::{platform::Platform, verdict::Verdict},
+        vo::{phase::Phase, platform::Platform, verdict::Verdict},
     },
     infra::api::api_client::ApiClient,
-    utils::api::get_json,
+    utils::api::get_json_with_error_body,
 };
 
 use super::{
     classifier::classify_contest,
     external::{
-        CodeforcesContest, CodeforcesContestResponse, CodeforcesProblem, CodeforcesProblemResponse,
-        CodeforcesProblemStat, CodeforcesSubmission, CodeforcesSubmissionResponse,
+        CodeforcesAPIResponse, CodeforcesContest, CodeforcesProblem, CodeforcesProblemResult,
+        CodeforcesProblemStat, CodeforcesRatingChange, CodeforcesSubmission,
+        CodeforcesUserRatingChange, Status,
     },
 };
 
-const CODEFORCES_URL_PREFIX: &'static str = "https://codeforces.com/api";
-
 #[trait_variant::make]
 pub trait CFAPIClient: Send + Sync {
-    async fn get_cf_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)>;
+    async fn get_cf_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError>;
+    /// Fetch the contests which have not finished yet, without their problems.
+    async fn get_cf_upcoming_contests(&self) -> Result<Vec<Contest>, IngestError>;
     async fn get_cf_user_submissions(
         &self,
         user_id: &str,
@@ -33,15 +40,60 @@ pub trait CFAPIClient: Send + Sync {
         size: Option<u32>,
     ) -> Result<Vec<Submission>>;
     async fn get_cf_recent_submissions(&self) -> Result<Vec<Submission>>;
+    async fn cf_user_exists(&self, user_id: &str) -> Result<bool>;
+    async fn get_cf_contest_results(
+        &self,
+        raw_contest_id: &str,
+    ) -> Result<Vec<ContestResult>, IngestError>;
+    async fn get_cf_rating_history(&self, user_id: &str) -> Result<Vec<RatingChange>, IngestError>;
 }
 
 impl ApiClient {
+    /// Call a method of Codeforces API and extract the result.
+    ///
+    /// Every call waits for the shared rate limiter, and is signed if the credentials are given.
+    async fn call_cf<T>(&self, method: &str, params: &[(&str, String)]) -> Result<T, IngestError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut params: Vec<(String, String)> = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        // Errors refer to the unsigned URL, so that the key is not logged.
+        let base_url = &self.base_urls.codeforces;
+        let url = build_url(base_url, method, &params);
+        if let Some(credentials) = &self.cf_credentials {
+            credentials.sign(method, &mut params, Utc::now().timestamp());
+        }
+
+        self.cf_limiter.acquire().await;
+        let response = get_json_with_error_body::<CodeforcesAPIResponse<T>>(
+            &build_url(base_url, method, &params),
+            &self.client,
+        )
+        .await
+        .map_err(|e| match e {
+            IngestError::Network { message, .. } => IngestError::Network {
+                url: url.clone(),
+                message,
+            },
+            IngestError::Decode { message, .. } => IngestError::Decode {
+                target: url.clone(),
+                message,
+            },
+            e => e,
+        })?;
+
+        extract_result(response, &url)
+    }
+
     async fn fetch_cf_problems(
         &self,
-    ) -> Result<(Vec<CodeforcesProblem>, Vec<CodeforcesProblemStat>)> {
-        let url = format!("{CODEFORCES_URL_PREFIX}/problemset.problems");
-        let result = get_json::<CodeforcesProblemResponse>(&url, &self.client).await?;
-        let problems_with_stats = result.result.unwrap();
+    ) -> Result<(Vec<CodeforcesProblem>, Vec<CodeforcesProblemStat>), IngestError> {
+        let problems_with_stats = self
+            .call_cf::<CodeforcesProblemResult>("problemset.problems", &[])
+            .await?;
 
         Ok((
             problems_with_stats.problems,
@@ -49,12 +101,10 @@ impl ApiClient {
         ))
     }
 
-    async fn fetch_cf_past_contests(&self) -> Result<Vec<CodeforcesContest>> {
-        let url = format!("{CODEFORCES_URL_PREFIX}/contest.list");
-        let result = get_json::<CodeforcesContestResponse>(&url, &self.client).await?;
-        let contests = result
-            .result
-            .unwrap()
+    async fn fetch_cf_past_contests(&self) -> Result<Vec<CodeforcesContest>, IngestError> {
+        let contests = self
+            .call_cf::<Vec<CodeforcesContest>>("contest.list", &[])
+            .await?
             .into_iter()
             .filter(|c| c.phase == "FINISHED")
             .collect::<Vec<CodeforcesContest>>();
@@ -62,24 +112,24 @@ impl ApiClient {
         Ok(contests)
     }
 
-    #[allow(dead_code)]
-    async fn fetch_cf_future_contests(&self) -> Result<Vec<CodeforcesContest>> {
-        let url = format!("{CODEFORCES_URL_PREFIX}/contest.list");
-        let result = get_json::<CodeforcesContestResponse>(&url, &self.client).await?;
-        let contests = result
-            .result
-            .unwrap()
+    async fn fetch_cf_future_contests(&self) -> Result<Vec<CodeforcesContest>, IngestError> {
+        let contests = self
+            .call_cf::<Vec<CodeforcesContest>>("contest.list", &[])
+            .await?
             .into_iter()
-            .filter(|c| c.phase == "BEFORE")
+            .filter(|c| c.phase != "FINISHED")
             .collect::<Vec<CodeforcesContest>>();
 
         Ok(contests)
     }
 
     async fn fetch_cf_recent_submissions(&self) -> Result<Vec<CodeforcesSubmission>> {
-        let url = format!("{CODEFORCES_URL_PREFIX}/problemset.recentStatus?count=100");
-        let result = get_json::<CodeforcesSubmissionResponse>(&url, &self.client).await?;
-        let submissions = result.result.unwrap();
+        let submissions = self
+            .call_cf::<Vec<CodeforcesSubmission>>(
+                "problemset.recentStatus",
+                &[("count", "100".to_string())],
+            )
+            .await?;
 
         Ok(submissions)
     }
@@ -90,29 +140,28 @@ impl ApiClient {
         page: Option<u32>,
         size: Option<u32>,
     ) -> Result<Vec<CodeforcesSubmission>> {
-        let mut url = Url::parse(&format!(
-            "{CODEFORCES_URL_PREFIX}/user.status?handle={user_id}"
-        ))?;
-        {
-            let mut query_pairs = url.query_pairs_mut();
-            query_pairs.append_pair("handle", user_id);
-
-            if let Some(page) = page {
-                query_pairs
-                    .append_pair("from", &((page - 1) * size.unwrap_or(100) + 1).to_string());
-            }
-            if let Some(size) = size {
-                query_pairs.append_pair("count", &size.to_string());
-            }
+        let mut params = vec![("handle", user_id.to_string())];
+        if let Some(page) = page {
+            // Pages are 1-based, and page 0 is regarded as the first page.
+            params.push((
+                "from",
+                (page.saturating_sub(1) * size.unwrap_or(100) + 1).to_string(),
+            ));
+        }
+        if let Some(size) = size {
+            params.push(("count", size.to_string()));
         }
 
-        let result = get_json::<CodeforcesSubmissionResponse>(&url.as_str(), &self.client).await?;
-        let submissions = result.result.unwrap();
+        let submissions = self
+            .call_cf::<Vec<CodeforcesSubmission>>("user.status", &params)
+            .await?;
 
         Ok(submissions)
     }
 
-    async fn build_cf_problems_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
+    async fn build_cf_problems_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
         let (raw_problems, raw_stats) = self.fetch_cf_problems().await?;
         let raw_contests = self.fetch_cf_past_contests().await?;
 
@@ -129,8 +178,16 @@ impl ApiClient {
         let mut c_to_p_map: HashMap<u64, Vec<Problem>> = HashMap::new();
 
         let mut problems: Vec<Problem> = vec![];
+        let mut skipped: Vec<IngestError> = vec![];
         raw_problems.iter().for_each(|p| {
-            let raw_contest = c_id_c_map.get(&p.contest_id).unwrap();
+            // Problems of running contests are listed before the contests finish.
+            let Some(raw_contest) = c_id_c_map.get(&p.contest_id) else {
+                skipped.push(IngestError::OrphanProblem {
+                    problem_id: format!("{}{}", p.contest_id, p.index),
+                    contest_id: Some(p.contest_id.to_string()),
+                });
+                return;
+            };
 
             let problem = build_problem(p, raw_contest, id_to_solved_count.clone());
             c_to_p_map
@@ -146,15 +203,26 @@ impl ApiClient {
             contests.push(build_contest(c.clone(), ps));
         });
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 }
 
 impl CFAPIClient for ApiClient {
-    async fn get_cf_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (problems, contests) = self.build_cf_problems_contests().await?;
+    async fn get_cf_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        let (problems, contests, skipped) = self.build_cf_problems_contests().await?;
+
+        Ok((problems, contests, skipped))
+    }
 
-        Ok((problems, contests))
+    async fn get_cf_upcoming_contests(&self) -> Result<Vec<Contest>, IngestError> {
+        let raw_contests = self.fetch_cf_future_contests().await?;
+
+        Ok(raw_contests
+            .into_iter()
+            .map(|contest| build_contest(contest, vec![]))
+            .collect())
     }
 
     async fn get_cf_user_submissions(
@@ -181,6 +249,117 @@ impl CFAPIClient for ApiClient {
 
         Ok(submissions)
     }
+
+    /// Codeforces API rejects the request if the handle does not exist.
+    async fn cf_user_exists(&self, user_id: &str) -> Result<bool> {
+        match self
+            .call_cf::<serde_json::Value>("user.info", &[("handles", user_id.to_string())])
+            .await
+        {
+            Ok(_) => Ok(true),
+            // e.g., "handles: User with handle xxx not found"
+            Err(IngestError::Rejected { comment, .. }) if comment.contains("not found") => {
+                Ok(false)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Codeforces API rejects the request for unrated contests, which have no participants here.
+    /// Participants in their first rated contest are excluded, as they have no rating yet.
+    async fn get_cf_contest_results(
+        &self,
+        raw_contest_id: &str,
+    ) -> Result<Vec<ContestResult>, IngestError> {
+        let changes = match self
+            .call_cf::<Vec<CodeforcesRatingChange>>(
+                "contest.ratingChanges",
+                &[("contestId", raw_contest_id.to_string())],
+            )
+            .await
+        {
+            Ok(changes) => changes,
+            Err(IngestError::Rejected { .. }) => vec![],
+            Err(e) => return Err(e),
+        };
+
+        Ok(changes
+            .iter()
+            .filter(|r| r.old_rating > 0)
+            .map(|r| {
+                ContestResult::reconstruct(
+                    Platform::Codeforces,
+                    &r.contest_id.to_string(),
+                    &r.handle,
+                    r.old_rating,
+                )
+            })
+            .collect())
+    }
+
+    /// Codeforces API returns the rated participations, oldest first.
+    async fn get_cf_rating_history(&self, user_id: &str) -> Result<Vec<RatingChange>, IngestError> {
+        let changes = self
+            .call_cf::<Vec<CodeforcesUserRatingChange>>(
+                "user.rating",
+                &[("handle", user_id.to_string())],
+            )
+            .await?;
+
+        Ok(changes
+            .iter()
+            .map(|r| {
+                RatingChange::reconstruct(
+                    Platform::Codeforces,
+                    &r.contest_id.to_string(),
+                    &r.handle,
+                    &r.contest_name,
+                    r.rank,
+                    r.old_rating,
+                    r.new_rating,
+                    None,
+                    r.rating_update_time_seconds,
+                )
+            })
+            .collect())
+    }
+}
+
+fn build_url(base_url: &str, method: &str, params: &[(String, String)]) -> String {
+    if params.is_empty() {
+        return format!("{base_url}/{method}");
+    }
+
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish();
+
+    format!("{base_url}/{method}?{query}")
+}
+
+/// Extract the result from the Codeforces API response.
+///
+/// Codeforces API returns `FAILED` with a comment when the request fails (e.g., the handle does not exist).
+fn extract_result<T>(response: CodeforcesAPIResponse<T>, url: &str) -> Result<T, IngestError> {
+    match response.status {
+        Status::OK => response.result.ok_or_else(|| IngestError::Decode {
+            target: url.to_string(),
+            message: "Codeforces API returned no result".to_string(),
+        }),
+        Status::FAILED => {
+            let comment = response.comment.unwrap_or_default();
+            if comment.starts_with("Call limit exceeded") {
+                Err(IngestError::RateLimited {
+                    url: url.to_string(),
+                })
+            } else {
+                Err(IngestError::Rejected {
+                    url: url.to_string(),
+                    comment,
+                })
+            }
+        }
+    }
 }
 
 fn build_problem(
@@ -195,7 +374,7 @@ fn build_problem(
         &problem.index,
         &problem.name,
         problem.points,
-        problem.rating,
+        calibration::from_cf_rating(problem.rating),
         String::from(classify_contest(&contest)),
         Some(false),
         problem
@@ -214,18 +393,28 @@ fn build_problem(
     )
 }
 
+/// Convert the phase of Codeforces into ours.
+///
+/// Contests under the system test are regarded as running, since their results are not fixed yet.
+fn convert_phase(raw_phase: &str) -> Phase {
+    match raw_phase {
+        "BEFORE" => Phase::Before,
+        "CODING" | "PENDING_SYSTEM_TEST" | "SYSTEM_TEST" => Phase::Coding,
+        "FINISHED" => Phase::Finished,
+        _ => Phase::Unknown,
+    }
+}
+
 fn build_contest(contest: CodeforcesContest, problems: Vec<Problem>) -> Contest {
     Contest::reconstruct(
         contest.id.to_string(),
         contest.name.to_string(),
         String::from(classify_contest(&contest)),
         Platform::Codeforces,
-        String::from(contest.phase),
-        if contest.start_time_seconds.is_some() {
-            Some(contest.start_time_seconds.unwrap() as i64)
-        } else {
-            None
-        },
+        String::from(convert_phase(&contest.phase)),
+        contest
+            .start_time_seconds
+            .map(|start_time| start_time as i64),
         Some(contest.duration_seconds),
         problems,
     )
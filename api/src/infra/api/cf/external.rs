@@ -12,6 +12,8 @@ pub(super) enum Status {
 #[serde(rename_all = "camelCase")]
 pub(super) struct CodeforcesAPIResponse<T> {
     pub status: Status,
+    /// The reason of the failure, given only when `status` is `FAILED`.
+    pub comment: Option<String>,
     pub result: Option<T>,
 }
 
@@ -60,8 +62,6 @@ pub(super) struct CodeforcesProblemResult {
     pub problem_statistics: Vec<CodeforcesProblemStat>,
 }
 
-pub(super) type CodeforcesProblemResponse = CodeforcesAPIResponse<CodeforcesProblemResult>;
-
 // Codeforces Contest
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -102,8 +102,6 @@ pub(super) struct CodeforcesContest {
     pub season: Option<String>,
 }
 
-pub(super) type CodeforcesContestResponse = CodeforcesAPIResponse<Vec<CodeforcesContest>>;
-
 // Codeforces Submission
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -135,8 +133,6 @@ pub(super) struct CodeforcesSubmission {
     pub points: Option<f64>,
 }
 
-pub(super) type CodeforcesSubmissionResponse = CodeforcesAPIResponse<Vec<CodeforcesSubmission>>;
-
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(super) struct CodeforcesParty {
@@ -164,3 +160,37 @@ pub(super) struct CodeforcesMember {
 
     pub name: Option<String>,
 }
+
+// Codeforces Rating Change
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CodeforcesRatingChange {
+    pub contest_id: u64,
+
+    pub handle: String,
+
+    pub rank: u64,
+
+    pub old_rating: i32,
+
+    pub new_rating: i32,
+}
+
+// Codeforces User Rating
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CodeforcesUserRatingChange {
+    pub contest_id: u64,
+
+    pub contest_name: String,
+
+    pub handle: String,
+
+    pub rank: i32,
+
+    pub rating_update_time_seconds: i64,
+
+    pub old_rating: i32,
+
+    pub new_rating: i32,
+}
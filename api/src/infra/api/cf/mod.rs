@@ -1,3 +1,4 @@
 pub mod api_client;
+pub mod classifier;
 pub mod external;
-pub mod classifier;
\ No newline at end of file
+pub mod signature;
@@ -1,6 +1,7 @@
 use crate::{
     domain::{
         contest::Contest,
+        ingest::IngestError,
         problem::Problem,
         vo::{phase::Phase, platform::Platform},
     },
@@ -12,28 +13,41 @@ use convert_case::{Case, Casing};
 
 use super::classifier::classify_contest;
 
-const CATEGORY_TOML_URL: &'static str =
-    "https://raw.githubusercontent.com/yosupo06/library-checker-problems/master/categories.toml";
-
 #[trait_variant::make]
 pub trait YOJAPIClient: Send + Sync {
-    async fn get_yoj_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)>;
+    async fn get_yoj_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError>;
 }
 
 impl ApiClient {
-    async fn fetch_yoj_categories(&self) -> Result<ProblemCategories> {
-        let categories = get_toml(CATEGORY_TOML_URL, &self.client).await?;
+    async fn fetch_yoj_categories(&self) -> Result<ProblemCategories, IngestError> {
+        let categories = get_toml(&self.base_urls.yoj_categories, &self.client).await?;
         Ok(categories)
     }
 
-    async fn build_yoj_problems_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
+    /// Build the problems and the contests from the categories.
+    ///
+    /// A problem whose name cannot be a part of its URL is skipped.
+    async fn build_yoj_problems_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
         let raw_categories = self.fetch_yoj_categories().await?;
 
         let mut problems: Vec<Problem> = vec![];
         let mut contests: Vec<Contest> = vec![];
+        let mut skipped: Vec<IngestError> = vec![];
         for category in raw_categories.categories.iter() {
             let mut tmp_problems: Vec<Problem> = vec![];
             for (index, raw_problem) in category.raw_problems.iter().enumerate() {
+                if !is_problem_name(raw_problem) {
+                    skipped.push(IngestError::Decode {
+                        target: format!("Library Checker problem in {}", category.name),
+                        message: format!("Invalid problem name: {raw_problem:?}"),
+                    });
+                    continue;
+                }
+
                 let problem = build_problem(&category.name, index, raw_problem);
                 problems.push(problem.clone());
                 tmp_problems.push(problem);
@@ -42,18 +56,28 @@ impl ApiClient {
             contests.push(build_contest(&category.name, tmp_problems));
         }
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 }
 
 impl YOJAPIClient for ApiClient {
-    async fn get_yoj_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (problems, contests) = self.build_yoj_problems_contests().await?;
+    async fn get_yoj_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        let (problems, contests, skipped) = self.build_yoj_problems_contests().await?;
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped))
     }
 }
 
+/// Whether the name is in the form of the problem names of Library Checker, like `point_add_range_sum`.
+fn is_problem_name(raw_problem: &str) -> bool {
+    !raw_problem.is_empty()
+        && raw_problem
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
 fn build_problem(category_name: &str, index: usize, raw_problem: &str) -> Problem {
     Problem::reconstruct(
         Platform::YOJ,
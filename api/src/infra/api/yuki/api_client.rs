@@ -1,12 +1,14 @@
-use anyhow::{Ok, Result};
-use chrono::{DateTime, Duration, Local};
-use std::collections::HashMap;
-use tokio::time::{sleep, Duration as TokioDuration};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 
+use crate::domain::calibration;
+use crate::domain::ingest::IngestError;
 use crate::domain::vo::phase::Phase;
+use crate::domain::vo::watermark::Watermark;
 use crate::domain::{contest::Contest, problem::Problem, vo::platform::Platform};
 use crate::infra::api::api_client::ApiClient;
-use crate::utils::api::get_json;
+use crate::utils::api::{exists, get_json};
 use crate::utils::format::num_to_alphabet;
 
 use super::external::{
@@ -15,65 +17,78 @@ use super::external::{
 
 use super::classifier::classify_contest;
 
-const YUKICODER_URL: &'static str = "https://yukicoder.me/api/v1";
-
 #[trait_variant::make]
 pub trait YukicoderAPIClient: Send + Sync {
-    async fn get_yuki_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)>;
+    async fn get_yuki_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError>;
+
+    /// Fetch the lists of the problems and the contests, which are shared by the batches of a run.
+    async fn get_yuki_catalog(&self) -> Result<YukicoderCatalog, IngestError>;
+
+    /// Fetch about `limit` problems of the catalog published after `watermark`, oldest first,
+    /// together with the contests they belong to.
+    ///
+    /// The problems of a contest are not split across batches,
+    /// so each contest is built with all of its problems published after `watermark`.
+    ///
+    /// The returned watermark points at the last problem in the batch.
+    /// If a problem fails for a retryable reason, or belongs to a contest which has not finished yet,
+    /// the batch stops there and the watermark stays before it.
+    /// It is `None` if there is no problem newer than `watermark` or none of them has been fetched.
+    async fn get_yuki_problems_and_contests_after(
+        &self,
+        catalog: &YukicoderCatalog,
+        watermark: Option<&Watermark>,
+        limit: usize,
+    ) -> Result<
+        (
+            Vec<Problem>,
+            Vec<Contest>,
+            Vec<IngestError>,
+            Option<Watermark>,
+        ),
+        IngestError,
+    >;
+
+    /// Fetch the contests running now or scheduled, without their problems.
+    async fn get_yuki_upcoming_contests(&self) -> Result<Vec<Contest>, IngestError>;
+
+    async fn yuki_user_exists(&self, user_name: &str) -> Result<bool>;
+}
+
+/// The problems and the past contests listed by yukicoder.
+pub struct YukicoderCatalog {
+    problems: Vec<YukicoderProblem>,
+    contests: Vec<YukicoderContest>,
+    /// The problems of the contests running now or scheduled, which are not listed in `contests` yet.
+    unfinished: HashSet<u64>,
+    /// The problems which cannot be crawled incrementally since their publication dates cannot be parsed.
+    pub skipped: Vec<IngestError>,
 }
 
 impl ApiClient {
-    async fn fetch_yuki_problem(&self, problem_id: u64) -> Result<YukicoderProblemWithStatistics> {
-        let url = format!("{YUKICODER_URL}/problems/{problem_id}");
+    async fn fetch_yuki_problem(
+        &self,
+        problem_id: u64,
+    ) -> Result<YukicoderProblemWithStatistics, IngestError> {
+        let url = format!("{}/problems/{problem_id}", self.base_urls.yukicoder);
         let problem = get_json::<YukicoderProblemWithStatistics>(&url, &self.client).await?;
 
         Ok(problem)
     }
 
-    async fn fetch_yuki_problem_ids(&self, is_recent: bool) -> Result<Vec<u64>> {
-        let url = format!("{YUKICODER_URL}/problems");
-        let mut problems = get_json::<Vec<YukicoderProblem>>(&url, &self.client).await?;
-
-        if is_recent {
-            let a_week_ago = Local::now() - Duration::days(7);
-
-            problems = problems
-                .into_iter()
-                .filter(|p| match DateTime::parse_from_rfc3339(&p.date) {
-                    std::result::Result::Ok(parsed_date) => {
-                        parsed_date.timestamp() > a_week_ago.timestamp()
-                    }
-                    Err(_) => false,
-                })
-                .collect();
-        }
-
-        let problem_ids = problems
-            .iter()
-            .map(|problem| problem.problem_id)
-            .collect::<Vec<u64>>();
+    async fn fetch_yuki_problems(&self) -> Result<Vec<YukicoderProblem>, IngestError> {
+        let url = format!("{}/problems", self.base_urls.yukicoder);
+        let problems = get_json::<Vec<YukicoderProblem>>(&url, &self.client).await?;
 
-        Ok(problem_ids)
+        Ok(problems)
     }
 
-    async fn fetch_yuki_past_contests(&self, is_recent: bool) -> Result<Vec<YukicoderContest>> {
-        let url = format!("{YUKICODER_URL}/contest/past");
+    async fn fetch_yuki_past_contests(&self) -> Result<Vec<YukicoderContest>, IngestError> {
+        let url = format!("{}/contest/past", self.base_urls.yukicoder);
         let mut contests: Vec<YukicoderContest> = get_json(&url, &self.client).await?;
 
-        if is_recent {
-            let a_week_ago = Local::now() - Duration::days(7);
-
-            contests = contests
-                .into_iter()
-                .filter(|c| match DateTime::parse_from_rfc3339(&c.date) {
-                    std::result::Result::Ok(parsed_date) => {
-                        parsed_date.timestamp() > a_week_ago.timestamp()
-                    }
-                    Err(_) => false,
-                })
-                .collect();
-        }
-
         contests.iter_mut().for_each(|contest| {
             contest.name = contest.name.trim().to_string();
         });
@@ -81,9 +96,15 @@ impl ApiClient {
         Ok(contests)
     }
 
-    #[allow(dead_code)]
-    async fn fetch_yuki_future_contests(&self) -> Result<Vec<YukicoderContest>> {
-        let url = format!("{YUKICODER_URL}/contest/future");
+    async fn fetch_yuki_current_contests(&self) -> Result<Vec<YukicoderContest>, IngestError> {
+        let url = format!("{}/contest/current", self.base_urls.yukicoder);
+        let current_contests: Vec<YukicoderContest> = get_json(&url, &self.client).await?;
+
+        Ok(current_contests)
+    }
+
+    async fn fetch_yuki_future_contests(&self) -> Result<Vec<YukicoderContest>, IngestError> {
+        let url = format!("{}/contest/future", self.base_urls.yukicoder);
         let future_contests: Vec<YukicoderContest> = get_json(&url, &self.client).await?;
 
         Ok(future_contests)
@@ -91,26 +112,33 @@ impl ApiClient {
 
     #[allow(dead_code)]
     async fn fetch_yuki_tags(&self) -> Result<Vec<YukicoderTag>> {
-        let url = format!("{YUKICODER_URL}/statistics/tags");
+        let url = format!("{}/statistics/tags", self.base_urls.yukicoder);
         let tags = get_json(&url, &self.client).await?;
 
         Ok(tags)
     }
 
-    async fn build_problems_contests(
+    /// Fetch the details of the given problems one by one and build them with their contests.
+    ///
+    /// yukicoder provides statistics only through the per-problem endpoint.
+    /// The requests are throttled by the limit of the host in `HttpClient`.
+    ///
+    /// A problem whose details cannot be decoded is skipped.
+    /// A problem which fails for a retryable reason is also skipped, and the build stops there,
+    /// since the following requests are likely to fail as well.
+    /// So does a problem in `unfinished`, which can be built once its contest is over.
+    /// The number of the problems processed before the stop is returned with the build.
+    async fn build_yuki_problems_contests(
         &self,
-        is_recent: bool,
-    ) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (raw_problem_ids, raw_contests) = tokio::try_join!(
-            self.fetch_yuki_problem_ids(is_recent),
-            self.fetch_yuki_past_contests(is_recent)
-        )?;
-
+        raw_problem_ids: &[u64],
+        raw_contests: &[YukicoderContest],
+        unfinished: &HashSet<u64>,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>, usize), IngestError> {
         let mut p_to_c_map: HashMap<u64, (YukicoderContest, String)> = HashMap::new();
         let mut c_to_p_id_map: HashMap<u64, Vec<Problem>> = HashMap::new();
         let mut c_to_c_map: HashMap<u64, YukicoderContest> = HashMap::new();
 
-        for c in &raw_contests {
+        for c in raw_contests {
             for (idx, &problem_id) in c.problem_id_list.iter().enumerate() {
                 p_to_c_map.insert(problem_id, (c.clone(), num_to_alphabet(idx)));
                 c_to_c_map.insert(c.id, c.clone());
@@ -118,10 +146,36 @@ impl ApiClient {
         }
 
         let mut problems: Vec<Problem> = vec![];
-        for &problem_id in &raw_problem_ids {
-            let raw_problem = self.fetch_yuki_problem(problem_id).await?;
-            let (contest, idx) = p_to_c_map.get(&problem_id).cloned().unwrap();
-
+        let mut skipped: Vec<IngestError> = vec![];
+        let mut processed = 0;
+        for &problem_id in raw_problem_ids {
+            // Problems which do not belong to any contest cannot be identified by our ID scheme.
+            let Some((contest, idx)) = p_to_c_map.get(&problem_id).cloned() else {
+                skipped.push(IngestError::OrphanProblem {
+                    problem_id: problem_id.to_string(),
+                    contest_id: None,
+                });
+                if unfinished.contains(&problem_id) {
+                    break;
+                }
+                processed += 1;
+                continue;
+            };
+
+            let raw_problem = match self.fetch_yuki_problem(problem_id).await {
+                Ok(raw_problem) => raw_problem,
+                Err(e) if e.is_retryable() => {
+                    skipped.push(e);
+                    break;
+                }
+                Err(e @ IngestError::Decode { .. }) => {
+                    skipped.push(e);
+                    processed += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            processed += 1;
             let problem = build_problem(&contest.id, &idx, &raw_problem, &contest);
             problems.push(problem.clone());
 
@@ -129,8 +183,6 @@ impl ApiClient {
                 .entry(contest.id)
                 .or_insert_with(Vec::new)
                 .push(problem.clone());
-
-            sleep(TokioDuration::from_secs(1)).await;
         }
 
         let contests: Vec<Contest> = c_to_p_id_map
@@ -138,18 +190,171 @@ impl ApiClient {
             .map(|(&id, problems)| build_contest(&c_to_c_map[&id], problems.clone()))
             .collect();
 
-        Ok((problems, contests))
+        Ok((problems, contests, skipped, processed))
     }
 }
 
 impl YukicoderAPIClient for ApiClient {
-    async fn get_yuki_problems_and_contests(&self) -> Result<(Vec<Problem>, Vec<Contest>)> {
-        let (problems, contests) = self.build_problems_contests(false).await?;
+    async fn get_yuki_problems_and_contests(
+        &self,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        let catalog = self.get_yuki_catalog().await?;
+
+        let raw_problem_ids = catalog
+            .problems
+            .iter()
+            .map(|problem| problem.problem_id)
+            .collect::<Vec<u64>>();
 
-        Ok((problems, contests))
+        // The whole list is built at once, so nothing is left to be built after the contests.
+        let (problems, contests, skipped, _) = self
+            .build_yuki_problems_contests(&raw_problem_ids, &catalog.contests, &HashSet::new())
+            .await?;
+
+        Ok((problems, contests, skipped))
+    }
+
+    async fn get_yuki_catalog(&self) -> Result<YukicoderCatalog, IngestError> {
+        let (problems, contests, current_contests, future_contests) = tokio::try_join!(
+            self.fetch_yuki_problems(),
+            self.fetch_yuki_past_contests(),
+            self.fetch_yuki_current_contests(),
+            self.fetch_yuki_future_contests()
+        )?;
+
+        let unfinished = current_contests
+            .iter()
+            .chain(future_contests.iter())
+            .flat_map(|contest| contest.problem_id_list.iter().copied())
+            .collect();
+
+        let skipped = problems
+            .iter()
+            .filter_map(|p| {
+                DateTime::parse_from_rfc3339(&p.date)
+                    .err()
+                    .map(|e| IngestError::Decode {
+                        target: format!("the date of yukicoder problem {}", p.problem_id),
+                        message: e.to_string(),
+                    })
+            })
+            .collect();
+
+        Ok(YukicoderCatalog {
+            problems,
+            contests,
+            unfinished,
+            skipped,
+        })
+    }
+
+    async fn get_yuki_problems_and_contests_after(
+        &self,
+        catalog: &YukicoderCatalog,
+        watermark: Option<&Watermark>,
+        limit: usize,
+    ) -> Result<
+        (
+            Vec<Problem>,
+            Vec<Contest>,
+            Vec<IngestError>,
+            Option<Watermark>,
+        ),
+        IngestError,
+    > {
+        let targets = select_problems_after(&catalog.problems, &catalog.contests, watermark, limit);
+        let raw_problem_ids = targets.iter().map(|(_, id)| *id).collect::<Vec<u64>>();
+
+        let (problems, contests, skipped, processed) = self
+            .build_yuki_problems_contests(&raw_problem_ids, &catalog.contests, &catalog.unfinished)
+            .await?;
+
+        Ok((
+            problems,
+            contests,
+            skipped,
+            targets[..processed].last().map(|(w, _)| *w),
+        ))
+    }
+
+    async fn get_yuki_upcoming_contests(&self) -> Result<Vec<Contest>, IngestError> {
+        let (current_contests, future_contests) = tokio::try_join!(
+            self.fetch_yuki_current_contests(),
+            self.fetch_yuki_future_contests()
+        )?;
+
+        Ok(current_contests
+            .iter()
+            .chain(future_contests.iter())
+            .map(|contest| {
+                let mut contest = contest.clone();
+                contest.name = contest.name.trim().to_string();
+                build_contest(&contest, vec![])
+            })
+            .collect())
+    }
+
+    async fn yuki_user_exists(&self, user_name: &str) -> Result<bool> {
+        let url = format!("{}/user/name/{user_name}", self.base_urls.yukicoder);
+        let exists = exists(&url, &self.client).await?;
+
+        Ok(exists)
     }
 }
 
+/// Select `limit` problems published after `watermark`, ordered by the publication date.
+///
+/// The selection is extended beyond `limit` while the next problem belongs to one of the selected contests,
+/// since the problems of a contest are published together.
+/// Problems whose publication date cannot be parsed are ignored. They are listed in `YukicoderCatalog::skipped`.
+fn select_problems_after(
+    problems: &[YukicoderProblem],
+    contests: &[YukicoderContest],
+    watermark: Option<&Watermark>,
+    limit: usize,
+) -> Vec<(Watermark, u64)> {
+    let mut targets = problems
+        .iter()
+        .filter_map(|p| {
+            let date = DateTime::parse_from_rfc3339(&p.date).ok()?;
+            Some((
+                Watermark::new(date.timestamp(), p.problem_id as i64),
+                p.problem_id,
+            ))
+        })
+        .filter(|(w, _)| watermark.is_none_or(|watermark| w > watermark))
+        .collect::<Vec<(Watermark, u64)>>();
+
+    targets.sort();
+
+    let contest_of: HashMap<u64, u64> = contests
+        .iter()
+        .flat_map(|c| {
+            c.problem_id_list
+                .iter()
+                .map(|&problem_id| (problem_id, c.id))
+        })
+        .collect();
+    let selected_contests: HashSet<u64> = targets
+        .iter()
+        .take(limit)
+        .filter_map(|(_, problem_id)| contest_of.get(problem_id).copied())
+        .collect();
+    let len = limit
+        + targets
+            .iter()
+            .skip(limit)
+            .take_while(|(_, problem_id)| {
+                contest_of
+                    .get(problem_id)
+                    .is_some_and(|contest_id| selected_contests.contains(contest_id))
+            })
+            .count();
+    targets.truncate(len);
+
+    targets
+}
+
 fn build_problem(
     contest_id: &u64,
     index: &str,
@@ -163,7 +368,7 @@ fn build_problem(
         index,
         &problem.title,
         Some(problem.level),
-        Option::None,
+        calibration::from_yuki_level(Some(problem.level)),
         String::from(classify_contest(contest)),
         Option::None,
         problem.tags.split(",").map(|s| s.to_string()).collect(),
@@ -175,22 +380,120 @@ fn build_problem(
 
 fn build_contest(contest: &YukicoderContest, problems: Vec<Problem>) -> Contest {
     let start_timestamp = DateTime::parse_from_rfc3339(&contest.date)
-        .unwrap()
-        .timestamp() as i64;
+        .ok()
+        .map(|d| d.timestamp());
 
-    let duration_seconds = DateTime::parse_from_rfc3339(&contest.end_date)
-        .unwrap()
-        .timestamp() as i64
-        - start_timestamp;
+    let end_timestamp = DateTime::parse_from_rfc3339(&contest.end_date)
+        .ok()
+        .map(|d| d.timestamp());
+
+    let duration_seconds = match (start_timestamp, end_timestamp) {
+        (Some(start), Some(end)) => Some(end - start),
+        _ => None,
+    };
+
+    let phase = match (start_timestamp, duration_seconds) {
+        (Some(start), Some(duration)) => {
+            Phase::from_schedule(start, duration, Utc::now().timestamp())
+        }
+        _ => Phase::Finished,
+    };
 
     Contest::reconstruct(
         contest.id.to_string(),
         contest.name.to_string(),
         String::from(classify_contest(contest)),
         Platform::Yukicoder,
-        String::from(Phase::Finished),
-        Some(start_timestamp),
-        Some(duration_seconds),
+        String::from(phase),
+        start_timestamp,
+        duration_seconds,
         problems,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_problem_with_date(problem_id: u64, date: &str) -> YukicoderProblem {
+        YukicoderProblem {
+            no: problem_id,
+            problem_id,
+            title: format!("problem {problem_id}"),
+            author_id: 0,
+            tester_ids: "".to_string(),
+            level: 1.0,
+            problem_type: 0,
+            tags: "".to_string(),
+            date: date.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_problems_after() {
+        let problems = vec![
+            build_problem_with_date(3, "2024-01-03T21:20:00+09:00"),
+            build_problem_with_date(1, "2024-01-01T21:20:00+09:00"),
+            build_problem_with_date(2, "2024-01-01T21:20:00+09:00"),
+            build_problem_with_date(4, "invalid"),
+        ];
+
+        let ids = |targets: Vec<(Watermark, u64)>| {
+            targets.iter().map(|(_, id)| *id).collect::<Vec<u64>>()
+        };
+
+        assert_eq!(
+            ids(select_problems_after(&problems, &[], None, 10)),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            ids(select_problems_after(&problems, &[], None, 2)),
+            vec![1, 2]
+        );
+
+        // Problems published in the same second as the watermark are ordered by their ID.
+        let (watermark, _) = select_problems_after(&problems, &[], None, 1)[0];
+        assert_eq!(
+            ids(select_problems_after(&problems, &[], Some(&watermark), 10)),
+            vec![2, 3]
+        );
+
+        let (watermark, _) = select_problems_after(&problems, &[], None, 10)[2];
+        assert!(select_problems_after(&problems, &[], Some(&watermark), 10).is_empty());
+    }
+
+    #[test]
+    fn test_select_problems_after_keeps_contests_together() {
+        let problems = vec![
+            build_problem_with_date(1, "2024-01-01T21:20:00+09:00"),
+            build_problem_with_date(2, "2024-01-01T21:20:00+09:00"),
+            build_problem_with_date(3, "2024-01-01T21:20:00+09:00"),
+            build_problem_with_date(4, "2024-01-08T21:20:00+09:00"),
+        ];
+        let contest = |id: u64, problem_id_list: Vec<u64>| YukicoderContest {
+            id,
+            name: format!("contest {id}"),
+            date: "2024-01-01T21:20:00+09:00".to_string(),
+            end_date: "2024-01-01T23:20:00+09:00".to_string(),
+            problem_id_list,
+        };
+        let contests = vec![contest(10, vec![1, 2, 3]), contest(11, vec![4])];
+
+        let ids = |targets: Vec<(Watermark, u64)>| {
+            targets.iter().map(|(_, id)| *id).collect::<Vec<u64>>()
+        };
+
+        assert_eq!(
+            ids(select_problems_after(&problems, &contests, None, 1)),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            ids(select_problems_after(&problems, &contests, None, 3)),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            ids(select_problems_after(&problems, &contests, None, 4)),
+            vec![1, 2, 3, 4]
+        );
+    }
+}
@@ -1,63 +1,86 @@
-use super::external::{github::GithubUser, google::GoogleUser};
-use crate::domain::vo::providers::AuthProvider;
-use anyhow::{Context, Result};
+use super::external::github::GithubUser;
+use crate::{
+    config::CONFIG,
+    domain::vo::providers::AuthProvider,
+    utils::{api::get_json_with_headers, http::HttpClient},
+};
+use anyhow::{anyhow, Context, Result};
 use openidconnect::{
     core::{CoreAuthenticationFlow, CoreClient, CoreProviderMetadata},
-    reqwest::{async_http_client, http_client},
-    AccessToken, AuthorizationCode, ClientId, ClientSecret, CsrfToken, IssuerUrl, Nonce,
-    OAuth2TokenResponse, RedirectUrl, Scope,
+    reqwest::async_http_client,
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, IssuerUrl, JsonWebKeySet, Nonce,
+    OAuth2TokenResponse, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse,
+    TokenUrl,
 };
+use reqwest::header::{self, HeaderMap, HeaderValue};
 use std::{collections::HashMap, sync::Arc};
 
 pub struct OidcClient {
     clients: Arc<HashMap<AuthProvider, CoreClient>>,
+    /// For the APIs of the providers, with the retries and the limits of the hosts.
+    http_client: Arc<HttpClient>,
 }
 
-const GOOGLE_API_URL: &str = "";
+const GOOGLE_ISSUER_URL: &str = "https://accounts.google.com";
+const GITHUB_ISSUER_URL: &str = "https://github.com";
+const GITHUB_AUTH_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
 const GITHUB_API_URL: &str = "https://api.github.com/user";
 
+/// The authorization request to redirect the user to.
+/// `csrf_token`, `nonce` and `pkce_verifier` must be kept until the callback.
+pub struct AuthRequest {
+    pub url: String,
+    pub csrf_token: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// The account of the user on the provider side.
+pub struct ProviderUser {
+    pub id: String,
+    pub name: Option<String>,
+}
+
 #[trait_variant::make]
 pub trait OidcClientTrait {
-    async fn get_auth_url(&self, provider: &AuthProvider) -> Result<String>;
-    async fn exchange_code(&self, provider: &AuthProvider, code: &str) -> Result<String>;
-    async fn get_user_info(
+    async fn get_auth_url(&self, provider: &AuthProvider) -> Result<AuthRequest>;
+    async fn exchange_code(
         &self,
         provider: &AuthProvider,
-        access_token: &str,
-    ) -> Result<(String, Option<String>)>;
+        code: &str,
+        pkce_verifier: &str,
+        nonce: &str,
+    ) -> Result<ProviderUser>;
 }
 
 impl OidcClient {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(http_client: Arc<HttpClient>) -> Result<Self> {
         let google_metadata = CoreProviderMetadata::discover_async(
-            IssuerUrl::new("https://accounts.google.com".to_string())?,
+            IssuerUrl::new(GOOGLE_ISSUER_URL.to_string())?,
             async_http_client,
         )
         .await?;
 
         let google_client = CoreClient::from_provider_metadata(
             google_metadata,
-            ClientId::new("google_client_id".to_string()),
-            Some(ClientSecret::new("google_client_secret".to_string())),
-        )
-        .set_redirect_uri(
-            RedirectUrl::new("http://localhost:8080/auth/callback/google".to_string()).unwrap(),
-        );
-
-        let github_metadata = CoreProviderMetadata::discover(
-            &IssuerUrl::new("https://github.com".to_string()).unwrap(),
-            http_client,
+            ClientId::new(CONFIG.google_client_id.clone()),
+            Some(ClientSecret::new(CONFIG.google_client_secret.clone())),
         )
-        .unwrap();
-
-        let github_client = CoreClient::from_provider_metadata(
-            github_metadata,
-            ClientId::new("github_client_id".to_string()),
-            Some(ClientSecret::new("github_client_secret".to_string())),
+        .set_redirect_uri(RedirectUrl::new(CONFIG.google_redirect_url.clone())?);
+
+        // GitHub is a plain OAuth 2.0 provider without a discovery document nor ID tokens,
+        // so the endpoints are configured by hand.
+        let github_client = CoreClient::new(
+            ClientId::new(CONFIG.github_client_id.clone()),
+            Some(ClientSecret::new(CONFIG.github_client_secret.clone())),
+            IssuerUrl::new(GITHUB_ISSUER_URL.to_string())?,
+            AuthUrl::new(GITHUB_AUTH_URL.to_string())?,
+            Some(TokenUrl::new(GITHUB_TOKEN_URL.to_string())?),
+            None,
+            JsonWebKeySet::default(),
         )
-        .set_redirect_uri(
-            RedirectUrl::new("http://localhost:8080/auth/callback/github".to_string()).unwrap(),
-        );
+        .set_redirect_uri(RedirectUrl::new(CONFIG.github_redirect_url.clone())?);
 
         let clients = Arc::new(
             [
@@ -69,98 +92,100 @@ impl OidcClient {
             .collect(),
         );
 
-        Ok(Self { clients })
+        Ok(Self {
+            clients,
+            http_client,
+        })
+    }
+
+    fn client(&self, provider: &AuthProvider) -> Result<&CoreClient> {
+        self.clients
+            .get(provider)
+            .ok_or_else(|| anyhow!("Invalid provider: {:?}", provider))
     }
 }
 
 impl OidcClientTrait for OidcClient {
-    async fn get_auth_url(&self, provider: &AuthProvider) -> Result<String> {
-        match provider {
-            AuthProvider::Google => {
-                let client = self.clients.get(provider).unwrap();
-                let (auth_url, _csrf_token, _nonce) = client
-                    .authorize_url(
-                        CoreAuthenticationFlow::AuthorizationCode,
-                        CsrfToken::new_random,
-                        Nonce::new_random,
-                    )
-                    .add_scope(Scope::new("openid".to_string()))
-                    .add_scope(Scope::new("email".to_string()))
-                    .add_scope(Scope::new("profile".to_string()))
-                    .url();
-
-                Ok(auth_url.to_string())
-            }
-
-            AuthProvider::Github => {
-                let client = self.clients.get(provider).unwrap();
-                let (auth_url, _csrf_token, _nonce) = client
-                    .authorize_url(
-                        CoreAuthenticationFlow::AuthorizationCode,
-                        CsrfToken::new_random,
-                        Nonce::new_random,
-                    )
-                    .add_scope(Scope::new("read:user".to_string()))
-                    .url();
-
-                Ok(auth_url.to_string())
-            }
-        }
-    }
-    async fn exchange_code(&self, provider: &AuthProvider, code: &str) -> Result<String> {
-        if let Some(client) = self.clients.get(provider) {
-            let token_response = client
-                .exchange_code(AuthorizationCode::new(code.to_string()))
-                .request_async(async_http_client)
-                .await
-                .map_err(|e| anyhow::anyhow!(e))?;
-
-            let access_token: AccessToken = token_response.access_token().to_owned();
-
-            Ok(access_token.secret().to_string())
-        } else {
-            Err(anyhow::anyhow!("Invalid provider"))
-        }
+    async fn get_auth_url(&self, provider: &AuthProvider) -> Result<AuthRequest> {
+        let client = self.client(provider)?;
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let request = client
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .set_pkce_challenge(pkce_challenge);
+
+        let request = match provider {
+            AuthProvider::Google => request
+                .add_scope(Scope::new("email".to_string()))
+                .add_scope(Scope::new("profile".to_string())),
+            AuthProvider::Github => request.add_scope(Scope::new("read:user".to_string())),
+        };
+
+        let (auth_url, csrf_token, nonce) = request.url();
+
+        Ok(AuthRequest {
+            url: auth_url.to_string(),
+            csrf_token: csrf_token.secret().to_string(),
+            nonce: nonce.secret().to_string(),
+            pkce_verifier: pkce_verifier.secret().to_string(),
+        })
     }
 
-    async fn get_user_info(
+    /// Exchange the authorization code and identify the user
+    ///
+    /// For Google, the user is taken from the ID token after its signature and nonce are verified.
+    /// GitHub does not issue ID tokens, so the user is fetched with the access token instead.
+    async fn exchange_code(
         &self,
         provider: &AuthProvider,
-        access_token: &str,
-    ) -> Result<(String, Option<String>)> {
+        code: &str,
+        pkce_verifier: &str,
+        nonce: &str,
+    ) -> Result<ProviderUser> {
+        let client = self.client(provider)?;
+
+        let token_response = client
+            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.to_string()))
+            .request_async(async_http_client)
+            .await
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("Failed to exchange code for {:?}", provider))?;
+
         match provider {
             AuthProvider::Google => {
-                let client = Arc::new(reqwest::Client::new());
-
-                let user_info = client
-                    .post(GOOGLE_API_URL)
-                    .header("Authorization", format!("Bearer {access_token}"))
-                    .send()
-                    .await
-                    .with_context(|| "Failed to fetch user info")
-                    .unwrap()
-                    .json::<GoogleUser>()
-                    .await
-                    .with_context(|| "Failed to parse json from user info")
-                    .unwrap();
-
-                Ok((user_info.sub.to_string(), user_info.email))
+                let id_token = token_response
+                    .id_token()
+                    .ok_or_else(|| anyhow!("Google did not return an ID token"))?;
+                let claims = id_token
+                    .claims(&client.id_token_verifier(), &Nonce::new(nonce.to_string()))
+                    .with_context(|| "Failed to verify the ID token")?;
+
+                Ok(ProviderUser {
+                    id: claims.subject().to_string(),
+                    name: claims.email().map(|email| email.to_string()),
+                })
             }
             AuthProvider::Github => {
-                let client = Arc::new(reqwest::Client::new());
-                let user_info = client
-                    .post(GITHUB_API_URL)
-                    .header("Authorization", format!("Bearer {access_token}"))
-                    .send()
-                    .await
-                    .with_context(|| "Failed to fetch user info")
-                    .unwrap()
-                    .json::<GithubUser>()
-                    .await
-                    .with_context(|| "Failed to parse json from user info")
-                    .unwrap();
-
-                Ok((user_info.id.to_string(), Some(user_info.id.to_string())))
+                let access_token = token_response.access_token().secret();
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    header::AUTHORIZATION,
+                    HeaderValue::from_str(&format!("Bearer {access_token}"))?,
+                );
+                let user_info =
+                    get_json_with_headers::<GithubUser>(GITHUB_API_URL, headers, &self.http_client)
+                        .await
+                        .with_context(|| "Failed to fetch user info")?;
+
+                Ok(ProviderUser {
+                    id: user_info.id.to_string(),
+                    name: Some(user_info.login),
+                })
             }
         }
     }
@@ -35,13 +35,13 @@ pub struct GithubUser {
     pub following: i64,
     pub created_at: String,
     pub updated_at: String,
-    pub private_gists: i64,
-    pub total_private_repos: i64,
-    pub owned_private_repos: i64,
-    pub disk_usage: i64,
-    pub collaborators: i64,
-    pub two_factor_authentication: bool,
-    pub plan: Plan,
+    pub private_gists: Option<i64>,
+    pub total_private_repos: Option<i64>,
+    pub owned_private_repos: Option<i64>,
+    pub disk_usage: Option<i64>,
+    pub collaborators: Option<i64>,
+    pub two_factor_authentication: Option<bool>,
+    pub plan: Option<Plan>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
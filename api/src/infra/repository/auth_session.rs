@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Row};
+
+use crate::domain::{auth_session::AuthSession, vo::providers::AuthProvider};
+
+#[trait_variant::make]
+pub trait AuthSessionRepository {
+    async fn create_session(&self, session: &AuthSession, expired_before: i64) -> Result<()>;
+    async fn take_session(&self, csrf_token: &str) -> Result<Option<AuthSession>>;
+}
+
+impl AuthSessionRepository for PgPool {
+    /// Store a pending login session
+    ///
+    /// Sessions created before `expired_before` are purged at the same time,
+    /// so abandoned logins do not pile up.
+    async fn create_session(&self, session: &AuthSession, expired_before: i64) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        sqlx::query(r"DELETE FROM auth_sessions WHERE created_at < $1")
+            .bind(expired_before)
+            .execute(&mut *transaction)
+            .await
+            .with_context(|| "Failed to purge expired auth sessions")?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO auth_sessions (csrf_token, provider, nonce, pkce_verifier, created_at)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&session.csrf_token)
+        .bind(String::from(session.provider))
+        .bind(&session.nonce)
+        .bind(&session.pkce_verifier)
+        .bind(session.created_at)
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| "Failed to create auth session")?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Remove and return the session identified by the CSRF token
+    ///
+    /// A session can be taken only once, so a replayed callback will not find it.
+    async fn take_session(&self, csrf_token: &str) -> Result<Option<AuthSession>> {
+        let row = sqlx::query(
+            r#"
+                DELETE FROM auth_sessions
+                WHERE csrf_token = $1
+                RETURNING csrf_token, provider, nonce, pkce_verifier, created_at
+            "#,
+        )
+        .bind(csrf_token)
+        .fetch_optional(self)
+        .await
+        .with_context(|| "Failed to take auth session")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let provider: String = row.get("provider");
+        let provider = AuthProvider::try_from(provider.as_str()).map_err(anyhow::Error::msg)?;
+
+        Ok(Some(AuthSession {
+            csrf_token: row.get("csrf_token"),
+            provider,
+            nonce: row.get("nonce"),
+            pkce_verifier: row.get("pkce_verifier"),
+            created_at: row.get("created_at"),
+        }))
+    }
+}
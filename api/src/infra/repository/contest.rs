@@ -1,174 +1,223 @@
 use std::collections::HashMap;
 
 use anyhow::{Context, Result};
-use sqlx::{Execute, PgPool, Postgres, QueryBuilder, Row};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 
-use crate::domain::{contest::Contest, problem::Problem};
+use crate::domain::{contest::Contest, problem::Problem, vo::phase::Phase};
 
+#[derive(Default)]
 pub struct Condition<'a> {
-    pub platform: Option<&'a str>,
-    pub category: Option<&'a str>,
+    /// Contests on any of these platforms, or on all platforms if empty.
+    pub platforms: Vec<&'a str>,
+    /// Contests in any of these categories, or in all categories if empty.
+    pub categories: Vec<&'a str>,
+    pub phase: Option<Phase>,
+    /// Contests starting at or after this time (in seconds).
+    pub from_start_time: Option<i64>,
+    /// Contests starting at or before this time (in seconds).
+    pub to_start_time: Option<i64>,
     pub page: Option<i32>,
     pub per_page: Option<i32>,
 }
 
-impl Default for Condition<'_> {
-    fn default() -> Self {
-        Self {
-            platform: Some("atcoder"),
-            category: None,
-            page: None,
-            per_page: None,
-        }
-    }
-}
-
 enum BindValue<'a> {
-    Str(&'a str),
-    Int(i32),
+    StrList(&'a [&'a str]),
+    String(String),
+    I64(i64),
 }
 
 #[trait_variant::make]
 pub trait ContestRepository {
     async fn get_contests_by_condition(&self, condition: &Condition<'_>) -> Result<Vec<Contest>>;
-    async fn update_contests(&self, contests: &Vec<Contest>) -> Result<()>;
+    async fn update_contests(&self, contests: &Vec<Contest>) -> Result<usize>;
 }
 
 impl ContestRepository for PgPool {
+    /// Get the contests matching the condition with their problems, the latest first
+    ///
+    /// The pagination applies to the contests, not to their problems.
     async fn get_contests_by_condition(&self, condition: &Condition<'_>) -> Result<Vec<Contest>> {
-        // タグ情報は必要ないので、空の配列を返す
         let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
             r#"
-            SELECT
-                contests.id,
-                contests.raw_id,
-                contests.name,
-                contests.category,
-                contests.platform,
-                contests.phase,
-                contests.start_time_seconds,
-                contests.duration_seconds,
-                contests.url,
-                problems.id,
-                problems.contest_id,
-                problems.problem_index AS index,
-                problems.name,
-                problems.title,
-                problems.platform,
-                problems.raw_point,
-                problems.difficulty,
-                problems.category,
-                problems.is_experimental,
-                problems.url,
-                problems.solver_count,
-                problems.submissions,
-                problems.success_rate,
-                ARRAY[]::VARCHAR[] AS tags
-            FROM
-                contests
-                LEFT JOIN contest_problems ON contests.id = contest_problems.contest_id
-                LEFT JOIN problems ON contest_problems.problem_id = problems.id
+            WITH selected_contests AS (
+                SELECT * FROM contests
             "#,
         );
 
         let mut conditions: Vec<(&str, BindValue)> = Vec::new();
 
-        if let Some(platform) = condition.platform {
-            conditions.push(("contests.platform = ", BindValue::Str(platform)));
+        if !condition.platforms.is_empty() {
+            conditions.push((
+                "contests.platform = ANY(",
+                BindValue::StrList(&condition.platforms),
+            ));
         }
 
-        if let Some(category) = condition.category {
-            conditions.push(("contests.category = ", BindValue::Str(category)));
+        if !condition.categories.is_empty() {
+            conditions.push((
+                "contests.category = ANY(",
+                BindValue::StrList(&condition.categories),
+            ));
+        }
+
+        if let Some(phase) = condition.phase {
+            conditions.push(("contests.phase = ", BindValue::String(String::from(phase))));
+        }
+
+        if let Some(from_start_time) = condition.from_start_time {
+            conditions.push((
+                "contests.start_time_seconds >= ",
+                BindValue::I64(from_start_time),
+            ));
+        }
+
+        if let Some(to_start_time) = condition.to_start_time {
+            conditions.push((
+                "contests.start_time_seconds <= ",
+                BindValue::I64(to_start_time),
+            ));
         }
 
         if !conditions.is_empty() {
             query_builder.push(" WHERE ");
 
-            for (i, (column, value)) in conditions.iter().enumerate() {
+            for (i, (column, value)) in conditions.into_iter().enumerate() {
                 if i != 0 {
                     query_builder.push(" AND ");
                 }
 
                 match value {
-                    BindValue::Str(value) => {
+                    BindValue::StrList(values) => {
+                        query_builder.push(column).push_bind(values).push(")");
+                    }
+                    BindValue::String(value) => {
                         query_builder.push(column).push_bind(value);
                     }
-                    BindValue::Int(value) => {
+                    BindValue::I64(value) => {
                         query_builder.push(column).push_bind(value);
                     }
                 }
             }
         }
 
-        let page = condition.page.unwrap_or(1);
+        let page = condition.page.unwrap_or(1).max(1);
         let per_page = condition.per_page.unwrap_or(100);
         let offset = (page - 1) * per_page;
 
         query_builder
+            .push(" ORDER BY contests.start_time_seconds DESC NULLS LAST, contests.id")
             .push(" LIMIT ")
             .push_bind(per_page)
             .push(" OFFSET ")
             .push_bind(offset);
 
-        let query = query_builder.build();
-
-        println!("{:?}", query.sql());
+        // タグ情報は必要ないので、空の配列を返す
+        query_builder.push(
+            r#"
+            )
+            SELECT
+                selected_contests.id,
+                selected_contests.raw_id,
+                selected_contests.name,
+                selected_contests.category,
+                selected_contests.platform,
+                selected_contests.phase,
+                selected_contests.start_time_seconds,
+                selected_contests.duration_seconds,
+                selected_contests.url,
+                problems.id AS problem_id,
+                problems.contest_id AS problem_contest_id,
+                problems.contest_name AS problem_contest_name,
+                problems.problem_index,
+                problems.name AS problem_name,
+                problems.title AS problem_title,
+                problems.platform AS problem_platform,
+                problems.category AS problem_category,
+                problems.raw_point,
+                problems.difficulty,
+                problems.is_experimental,
+                problems.difficulty_confidence,
+                problems.url AS problem_url,
+                problems.solver_count,
+                problems.submissions,
+                problems.success_rate,
+                ARRAY[]::VARCHAR[] AS tags
+            FROM
+                selected_contests
+                LEFT JOIN contest_problems ON selected_contests.id = contest_problems.contest_id
+                LEFT JOIN problems ON contest_problems.problem_id = problems.id
+            ORDER BY
+                selected_contests.start_time_seconds DESC NULLS LAST,
+                selected_contests.id,
+                problems.problem_index
+            "#,
+        );
 
-        let rows = sqlx::query(&query.sql()).fetch_all(self).await?;
+        let rows = query_builder
+            .build()
+            .fetch_all(self)
+            .await
+            .context("Failed to fetch contests")?;
 
-        let mut contests_map: HashMap<String, Contest> = HashMap::new();
+        // Keep the order of the contests while grouping the problems by contest
+        let mut contests: Vec<Contest> = Vec::new();
+        let mut contest_indices: HashMap<String, usize> = HashMap::new();
 
         for row in rows {
-            let contest_id = row.get("contests.id");
-            let contest = contests_map.entry(contest_id).or_insert_with(|| {
-                Contest::reconstruct_from_db_wo_problems(
-                    row.get("contests.id"),
-                    row.get("contests.raw_id"),
-                    row.get("contests.name"),
-                    row.get("contests.category"),
-                    row.get("contests.platform"),
-                    row.get("contests.phase"),
-                    row.get("contests.start_time_seconds"),
-                    row.get("contests.duration_seconds"),
-                    row.get("contests.url"),
-                )
+            let contest_id: String = row.get("id");
+            let index = *contest_indices.entry(contest_id).or_insert_with(|| {
+                contests.push(Contest::reconstruct_from_db_wo_problems(
+                    row.get("id"),
+                    row.get("raw_id"),
+                    row.get("name"),
+                    row.get("category"),
+                    row.get("platform"),
+                    row.get("phase"),
+                    row.get("start_time_seconds"),
+                    row.get("duration_seconds"),
+                    row.get("url"),
+                ));
+                contests.len() - 1
             });
+            let contest = &mut contests[index];
 
-            if let Some(problem_id) = row.get::<Option<String>, _>("problems.id") {
+            if let Some(problem_id) = row.get::<Option<String>, _>("problem_id") {
                 let problem = Problem::reconstruct_from_db(
                     problem_id,
-                    row.get("problems.contest_id"),
-                    row.get("problem.contest_name"),
-                    row.get("problems.index"),
-                    row.get("problems.name"),
-                    row.get("problems.title"),
-                    row.get("problems.platform"),
-                    row.get("problem.category"),
-                    row.get("problems.raw_point"),
-                    row.get("problems.difficulty"),
-                    row.get("problems.is_experimental"),
-                    row.get("problems.tags"),
-                    row.get("problems.url"),
-                    row.get("problems.solver_count"),
-                    row.get("problems.submissions"),
-                    row.get("problems.success_rate"),
+                    row.get("problem_contest_id"),
+                    row.get("problem_contest_name"),
+                    row.get("problem_index"),
+                    row.get("problem_name"),
+                    row.get("problem_title"),
+                    row.get("problem_platform"),
+                    row.get("problem_category"),
+                    row.get("raw_point"),
+                    row.get("difficulty"),
+                    row.get("is_experimental"),
+                    row.get("difficulty_confidence"),
+                    row.get("tags"),
+                    row.get("problem_url"),
+                    row.get("solver_count"),
+                    row.get("submissions"),
+                    row.get("success_rate"),
                 );
 
                 contest.problems.push(problem);
             }
         }
 
-        let contests: Vec<Contest> = contests_map.into_iter().map(|(_, v)| v).collect();
-
-        for c in contests.iter() {
-            println!("{:?}", c);
-        }
-
         Ok(contests)
     }
 
-    async fn update_contests(&self, contests: &Vec<Contest>) -> Result<()> {
-        let mut transaction = self.begin().await.unwrap();
+    /// Upsert the contests and link them to their problems
+    ///
+    /// The number of the contests which were not stored before is returned.
+    async fn update_contests(&self, contests: &Vec<Contest>) -> Result<usize> {
+        let mut transaction = self
+            .begin()
+            .await
+            .context("Failed to begin the transaction to update contests")?;
+        let mut inserted = 0;
 
         for chunk in contests.chunks(100) {
             // contests テーブルの更新
@@ -204,14 +253,18 @@ impl ContestRepository for PgPool {
                     start_time_seconds = EXCLUDED.start_time_seconds,
                     duration_seconds = EXCLUDED.duration_seconds,
                     url = EXCLUDED.url
+                RETURNING (xmax = 0) AS inserted
                 "#,
                 );
 
                 let query = query_builder.build();
-                query
-                    .execute(&mut *transaction)
+                inserted += query
+                    .fetch_all(&mut *transaction)
                     .await
-                    .with_context(|| format!("Failed to update contests: {:?}", chunk))?;
+                    .with_context(|| format!("Failed to update contests: {:?}", chunk))?
+                    .iter()
+                    .filter(|row| row.get::<bool, _>("inserted"))
+                    .count();
             }
 
             // contest_problems テーブルの更新
@@ -227,6 +280,11 @@ impl ContestRepository for PgPool {
                     .flat_map(|contest| contest.problems.iter())
                     .collect::<Vec<&Problem>>();
 
+                // Upcoming contests have no problems yet
+                if problems.is_empty() {
+                    continue;
+                }
+
                 query_builder.push_values(problems, |mut separated, problem| {
                     separated
                         .push_bind(&problem.contest_id)
@@ -249,8 +307,11 @@ impl ContestRepository for PgPool {
             }
         }
 
-        transaction.commit().await.unwrap();
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit the update of contests")?;
 
-        Ok(())
+        Ok(inserted)
     }
 }
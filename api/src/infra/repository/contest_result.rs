@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+
+use crate::domain::{contest_result::ContestResult, estimation::Sample, vo::platform::Platform};
+
+#[trait_variant::make]
+pub trait ContestResultRepository {
+    async fn get_contests_without_results(&self, platform: &Platform) -> Result<Vec<String>>;
+    async fn update_contest_results(
+        &self,
+        contest_id: &str,
+        results: &[ContestResult],
+    ) -> Result<()>;
+    async fn get_estimation_samples(&self, platform: &Platform) -> Result<Vec<(String, Sample)>>;
+}
+
+impl ContestResultRepository for PgPool {
+    /// Get the raw IDs of the finished contests whose results have not been fetched yet
+    async fn get_contests_without_results(&self, platform: &Platform) -> Result<Vec<String>> {
+        let raw_ids = sqlx::query_scalar::<_, String>(
+            r#"
+                SELECT contests.raw_id
+                FROM contests
+                WHERE
+                    contests.platform = $1
+                    AND contests.phase = 'finished'
+                    AND contests.start_time_seconds IS NOT NULL
+                    AND NOT EXISTS (
+                        SELECT 1 FROM contest_result_crawls
+                        WHERE contest_result_crawls.contest_id = contests.id
+                    )
+                ORDER BY contests.start_time_seconds DESC
+            "#,
+        )
+        .bind(String::from(*platform))
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch contests without results of {:?}", platform))?;
+
+        Ok(raw_ids)
+    }
+
+    /// Store the results of the contest and mark the contest as fetched
+    ///
+    /// Contests without any result (e.g., unrated contests) are marked as well,
+    /// so that they are not fetched again.
+    async fn update_contest_results(
+        &self,
+        contest_id: &str,
+        results: &[ContestResult],
+    ) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        for chunk in results.chunks(1000) {
+            let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO contest_results (contest_id, platform, user_id, rating)
+                "#,
+            );
+
+            query_builder.push_values(chunk, |mut separated, result| {
+                separated
+                    .push_bind(&result.contest_id)
+                    .push_bind(String::from(result.platform))
+                    .push_bind(&result.user_id)
+                    .push_bind(result.rating);
+            });
+
+            query_builder.push(
+                r#"
+                ON CONFLICT (contest_id, user_id) DO UPDATE SET
+                    rating = EXCLUDED.rating
+                "#,
+            );
+
+            query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await
+                .with_context(|| format!("Failed to update results of {contest_id}"))?;
+        }
+
+        sqlx::query(
+            r#"
+                INSERT INTO contest_result_crawls (contest_id, fetched_at)
+                VALUES ($1, CURRENT_TIMESTAMP)
+                ON CONFLICT (contest_id) DO UPDATE SET fetched_at = EXCLUDED.fetched_at
+            "#,
+        )
+        .bind(contest_id)
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Failed to mark results of {contest_id} as fetched"))?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Get (problem ID, sample) pairs for the difficulty estimation
+    ///
+    /// A participant is taken into account only if any of their submissions during the contest is stored,
+    /// since otherwise it is unknown which problems they solved.
+    /// A problem counts as solved if it was accepted during the contest.
+    async fn get_estimation_samples(&self, platform: &Platform) -> Result<Vec<(String, Sample)>> {
+        let rows = sqlx::query(
+            r#"
+                SELECT
+                    problems.id AS problem_id,
+                    contest_results.rating,
+                    EXISTS (
+                        SELECT 1 FROM submissions
+                        WHERE
+                            submissions.platform = contest_results.platform
+                            AND submissions.user_id = contest_results.user_id
+                            AND submissions.verdict = 'AC'
+                            AND submissions.submission_date
+                                BETWEEN contests.start_time_seconds
+                                AND contests.start_time_seconds + contests.duration_seconds
+                            AND (
+                                (
+                                    problems.platform = 'atcoder'
+                                    AND submissions.problem_index = SUBSTRING(problems.url FROM '[^/]+$')
+                                )
+                                OR (
+                                    problems.platform = 'codeforces'
+                                    AND problems.id = 'codeforces_' || submissions.contest_id || '_' || submissions.problem_index
+                                )
+                            )
+                    ) AS solved
+                FROM
+                    contest_results
+                    JOIN contests ON contests.id = contest_results.contest_id
+                    JOIN contest_problems ON contest_problems.contest_id = contests.id
+                    JOIN problems ON problems.id = contest_problems.problem_id
+                WHERE
+                    contest_results.platform = $1
+                    AND EXISTS (
+                        SELECT 1 FROM submissions
+                        WHERE
+                            submissions.platform = contest_results.platform
+                            AND submissions.user_id = contest_results.user_id
+                            AND submissions.submission_date
+                                BETWEEN contests.start_time_seconds
+                                AND contests.start_time_seconds + contests.duration_seconds
+                    )
+            "#,
+        )
+        .bind(String::from(*platform))
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch estimation samples of {:?}", platform))?;
+
+        let samples = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("problem_id"),
+                    Sample {
+                        rating: row.get::<i32, _>("rating") as f64,
+                        solved: row.get("solved"),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(samples)
+    }
+}
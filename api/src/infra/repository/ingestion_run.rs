@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+use crate::domain::{
+    ingestion_run::{IngestionRun, RunMode},
+    scheduler::{JobRun, LOCK_TIMEOUT_SECONDS},
+    vo::platform::Platform,
+};
+
+#[trait_variant::make]
+pub trait IngestionRunRepository {
+    async fn create_ingestion_run(
+        &self,
+        platform: &Platform,
+        mode: RunMode,
+        contest_id: Option<&str>,
+        triggered_by: Option<&str>,
+        started_at: i64,
+    ) -> Result<IngestionRun>;
+    async fn finish_ingestion_run(&self, id: i64, run: &JobRun) -> Result<()>;
+    async fn fail_stale_ingestion_runs(&self, now: i64) -> Result<u64>;
+    async fn get_ingestion_runs(
+        &self,
+        platform: Option<&Platform>,
+        failed_only: bool,
+        limit: i64,
+    ) -> Result<Vec<IngestionRun>>;
+}
+
+const COLUMNS: &str = r#"
+    id, platform, mode, contest_id, triggered_by, started_at, finished_at, succeeded, error,
+    problems_inserted, problems_updated, contests_inserted, contests_updated, skipped
+"#;
+
+impl IngestionRunRepository for PgPool {
+    /// Record the start of a run
+    async fn create_ingestion_run(
+        &self,
+        platform: &Platform,
+        mode: RunMode,
+        contest_id: Option<&str>,
+        triggered_by: Option<&str>,
+        started_at: i64,
+    ) -> Result<IngestionRun> {
+        let row = sqlx::query(&format!(
+            r#"
+                INSERT INTO ingestion_runs (platform, mode, contest_id, triggered_by, started_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING {COLUMNS}
+            "#
+        ))
+        .bind(String::from(*platform))
+        .bind(String::from(mode))
+        .bind(contest_id)
+        .bind(triggered_by)
+        .bind(started_at)
+        .fetch_one(self)
+        .await
+        .with_context(|| format!("Failed to record the start of the update of {:?}", platform))?;
+
+        build_ingestion_run(&row)
+    }
+
+    /// Record the result of a run
+    async fn finish_ingestion_run(&self, id: i64, run: &JobRun) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE ingestion_runs SET
+                    finished_at = $2,
+                    succeeded = $3,
+                    error = $4,
+                    problems_inserted = $5,
+                    problems_updated = $6,
+                    contests_inserted = $7,
+                    contests_updated = $8,
+                    skipped = $9
+                WHERE
+                    id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(run.finished_at)
+        .bind(run.error.is_none())
+        .bind(&run.error)
+        .bind(run.problems_inserted)
+        .bind(run.problems - run.problems_inserted)
+        .bind(run.contests_inserted)
+        .bind(run.contests - run.contests_inserted)
+        .bind(run.skipped)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to record the result of the ingestion run {}", id))?;
+
+        Ok(())
+    }
+
+    /// Record the runs left unfinished by a crashed or restarted process as failed
+    ///
+    /// A run is still running only while the lock of its platform, taken when it started, is held.
+    /// The number of the runs recorded as failed is returned.
+    async fn fail_stale_ingestion_runs(&self, now: i64) -> Result<u64> {
+        let failed = sqlx::query(
+            r#"
+                UPDATE ingestion_runs SET
+                    finished_at = $1,
+                    succeeded = FALSE,
+                    error = 'Interrupted before it finished'
+                WHERE
+                    finished_at IS NULL
+                    AND NOT EXISTS (
+                        SELECT 1 FROM scheduler_jobs
+                        WHERE
+                            scheduler_jobs.platform = ingestion_runs.platform
+                            AND scheduler_jobs.running_since = ingestion_runs.started_at
+                            AND scheduler_jobs.running_since > $2
+                    )
+            "#,
+        )
+        .bind(now)
+        .bind(now - LOCK_TIMEOUT_SECONDS)
+        .execute(self)
+        .await
+        .context("Failed to record the interrupted ingestion runs")?
+        .rows_affected();
+
+        Ok(failed)
+    }
+
+    /// Get the runs of the platform, or of all the platforms if `None`, the latest first
+    async fn get_ingestion_runs(
+        &self,
+        platform: Option<&Platform>,
+        failed_only: bool,
+        limit: i64,
+    ) -> Result<Vec<IngestionRun>> {
+        let runs = sqlx::query(&format!(
+            r#"
+                SELECT {COLUMNS}
+                FROM
+                    ingestion_runs
+                WHERE
+                    ($1::VARCHAR IS NULL OR platform = $1)
+                    AND (NOT $2 OR succeeded = FALSE)
+                ORDER BY
+                    started_at DESC, id DESC
+                LIMIT $3
+            "#
+        ))
+        .bind(platform.map(|p| String::from(*p)))
+        .bind(failed_only)
+        .bind(limit)
+        .fetch_all(self)
+        .await
+        .context("Failed to fetch the ingestion runs")?
+        .iter()
+        .map(build_ingestion_run)
+        .collect::<Result<_>>()?;
+
+        Ok(runs)
+    }
+}
+
+/// A run with an unknown platform or mode, such as one written by a newer version, is an error.
+fn build_ingestion_run(row: &PgRow) -> Result<IngestionRun> {
+    Ok(IngestionRun {
+        id: row.get("id"),
+        platform: row
+            .get::<&str, _>("platform")
+            .parse()
+            .map_err(anyhow::Error::msg)?,
+        mode: row
+            .get::<&str, _>("mode")
+            .parse()
+            .map_err(anyhow::Error::msg)?,
+        contest_id: row.get("contest_id"),
+        triggered_by: row.get("triggered_by"),
+        started_at: row.get("started_at"),
+        finished_at: row.get("finished_at"),
+        succeeded: row.get("succeeded"),
+        error: row.get("error"),
+        problems_inserted: row.get("problems_inserted"),
+        problems_updated: row.get("problems_updated"),
+        contests_inserted: row.get("contests_inserted"),
+        contests_updated: row.get("contests_updated"),
+        skipped: row.get("skipped"),
+    })
+}
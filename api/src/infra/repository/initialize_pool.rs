@@ -1,7 +1,10 @@
 use anyhow::Result;
 use std::time::Duration;
 
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
+
+/// The migrations in `migrations/`, embedded at compile time.
+static MIGRATOR: Migrator = sqlx::migrate!();
 
 pub async fn initialize_pool<S: AsRef<str>>(db_url: S) -> Result<PgPool> {
     let pool = PgPoolOptions::new()
@@ -12,3 +15,13 @@ pub async fn initialize_pool<S: AsRef<str>>(db_url: S) -> Result<PgPool> {
 
     Ok(pool)
 }
+
+/// Apply the migrations which have not been applied yet.
+///
+/// The migrations only add tables, columns, indexes and seed data, so the stored data is kept.
+/// Concurrent runs are serialized by an advisory lock.
+pub async fn run_migrations(pool: &PgPool) -> Result<()> {
+    MIGRATOR.run(pool).await?;
+
+    Ok(())
+}
@@ -1,5 +1,16 @@
+pub mod auth_session;
 pub mod contest;
+pub mod contest_result;
+pub mod ingestion_run;
 pub mod initialize_pool;
 pub mod problem;
+pub mod problem_list;
+pub mod rating_history;
+pub mod scheduler;
+pub mod submission;
+pub mod tag_proposal;
 pub mod technical_tag;
 pub mod user;
+pub mod user_stats;
+pub mod virtual_contest;
+pub mod watermark;
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
-use sqlx::{PgPool, Postgres, QueryBuilder};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 
-use crate::domain::problem::Problem;
+use crate::domain::{calibration::NormalizedDifficulty, problem::Problem, vo::platform::Platform};
 
 pub struct Condition<'a> {
     pub platform: Option<&'a str>,
@@ -27,6 +27,13 @@ impl Default for Condition<'_> {
     }
 }
 
+pub struct SearchCondition<'a> {
+    pub query: &'a str,
+    pub platform: Option<&'a str>,
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
 enum BindValue<'a> {
     Str(&'a str),
     I32(i32),
@@ -36,7 +43,17 @@ enum BindValue<'a> {
 pub trait ProblemRepository {
     async fn get_problems_by_condition(&self, condition: &Condition<'_>) -> Result<Vec<Problem>>;
     async fn get_problem_by_id(&self, id: &str) -> Result<Problem>;
-    async fn update_problems(&self, problems: &[Problem]) -> Result<()>;
+    async fn get_problems_by_keys(
+        &self,
+        platform: &Platform,
+        keys: &[String],
+    ) -> Result<Vec<Problem>>;
+    async fn search_problems(&self, condition: &SearchCondition<'_>) -> Result<Vec<Problem>>;
+    async fn update_problems(&self, problems: &[Problem]) -> Result<usize>;
+    async fn update_estimated_difficulties(
+        &self,
+        difficulties: &[(String, NormalizedDifficulty, bool)],
+    ) -> Result<()>;
 }
 
 impl ProblemRepository for PgPool {
@@ -55,6 +72,7 @@ impl ProblemRepository for PgPool {
                     problems.difficulty,
                     problems.category,
                     problems.is_experimental,
+                    problems.difficulty_confidence,
                     problems.url,
                     problems.solver_count,
                     problems.submissions,
@@ -73,15 +91,13 @@ impl ProblemRepository for PgPool {
             conditions.push(("problems.platform = ", BindValue::Str(platform)));
         }
 
-        if let Some(algo_id) = condition.algo_id {
-            conditions.push(("technical_tags.algorithm_id = ", BindValue::Str(algo_id)));
+        // Difficulties of all the platforms are on the AtCoder scale, so the same range applies.
+        if let Some(from_difficulty) = condition.from_difficulty {
+            conditions.push(("problems.difficulty >= ", BindValue::I32(from_difficulty)));
         }
 
-        if let Some(technical_tag_id) = condition.technical_tag_id {
-            conditions.push((
-                "problem_tags.technical_tag_id = ",
-                BindValue::Str(technical_tag_id),
-            ));
+        if let Some(to_difficulty) = condition.to_difficulty {
+            conditions.push(("problems.difficulty <= ", BindValue::I32(to_difficulty)));
         }
 
         if !conditions.is_empty() {
@@ -104,6 +120,26 @@ impl ProblemRepository for PgPool {
 
         query_builder.push(" GROUP BY problems.id");
 
+        // Tags are filtered after the aggregation, so that all the tags of the matched problems are returned.
+        if condition.algo_id.is_some() || condition.technical_tag_id.is_some() {
+            query_builder.push(" HAVING ");
+            let mut having = query_builder.separated(" AND ");
+
+            if let Some(algo_id) = condition.algo_id {
+                having
+                    .push("BOOL_OR(technical_tags.algorithm_id = ")
+                    .push_bind_unseparated(algo_id)
+                    .push_unseparated(")");
+            }
+
+            if let Some(technical_tag_id) = condition.technical_tag_id {
+                having
+                    .push("BOOL_OR(problem_tags.technical_tag_id = ")
+                    .push_bind_unseparated(technical_tag_id)
+                    .push_unseparated(")");
+            }
+        }
+
         let page = condition.page.unwrap_or(1);
         let page_size = condition.page_size.unwrap_or(20);
         let offset = (page - 1) * page_size;
@@ -119,10 +155,6 @@ impl ProblemRepository for PgPool {
             .fetch_all(self)
             .await?;
 
-        for p in problems.iter() {
-            println!("{:?}", p);
-        }
-
         Ok(problems)
     }
 
@@ -139,8 +171,9 @@ impl ProblemRepository for PgPool {
                     problems.platform,
                     problems.raw_point,
                     problems.difficulty,
-                    problem.category,
+                    problems.category,
                     problems.is_experimental,
+                    problems.difficulty_confidence,
                     problems.url,
                     problems.solver_count,
                     problems.submissions,
@@ -161,20 +194,159 @@ impl ProblemRepository for PgPool {
         .await
         .with_context(|| format!("Failed to fetch problem"))?;
 
-        println!("{:?}", problem);
-
         Ok(problem)
     }
 
-    async fn update_problems(&self, problems: &[Problem]) -> Result<()> {
+    /// Get the problems of the platform by the keys which the submissions to them carry
+    ///
+    /// The keys are matched in the same way as `service::problem::problem_key`:
+    /// the task ID in the URL for AtCoder, the raw problem ID for AOJ, and the problem ID otherwise.
+    async fn get_problems_by_keys(
+        &self,
+        platform: &Platform,
+        keys: &[String],
+    ) -> Result<Vec<Problem>> {
+        let key_column = match platform {
+            Platform::Atcoder => "SUBSTRING(problems.url FROM '[^/]+$')",
+            Platform::Aoj => "problems.problem_index",
+            _ => "problems.id",
+        };
+
+        let problems = sqlx::query_as::<_, Problem>(&format!(
+            r#"
+                SELECT
+                    problems.id,
+                    problems.contest_id,
+                    problems.contest_name,
+                    problems.problem_index AS index,
+                    problems.name,
+                    problems.title,
+                    problems.platform,
+                    problems.raw_point,
+                    problems.difficulty,
+                    problems.category,
+                    problems.is_experimental,
+                    problems.difficulty_confidence,
+                    problems.url,
+                    problems.solver_count,
+                    problems.submissions,
+                    problems.success_rate,
+                    ARRAY_REMOVE (ARRAY_AGG (technical_tags.en_name), NULL) AS tags
+                FROM
+                    problems
+                    LEFT JOIN problem_tags ON problems.id = problem_tags.problem_id
+                    LEFT JOIN technical_tags ON problem_tags.technical_tag_id = technical_tags.id
+                WHERE
+                    problems.platform = $1
+                    AND {key_column} = ANY($2)
+                GROUP BY
+                    problems.id
+            "#
+        ))
+        .bind(String::from(*platform))
+        .bind(keys)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch problems of {:?} by keys", platform))?;
+
+        Ok(problems)
+    }
+
+    /// Search the problems by name, title and contest name, best match first
+    ///
+    /// Problems containing the query as a substring match regardless of the language,
+    /// which covers short Japanese queries that trigrams do not work well with.
+    /// Problems similar to the query (e.g., with a typo) match as well, by trigram word similarity.
+    ///
+    /// The problems are ranked in the order of exact match, prefix match, substring match,
+    /// and then by the similarity.
+    async fn search_problems(&self, condition: &SearchCondition<'_>) -> Result<Vec<Problem>> {
+        let pattern = format!("%{}%", escape_like(condition.query));
+        let prefix_pattern = format!("{}%", escape_like(condition.query));
+
+        let page = condition.page.unwrap_or(1);
+        let page_size = condition.page_size.unwrap_or(20);
+        let offset = (page - 1) * page_size;
+
+        let problems = sqlx::query_as::<_, Problem>(
+            r#"
+                SELECT
+                    problems.id,
+                    problems.contest_id,
+                    problems.contest_name,
+                    problems.problem_index AS index,
+                    problems.name,
+                    problems.title,
+                    problems.platform,
+                    problems.raw_point,
+                    problems.difficulty,
+                    problems.category,
+                    problems.is_experimental,
+                    problems.difficulty_confidence,
+                    problems.url,
+                    problems.solver_count,
+                    problems.submissions,
+                    problems.success_rate,
+                    ARRAY_REMOVE (ARRAY_AGG (technical_tags.en_name), NULL) AS tags
+                FROM
+                    problems
+                    LEFT JOIN problem_tags ON problems.id = problem_tags.problem_id
+                    LEFT JOIN technical_tags ON problem_tags.technical_tag_id = technical_tags.id
+                WHERE
+                    ($4::VARCHAR IS NULL OR problems.platform = $4)
+                    AND (
+                        problems.name ILIKE $2
+                        OR problems.title ILIKE $2
+                        OR problems.contest_name ILIKE $2
+                        OR $1 <% problems.name
+                        OR $1 <% problems.title
+                        OR $1 <% problems.contest_name
+                    )
+                GROUP BY
+                    problems.id
+                ORDER BY
+                    CASE
+                        WHEN LOWER(problems.name) = LOWER($1) OR LOWER(problems.title) = LOWER($1) THEN 3
+                        WHEN problems.name ILIKE $3 OR problems.title ILIKE $3 THEN 2
+                        WHEN problems.name ILIKE $2 OR problems.title ILIKE $2 THEN 1
+                        ELSE 0
+                    END DESC,
+                    GREATEST(
+                        WORD_SIMILARITY($1, problems.name),
+                        WORD_SIMILARITY($1, problems.title),
+                        WORD_SIMILARITY($1, problems.contest_name)
+                    ) DESC,
+                    problems.id
+                LIMIT $5 OFFSET $6
+                "#,
+        )
+        .bind(condition.query)
+        .bind(pattern)
+        .bind(prefix_pattern)
+        .bind(condition.platform)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to search problems by {}", condition.query))?;
+
+        Ok(problems)
+    }
+
+    /// Upsert the problems and attach their native tags
+    ///
+    /// A problem without a difficulty upstream keeps the stored one, which may have been estimated in-house.
+    /// The number of the problems which were not stored before is returned.
+    async fn update_problems(&self, problems: &[Problem]) -> Result<usize> {
         let mut transaction = self.begin().await?;
+        let mut inserted = 0;
 
         for chunk in problems.chunks(100) {
             let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
                 r#"
                 INSERT INTO problems (
                     id, contest_id, contest_name, problem_index, name, title, platform,
-                    raw_point, difficulty, category, is_experimental, url,
+                    raw_point, difficulty, category, is_experimental, difficulty_confidence, url,
                     solver_count, submissions, success_rate
                 )
                 "#,
@@ -193,6 +365,7 @@ impl ProblemRepository for PgPool {
                     .push_bind(problem.difficulty)
                     .push_bind(&problem.category)
                     .push_bind(problem.is_experimental)
+                    .push_bind(problem.difficulty_confidence.map(String::from))
                     .push_bind(&problem.url)
                     .push_bind(problem.solver_count)
                     .push_bind(problem.submissions)
@@ -209,27 +382,178 @@ impl ProblemRepository for PgPool {
                     title = EXCLUDED.title,
                     platform = EXCLUDED.platform,
                     raw_point = EXCLUDED.raw_point,
-                    difficulty = EXCLUDED.difficulty,
+                    difficulty = COALESCE(EXCLUDED.difficulty, problems.difficulty),
+                    difficulty_estimated = EXCLUDED.difficulty IS NULL AND problems.difficulty_estimated,
                     category = EXCLUDED.category,
-                    is_experimental = EXCLUDED.is_experimental,
+                    is_experimental = CASE
+                        WHEN EXCLUDED.difficulty IS NULL THEN problems.is_experimental
+                        ELSE EXCLUDED.is_experimental
+                    END,
+                    difficulty_confidence = CASE
+                        WHEN EXCLUDED.difficulty IS NULL THEN problems.difficulty_confidence
+                        ELSE EXCLUDED.difficulty_confidence
+                    END,
                     url = EXCLUDED.url,
                     solver_count = EXCLUDED.solver_count,
                     submissions = EXCLUDED.submissions,
                     success_rate = EXCLUDED.success_rate
+                RETURNING (xmax = 0) AS inserted
                 "#,
             );
 
             let query = query_builder.build();
-            query
-                .execute(&mut *transaction)
+            inserted += query
+                .fetch_all(&mut *transaction)
                 .await
-                .with_context(|| format!("Failed to execute query"))?;
+                .with_context(|| format!("Failed to execute query"))?
+                .iter()
+                .filter(|row| row.get::<bool, _>("inserted"))
+                .count();
         }
 
         // problem_tags の upsert
+        // The native tags of the platforms are mapped onto the technical tags through tag_aliases.
+        // Tags without any alias are ignored.
+        // The tags derived before are replaced, so that a tag removed upstream is removed as well,
+        // while the tags attached by approved proposals are kept.
+        let problem_ids: Vec<&str> = problems.iter().map(|problem| problem.id.as_str()).collect();
+        sqlx::query(
+            r#"
+            DELETE FROM problem_tags
+            WHERE
+                problem_id = ANY ($1)
+                AND NOT EXISTS (
+                    SELECT 1 FROM tag_proposals
+                    WHERE
+                        tag_proposals.problem_id = problem_tags.problem_id
+                        AND tag_proposals.technical_tag_id = problem_tags.technical_tag_id
+                        AND tag_proposals.status = 'approved'
+                )
+            "#,
+        )
+        .bind(&problem_ids)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to clear problem_tags")?;
+
+        let native_tags: Vec<(&str, String, &str)> = problems
+            .iter()
+            .flat_map(|problem| {
+                problem.tags.iter().map(|tag| {
+                    (
+                        problem.id.as_str(),
+                        String::from(problem.platform),
+                        tag.as_str(),
+                    )
+                })
+            })
+            .collect();
+
+        for chunk in native_tags.chunks(1000) {
+            let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO problem_tags (problem_id, technical_tag_id)
+                SELECT DISTINCT native_tags.problem_id, tag_aliases.technical_tag_id
+                FROM (
+                "#,
+            );
+
+            query_builder.push_values(chunk, |mut separated, (problem_id, platform, tag)| {
+                separated
+                    .push_bind(*problem_id)
+                    .push_bind(platform)
+                    .push_bind(*tag);
+            });
+
+            query_builder.push(
+                r#"
+                ) AS native_tags (problem_id, platform, tag)
+                JOIN tag_aliases ON
+                    tag_aliases.platform = native_tags.platform
+                    AND tag_aliases.alias = LOWER(TRIM(native_tags.tag))
+                ON CONFLICT (problem_id, technical_tag_id) DO NOTHING
+                "#,
+            );
+
+            query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await
+                .context("Failed to update problem_tags")?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(inserted)
+    }
+
+    /// Write the difficulties estimated in-house, as (problem ID, difficulty, is_experimental)
+    ///
+    /// Only the problems without any difficulty or with an in-house estimate are written,
+    /// so the difficulties given upstream, such as the estimates by AtCoder Problems
+    /// and the ratings of Codeforces, are never overwritten.
+    /// Experimental estimates do not replace the non-experimental ones.
+    async fn update_estimated_difficulties(
+        &self,
+        difficulties: &[(String, NormalizedDifficulty, bool)],
+    ) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        for chunk in difficulties.chunks(100) {
+            let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+                r#"
+                UPDATE problems SET
+                    difficulty = estimates.difficulty,
+                    is_experimental = estimates.is_experimental,
+                    difficulty_confidence = estimates.difficulty_confidence,
+                    difficulty_estimated = TRUE
+                FROM (
+                "#,
+            );
+
+            query_builder.push_values(
+                chunk,
+                |mut separated, (problem_id, difficulty, is_experimental)| {
+                    separated
+                        .push_bind(problem_id)
+                        .push_bind(difficulty.value)
+                        .push_bind(*is_experimental)
+                        .push_bind(String::from(difficulty.confidence));
+                },
+            );
+
+            query_builder.push(
+                r#"
+                ) AS estimates (id, difficulty, is_experimental, difficulty_confidence)
+                WHERE
+                    problems.id = estimates.id
+                    AND (
+                        problems.difficulty IS NULL
+                        OR (
+                            problems.difficulty_estimated
+                            AND (NOT estimates.is_experimental OR problems.is_experimental)
+                        )
+                    )
+                "#,
+            );
+
+            query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await
+                .with_context(|| "Failed to update estimated difficulties")?;
+        }
 
         transaction.commit().await?;
 
         Ok(())
     }
 }
+
+/// Escape the wildcards of LIKE, so that the query matches literally.
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::domain::problem_list::{
+    ProblemList, ProblemListEntry, ProblemListError, ProblemListExport, ProblemListItem,
+};
+
+const SELECT_PROBLEM_LISTS: &str = r#"
+    SELECT
+        problem_lists.id,
+        problem_lists.owner_id,
+        problem_lists.name,
+        problem_lists.description,
+        problem_lists.is_public,
+        (
+            SELECT COUNT(*)
+            FROM problem_list_items
+            WHERE problem_list_items.list_id = problem_lists.id
+        ) AS item_count,
+        problem_lists.created_at,
+        problem_lists.updated_at
+    FROM
+        problem_lists
+"#;
+
+#[trait_variant::make]
+pub trait ProblemListRepository {
+    async fn get_problem_lists(&self, owner_id: &str) -> Result<Vec<ProblemList>>;
+    async fn get_problem_list(&self, list_id: &str) -> Result<Option<ProblemList>>;
+    async fn get_problem_list_items(&self, list_id: &str) -> Result<Vec<ProblemListItem>>;
+    async fn create_problem_list(
+        &self,
+        owner_id: &str,
+        content: &ProblemListExport,
+        is_public: bool,
+        now: i64,
+    ) -> Result<ProblemList>;
+    async fn update_problem_list(
+        &self,
+        list_id: &str,
+        name: &str,
+        description: Option<&str>,
+        is_public: bool,
+        now: i64,
+    ) -> Result<ProblemList>;
+    async fn replace_problem_list_items(
+        &self,
+        list_id: &str,
+        items: &[ProblemListEntry],
+        now: i64,
+    ) -> Result<()>;
+    async fn upsert_problem_list_item(
+        &self,
+        list_id: &str,
+        item: &ProblemListEntry,
+        now: i64,
+    ) -> Result<()>;
+    async fn delete_problem_list(&self, list_id: &str) -> Result<()>;
+}
+
+impl ProblemListRepository for PgPool {
+    /// Get the lists of the user, the last updated first
+    async fn get_problem_lists(&self, owner_id: &str) -> Result<Vec<ProblemList>> {
+        let lists = sqlx::query_as::<_, ProblemList>(&format!(
+            r#"
+                {SELECT_PROBLEM_LISTS}
+                WHERE problem_lists.owner_id = $1
+                ORDER BY problem_lists.updated_at DESC, problem_lists.id
+            "#
+        ))
+        .bind(owner_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the problem lists of {owner_id}"))?;
+
+        Ok(lists)
+    }
+
+    async fn get_problem_list(&self, list_id: &str) -> Result<Option<ProblemList>> {
+        let list = sqlx::query_as::<_, ProblemList>(&format!(
+            "{SELECT_PROBLEM_LISTS} WHERE problem_lists.id = $1"
+        ))
+        .bind(list_id)
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to fetch problem list {list_id}"))?;
+
+        Ok(list)
+    }
+
+    /// Get the problems in the list in their order
+    async fn get_problem_list_items(&self, list_id: &str) -> Result<Vec<ProblemListItem>> {
+        let items = sqlx::query_as::<_, ProblemListItem>(
+            r#"
+                SELECT
+                    problems.id,
+                    problems.contest_id,
+                    problems.contest_name,
+                    problems.problem_index AS index,
+                    problems.name,
+                    problems.title,
+                    problems.platform,
+                    problems.raw_point,
+                    problems.difficulty,
+                    problems.category,
+                    problems.is_experimental,
+                    problems.difficulty_confidence,
+                    problems.url,
+                    problems.solver_count,
+                    problems.submissions,
+                    problems.success_rate,
+                    ARRAY_REMOVE (ARRAY_AGG (technical_tags.en_name), NULL) AS tags,
+                    problem_list_items.note
+                FROM
+                    problem_list_items
+                    JOIN problems ON problem_list_items.problem_id = problems.id
+                    LEFT JOIN problem_tags ON problems.id = problem_tags.problem_id
+                    LEFT JOIN technical_tags ON problem_tags.technical_tag_id = technical_tags.id
+                WHERE
+                    problem_list_items.list_id = $1
+                GROUP BY
+                    problems.id, problem_list_items.position, problem_list_items.note
+                ORDER BY
+                    problem_list_items.position
+            "#,
+        )
+        .bind(list_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the problems in problem list {list_id}"))?;
+
+        Ok(items)
+    }
+
+    /// Create a list with the content
+    ///
+    /// Fails with `ProblemListError::Invalid` if any of the problems does not exist.
+    async fn create_problem_list(
+        &self,
+        owner_id: &str,
+        content: &ProblemListExport,
+        is_public: bool,
+        now: i64,
+    ) -> Result<ProblemList> {
+        let mut transaction = self.begin().await?;
+
+        let list_id = sqlx::query_scalar::<_, String>(
+            r#"
+                INSERT INTO problem_lists (id, owner_id, name, description, is_public, created_at, updated_at)
+                VALUES (gen_random_uuid()::text, $1, $2, $3, $4, $5, $5)
+                RETURNING id
+            "#,
+        )
+        .bind(owner_id)
+        .bind(&content.name)
+        .bind(&content.description)
+        .bind(is_public)
+        .bind(now)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create a problem list")?;
+
+        insert_items(&mut transaction, &list_id, &content.items).await?;
+
+        transaction.commit().await?;
+
+        self.get_problem_list(&list_id)
+            .await?
+            .ok_or_else(|| ProblemListError::NotFound.into())
+    }
+
+    async fn update_problem_list(
+        &self,
+        list_id: &str,
+        name: &str,
+        description: Option<&str>,
+        is_public: bool,
+        now: i64,
+    ) -> Result<ProblemList> {
+        sqlx::query(
+            r#"
+                UPDATE problem_lists
+                SET name = $2, description = $3, is_public = $4, updated_at = $5
+                WHERE id = $1
+            "#,
+        )
+        .bind(list_id)
+        .bind(name)
+        .bind(description)
+        .bind(is_public)
+        .bind(now)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to update problem list {list_id}"))?;
+
+        self.get_problem_list(list_id)
+            .await?
+            .ok_or_else(|| ProblemListError::NotFound.into())
+    }
+
+    /// Replace the problems in the list, which also reorders them
+    ///
+    /// Fails with `ProblemListError::Invalid` if any of the problems does not exist.
+    async fn replace_problem_list_items(
+        &self,
+        list_id: &str,
+        items: &[ProblemListEntry],
+        now: i64,
+    ) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        sqlx::query("DELETE FROM problem_list_items WHERE list_id = $1")
+            .bind(list_id)
+            .execute(&mut *transaction)
+            .await
+            .with_context(|| format!("Failed to clear problem list {list_id}"))?;
+
+        insert_items(&mut transaction, list_id, items).await?;
+        touch(&mut transaction, list_id, now).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Add the problem to the end of the list, or update its note if it is already in the list
+    async fn upsert_problem_list_item(
+        &self,
+        list_id: &str,
+        item: &ProblemListEntry,
+        now: i64,
+    ) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        let exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM problems WHERE id = $1)")
+                .bind(&item.problem_id)
+                .fetch_one(&mut *transaction)
+                .await?;
+        if !exists {
+            return Err(ProblemListError::NotFound.into());
+        }
+
+        sqlx::query(
+            r#"
+                INSERT INTO problem_list_items (list_id, problem_id, position, note)
+                SELECT $1, $2, COALESCE(MAX(position) + 1, 0), $3
+                FROM problem_list_items
+                WHERE list_id = $1
+                ON CONFLICT (list_id, problem_id) DO UPDATE SET note = EXCLUDED.note
+            "#,
+        )
+        .bind(list_id)
+        .bind(&item.problem_id)
+        .bind(&item.note)
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Failed to add a problem to problem list {list_id}"))?;
+
+        touch(&mut transaction, list_id, now).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_problem_list(&self, list_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM problem_lists WHERE id = $1")
+            .bind(list_id)
+            .execute(self)
+            .await
+            .with_context(|| format!("Failed to delete problem list {list_id}"))?;
+
+        Ok(())
+    }
+}
+
+/// Insert the problems in their order after checking that all of them exist
+async fn insert_items(
+    transaction: &mut sqlx::Transaction<'_, Postgres>,
+    list_id: &str,
+    items: &[ProblemListEntry],
+) -> Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let problem_ids: Vec<&str> = items.iter().map(|item| item.problem_id.as_str()).collect();
+    let missing = sqlx::query_scalar::<_, String>(
+        r#"
+            SELECT requested.id
+            FROM UNNEST($1::TEXT[]) AS requested (id)
+            WHERE NOT EXISTS (SELECT 1 FROM problems WHERE problems.id = requested.id)
+        "#,
+    )
+    .bind(&problem_ids)
+    .fetch_all(&mut **transaction)
+    .await?;
+    if !missing.is_empty() {
+        return Err(
+            ProblemListError::Invalid(format!("Unknown problems: {}", missing.join(", "))).into(),
+        );
+    }
+
+    let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+        "INSERT INTO problem_list_items (list_id, problem_id, position, note)",
+    );
+    query_builder.push_values(
+        items.iter().enumerate(),
+        |mut separated, (position, item)| {
+            separated
+                .push_bind(list_id)
+                .push_bind(&item.problem_id)
+                .push_bind(position as i32)
+                .push_bind(&item.note);
+        },
+    );
+    query_builder
+        .build()
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update problem_list_items")?;
+
+    Ok(())
+}
+
+async fn touch(
+    transaction: &mut sqlx::Transaction<'_, Postgres>,
+    list_id: &str,
+    now: i64,
+) -> Result<()> {
+    sqlx::query("UPDATE problem_lists SET updated_at = $2 WHERE id = $1")
+        .bind(list_id)
+        .bind(now)
+        .execute(&mut **transaction)
+        .await
+        .with_context(|| format!("Failed to update problem list {list_id}"))?;
+
+    Ok(())
+}
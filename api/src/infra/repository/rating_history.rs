@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::domain::{rating_history::RatingChange, vo::platform::Platform};
+
+#[trait_variant::make]
+pub trait RatingHistoryRepository {
+    async fn get_rating_history(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<Vec<RatingChange>>;
+    async fn update_rating_history(&self, changes: &[RatingChange]) -> Result<()>;
+}
+
+impl RatingHistoryRepository for PgPool {
+    /// Get the stored rating changes of a user, oldest first
+    async fn get_rating_history(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<Vec<RatingChange>> {
+        let changes = sqlx::query_as::<_, RatingChange>(
+            r#"
+                SELECT
+                    contest_id, platform, user_id, contest_name, rank,
+                    old_rating, new_rating, performance, rated_at
+                FROM
+                    rating_history
+                WHERE
+                    platform = $1 AND user_id = $2
+                ORDER BY
+                    rated_at, contest_id
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(user_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the rating history of {user_id}"))?;
+
+        Ok(changes)
+    }
+
+    async fn update_rating_history(&self, changes: &[RatingChange]) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        for chunk in changes.chunks(1000) {
+            let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO rating_history (
+                    contest_id, platform, user_id, contest_name, rank,
+                    old_rating, new_rating, performance, rated_at
+                )
+                "#,
+            );
+
+            query_builder.push_values(chunk, |mut separated, change| {
+                separated
+                    .push_bind(&change.contest_id)
+                    .push_bind(String::from(change.platform))
+                    .push_bind(&change.user_id)
+                    .push_bind(&change.contest_name)
+                    .push_bind(change.rank)
+                    .push_bind(change.old_rating)
+                    .push_bind(change.new_rating)
+                    .push_bind(change.performance)
+                    .push_bind(change.rated_at);
+            });
+
+            query_builder.push(
+                r#"
+                ON CONFLICT (contest_id, user_id) DO UPDATE SET
+                    contest_name = EXCLUDED.contest_name,
+                    rank = EXCLUDED.rank,
+                    old_rating = EXCLUDED.old_rating,
+                    new_rating = EXCLUDED.new_rating,
+                    performance = EXCLUDED.performance,
+                    rated_at = EXCLUDED.rated_at
+                "#,
+            );
+
+            query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await
+                .context("Failed to update rating_history")?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
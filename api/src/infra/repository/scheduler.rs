@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+use crate::domain::{
+    scheduler::{JobRun, JobStatus, LOCK_TIMEOUT_SECONDS},
+    vo::platform::Platform,
+};
+
+#[trait_variant::make]
+pub trait SchedulerRepository {
+    async fn get_job_statuses(&self) -> Result<Vec<JobStatus>>;
+    async fn try_lock_job(&self, platform: &Platform, now: i64) -> Result<bool>;
+    async fn unlock_job(&self, platform: &Platform, run: &JobRun) -> Result<()>;
+}
+
+impl SchedulerRepository for PgPool {
+    /// Get the statuses of the platforms which have been updated at least once
+    async fn get_job_statuses(&self) -> Result<Vec<JobStatus>> {
+        let statuses = sqlx::query(
+            r#"
+                SELECT
+                    platform, running_since, last_started_at, last_finished_at,
+                    last_succeeded, last_error, problems, contests, skipped
+                FROM
+                    scheduler_jobs
+            "#,
+        )
+        .fetch_all(self)
+        .await
+        .context("Failed to fetch the statuses of the scheduled updates")?
+        .iter()
+        .map(build_job_status)
+        .collect();
+
+        Ok(statuses)
+    }
+
+    /// Take the lock of the platform
+    ///
+    /// `false` is returned if another run holds the lock.
+    /// A lock held longer than `LOCK_TIMEOUT_SECONDS` is taken over.
+    async fn try_lock_job(&self, platform: &Platform, now: i64) -> Result<bool> {
+        let locked = sqlx::query(
+            r#"
+                INSERT INTO scheduler_jobs (platform, running_since)
+                VALUES ($1, $2)
+                ON CONFLICT (platform) DO UPDATE SET
+                    running_since = EXCLUDED.running_since
+                WHERE
+                    scheduler_jobs.running_since IS NULL
+                    OR scheduler_jobs.running_since <= $3
+                RETURNING platform
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(now)
+        .bind(now - LOCK_TIMEOUT_SECONDS)
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to lock the update of {:?}", platform))?
+        .is_some();
+
+        Ok(locked)
+    }
+
+    /// Release the lock taken at `run.started_at` and record the result of the run
+    ///
+    /// Nothing is recorded if the lock has been taken over in the meantime.
+    async fn unlock_job(&self, platform: &Platform, run: &JobRun) -> Result<()> {
+        sqlx::query(
+            r#"
+                UPDATE scheduler_jobs SET
+                    running_since = NULL,
+                    last_started_at = $2,
+                    last_finished_at = $3,
+                    last_succeeded = $4,
+                    last_error = $5,
+                    problems = $6,
+                    contests = $7,
+                    skipped = $8
+                WHERE
+                    platform = $1 AND running_since = $2
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(run.started_at)
+        .bind(run.finished_at)
+        .bind(run.error.is_none())
+        .bind(&run.error)
+        .bind(run.problems)
+        .bind(run.contests)
+        .bind(run.skipped)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to unlock the update of {:?}", platform))?;
+
+        Ok(())
+    }
+}
+
+fn build_job_status(row: &PgRow) -> JobStatus {
+    JobStatus {
+        platform: Platform::from(row.get::<&str, _>("platform")),
+        running_since: row.get("running_since"),
+        last_started_at: row.get("last_started_at"),
+        last_finished_at: row.get("last_finished_at"),
+        last_succeeded: row.get("last_succeeded"),
+        last_error: row.get("last_error"),
+        problems: row.get("problems"),
+        contests: row.get("contests"),
+        skipped: row.get("skipped"),
+    }
+}
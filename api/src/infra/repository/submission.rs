@@ -0,0 +1,245 @@
+use anyhow::{Context, Result};
+use sqlx::{postgres::PgRow, PgPool, Postgres, QueryBuilder, Row};
+
+use crate::domain::{
+    submission::{ProblemInfo, Submission},
+    vo::{platform::Platform, verdict::Verdict},
+};
+
+/// Cheap aggregates of the stored submissions of a user, which change whenever they are updated.
+#[derive(Debug, PartialEq, sqlx::FromRow)]
+pub struct SubmissionSummary {
+    pub count: i64,
+    pub accepted: i64,
+    pub last_date: Option<i64>,
+}
+
+pub struct Condition<'a> {
+    pub platform: Platform,
+    /// The handle, which is matched regardless of the case.
+    pub user_id: &'a str,
+    pub from_second: Option<i64>,
+    /// The 0-based index of the page.
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+#[trait_variant::make]
+pub trait SubmissionRepository {
+    async fn get_submissions_by_condition(
+        &self,
+        condition: &Condition<'_>,
+    ) -> Result<Vec<Submission>>;
+    async fn get_last_submission_date(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<Option<u64>>;
+    async fn get_submission_summary(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<SubmissionSummary>;
+    async fn update_submissions(&self, submissions: &[Submission]) -> Result<()>;
+}
+
+impl SubmissionRepository for PgPool {
+    /// Get the stored submissions of a user, newest first
+    ///
+    /// If `page_size` is not provided, all the submissions matching the condition will be returned.
+    async fn get_submissions_by_condition(
+        &self,
+        condition: &Condition<'_>,
+    ) -> Result<Vec<Submission>> {
+        let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+            r#"
+                SELECT
+                    id, raw_id, user_id, language, raw_language, platform, verdict,
+                    execution_time, memory, code_size, submission_date,
+                    contest_id, problem_index, problem_name, raw_point, difficulty
+                FROM
+                    submissions
+                WHERE
+                    platform =
+                "#,
+        );
+
+        query_builder
+            .push_bind(String::from(condition.platform))
+            .push(" AND LOWER(user_id) = LOWER(")
+            .push_bind(condition.user_id)
+            .push(")");
+
+        if let Some(from_second) = condition.from_second {
+            query_builder
+                .push(" AND submission_date >= ")
+                .push_bind(from_second);
+        }
+
+        query_builder.push(" ORDER BY submission_date DESC, id DESC");
+
+        if let Some(page_size) = condition.page_size {
+            let offset = condition.page.unwrap_or(0).max(0) * page_size.max(0);
+
+            query_builder
+                .push(" LIMIT ")
+                .push_bind(page_size.max(0))
+                .push(" OFFSET ")
+                .push_bind(offset);
+        }
+
+        let rows = query_builder
+            .build()
+            .fetch_all(self)
+            .await
+            .with_context(|| format!("Failed to fetch submissions of {}", condition.user_id))?;
+
+        let submissions = rows.iter().map(build_submission).collect();
+
+        Ok(submissions)
+    }
+
+    /// Get the point from which the submissions of a user should be synchronized
+    ///
+    /// This is the date of the last stored submission.
+    /// Submissions still waiting for judge are regarded as not stored,
+    /// so that their verdicts are refreshed on the next synchronization.
+    /// `None` is returned if no submission of the user has been stored.
+    async fn get_last_submission_date(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<Option<u64>> {
+        let date: Option<i64> = sqlx::query_scalar(
+            r#"
+                SELECT
+                    COALESCE(
+                        MIN(submission_date) FILTER (WHERE verdict IN ('WJ', 'WR', 'TESTING')),
+                        MAX(submission_date)
+                    )
+                FROM
+                    submissions
+                WHERE
+                    platform = $1 AND LOWER(user_id) = LOWER($2)
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(user_id)
+        .fetch_one(self)
+        .await
+        .with_context(|| format!("Failed to fetch the last submission of {user_id}"))?;
+
+        Ok(date.map(|d| d as u64))
+    }
+
+    async fn get_submission_summary(
+        &self,
+        platform: &Platform,
+        user_id: &str,
+    ) -> Result<SubmissionSummary> {
+        let summary = sqlx::query_as::<_, SubmissionSummary>(
+            r#"
+                SELECT
+                    COUNT(*) AS count,
+                    COUNT(*) FILTER (WHERE verdict = 'AC') AS accepted,
+                    MAX(submission_date) AS last_date
+                FROM
+                    submissions
+                WHERE
+                    platform = $1 AND LOWER(user_id) = LOWER($2)
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(user_id)
+        .fetch_one(self)
+        .await
+        .with_context(|| format!("Failed to summarize the submissions of {user_id}"))?;
+
+        Ok(summary)
+    }
+
+    async fn update_submissions(&self, submissions: &[Submission]) -> Result<()> {
+        let mut transaction = self.begin().await?;
+
+        for chunk in submissions.chunks(100) {
+            let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+                r#"
+                INSERT INTO submissions (
+                    id, raw_id, user_id, language, raw_language, platform, verdict,
+                    execution_time, memory, code_size, submission_date,
+                    contest_id, problem_index, problem_name, raw_point, difficulty
+                )
+                "#,
+            );
+
+            query_builder.push_values(chunk, |mut separated, submission| {
+                separated
+                    .push_bind(&submission.id)
+                    .push_bind(&submission.raw_id)
+                    .push_bind(&submission.user_id)
+                    .push_bind(&submission.language)
+                    .push_bind(&submission.raw_language)
+                    .push_bind(String::from(submission.platform))
+                    .push_bind(String::from(submission.verdict.clone()))
+                    .push_bind(submission.execution_time.map(|v| v as i64))
+                    .push_bind(submission.memory.map(|v| v as i64))
+                    .push_bind(submission.code_size.map(|v| v as i64))
+                    .push_bind(submission.submission_date as i64)
+                    .push_bind(&submission.problem.contest_id)
+                    .push_bind(&submission.problem.index)
+                    .push_bind(&submission.problem.name)
+                    .push_bind(submission.problem.raw_point)
+                    .push_bind(submission.problem.difficulty);
+            });
+
+            query_builder.push(
+                r#"
+                ON CONFLICT (id) DO UPDATE SET
+                    language = EXCLUDED.language,
+                    raw_language = EXCLUDED.raw_language,
+                    verdict = EXCLUDED.verdict,
+                    execution_time = EXCLUDED.execution_time,
+                    memory = EXCLUDED.memory,
+                    code_size = EXCLUDED.code_size,
+                    problem_name = COALESCE(EXCLUDED.problem_name, submissions.problem_name),
+                    raw_point = COALESCE(EXCLUDED.raw_point, submissions.raw_point),
+                    difficulty = COALESCE(EXCLUDED.difficulty, submissions.difficulty)
+                "#,
+            );
+
+            let query = query_builder.build();
+            query
+                .execute(&mut *transaction)
+                .await
+                .with_context(|| "Failed to update submissions")?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+fn build_submission(row: &PgRow) -> Submission {
+    Submission::reconstruct_from_db(
+        row.get("id"),
+        row.get("raw_id"),
+        row.get("user_id"),
+        row.get("language"),
+        row.get("raw_language"),
+        Platform::from(row.get::<&str, _>("platform")),
+        Verdict::from(row.get::<&str, _>("verdict")),
+        row.get::<Option<i64>, _>("execution_time")
+            .map(|v| v as u64),
+        row.get::<Option<i64>, _>("memory").map(|v| v as u64),
+        row.get::<Option<i64>, _>("code_size").map(|v| v as u64),
+        row.get::<i64, _>("submission_date") as u64,
+        ProblemInfo {
+            contest_id: row.get("contest_id"),
+            index: row.get("problem_index"),
+            name: row.get("problem_name"),
+            raw_point: row.get("raw_point"),
+            difficulty: row.get("difficulty"),
+        },
+    )
+}
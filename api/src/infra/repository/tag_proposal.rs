@@ -0,0 +1,374 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::domain::{
+    tag_proposal::{TagProposal, TagProposalAction, TagProposalError, TagProposalEvent},
+    vo::proposal_status::ProposalStatus,
+};
+
+const SELECT_PROPOSALS: &str = r#"
+    SELECT
+        tag_proposals.id,
+        tag_proposals.problem_id,
+        tag_proposals.technical_tag_id,
+        technical_tags.en_name AS tag_en_name,
+        technical_tags.ja_name AS tag_ja_name,
+        tag_proposals.proposer_id,
+        tag_proposals.status,
+        COALESCE(
+            (
+                SELECT SUM(tag_proposal_votes.vote)
+                FROM tag_proposal_votes
+                WHERE tag_proposal_votes.proposal_id = tag_proposals.id
+            ),
+            0
+        )::BIGINT AS score,
+        tag_proposals.created_at,
+        tag_proposals.reviewer_id,
+        tag_proposals.reviewed_at
+    FROM
+        tag_proposals
+        JOIN technical_tags ON tag_proposals.technical_tag_id = technical_tags.id
+"#;
+
+#[trait_variant::make]
+pub trait TagProposalRepository {
+    async fn get_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+        problem_id: Option<&str>,
+    ) -> Result<Vec<TagProposal>>;
+    async fn get_proposal(&self, proposal_id: &str) -> Result<Option<TagProposal>>;
+    async fn create_proposal(
+        &self,
+        problem_id: &str,
+        technical_tag_id: &str,
+        proposer_id: &str,
+        now: i64,
+    ) -> Result<TagProposal>;
+    async fn vote_proposal(
+        &self,
+        proposal_id: &str,
+        user_id: &str,
+        is_up: bool,
+        now: i64,
+    ) -> Result<TagProposal>;
+    async fn review_proposal(
+        &self,
+        proposal_id: &str,
+        reviewer_id: &str,
+        approve: bool,
+        comment: Option<&str>,
+        now: i64,
+    ) -> Result<TagProposal>;
+    async fn get_proposal_events(&self, proposal_id: &str) -> Result<Vec<TagProposalEvent>>;
+}
+
+impl TagProposalRepository for PgPool {
+    /// Get the proposals, the newest first
+    async fn get_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+        problem_id: Option<&str>,
+    ) -> Result<Vec<TagProposal>> {
+        let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(SELECT_PROPOSALS);
+
+        query_builder.push(" WHERE TRUE");
+
+        if let Some(status) = status {
+            query_builder
+                .push(" AND tag_proposals.status = ")
+                .push_bind(String::from(status));
+        }
+
+        if let Some(problem_id) = problem_id {
+            query_builder
+                .push(" AND tag_proposals.problem_id = ")
+                .push_bind(problem_id);
+        }
+
+        query_builder.push(" ORDER BY tag_proposals.created_at DESC, tag_proposals.id");
+
+        let proposals = query_builder
+            .build_query_as::<TagProposal>()
+            .fetch_all(self)
+            .await
+            .context("Failed to fetch tag proposals")?;
+
+        Ok(proposals)
+    }
+
+    async fn get_proposal(&self, proposal_id: &str) -> Result<Option<TagProposal>> {
+        let proposal = sqlx::query_as::<_, TagProposal>(&format!(
+            "{SELECT_PROPOSALS} WHERE tag_proposals.id = $1"
+        ))
+        .bind(proposal_id)
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to fetch tag proposal {proposal_id}"))?;
+
+        Ok(proposal)
+    }
+
+    /// Propose the tag for the problem
+    ///
+    /// Fails with `TagProposalError::Duplicated` if the tag is already attached to the problem
+    /// or a proposal of it is pending.
+    async fn create_proposal(
+        &self,
+        problem_id: &str,
+        technical_tag_id: &str,
+        proposer_id: &str,
+        now: i64,
+    ) -> Result<TagProposal> {
+        let mut transaction = self.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+                SELECT
+                    EXISTS (SELECT 1 FROM problems WHERE id = $1)
+                    AND EXISTS (SELECT 1 FROM technical_tags WHERE id = $2)
+            "#,
+        )
+        .bind(problem_id)
+        .bind(technical_tag_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+        if !exists {
+            return Err(TagProposalError::NotFound.into());
+        }
+
+        let attached = sqlx::query_scalar::<_, bool>(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM problem_tags WHERE problem_id = $1 AND technical_tag_id = $2
+                )
+            "#,
+        )
+        .bind(problem_id)
+        .bind(technical_tag_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+        if attached {
+            return Err(TagProposalError::Duplicated.into());
+        }
+
+        let proposal_id = sqlx::query_scalar::<_, String>(
+            r#"
+                INSERT INTO tag_proposals (id, problem_id, technical_tag_id, proposer_id, created_at)
+                VALUES (gen_random_uuid()::text, $1, $2, $3, $4)
+                ON CONFLICT (problem_id, technical_tag_id) WHERE status = 'pending' DO NOTHING
+                RETURNING id
+            "#,
+        )
+        .bind(problem_id)
+        .bind(technical_tag_id)
+        .bind(proposer_id)
+        .bind(now)
+        .fetch_optional(&mut *transaction)
+        .await
+        .context("Failed to create a tag proposal")?
+        .ok_or(TagProposalError::Duplicated)?;
+
+        insert_event(
+            &mut transaction,
+            &proposal_id,
+            proposer_id,
+            TagProposalAction::Propose,
+            None,
+            now,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        self.get_proposal(&proposal_id)
+            .await?
+            .ok_or_else(|| TagProposalError::NotFound.into())
+    }
+
+    /// Vote up or down on the pending proposal, replacing the previous vote of the user
+    async fn vote_proposal(
+        &self,
+        proposal_id: &str,
+        user_id: &str,
+        is_up: bool,
+        now: i64,
+    ) -> Result<TagProposal> {
+        let mut transaction = self.begin().await?;
+
+        lock_pending_proposal(&mut transaction, proposal_id).await?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO tag_proposal_votes (proposal_id, user_id, vote)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (proposal_id, user_id) DO UPDATE SET vote = EXCLUDED.vote
+            "#,
+        )
+        .bind(proposal_id)
+        .bind(user_id)
+        .bind(if is_up { 1_i16 } else { -1_i16 })
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Failed to vote on tag proposal {proposal_id}"))?;
+
+        let action = if is_up {
+            TagProposalAction::VoteUp
+        } else {
+            TagProposalAction::VoteDown
+        };
+        insert_event(&mut transaction, proposal_id, user_id, action, None, now).await?;
+
+        transaction.commit().await?;
+
+        self.get_proposal(proposal_id)
+            .await?
+            .ok_or_else(|| TagProposalError::NotFound.into())
+    }
+
+    /// Approve or reject the pending proposal
+    ///
+    /// The tag is attached to the problem when the proposal is approved.
+    async fn review_proposal(
+        &self,
+        proposal_id: &str,
+        reviewer_id: &str,
+        approve: bool,
+        comment: Option<&str>,
+        now: i64,
+    ) -> Result<TagProposal> {
+        let mut transaction = self.begin().await?;
+
+        let (problem_id, technical_tag_id) =
+            lock_pending_proposal(&mut transaction, proposal_id).await?;
+
+        let status = if approve {
+            ProposalStatus::Approved
+        } else {
+            ProposalStatus::Rejected
+        };
+        sqlx::query(
+            r#"
+                UPDATE tag_proposals
+                SET status = $2, reviewer_id = $3, reviewed_at = $4
+                WHERE id = $1
+            "#,
+        )
+        .bind(proposal_id)
+        .bind(String::from(status))
+        .bind(reviewer_id)
+        .bind(now)
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Failed to review tag proposal {proposal_id}"))?;
+
+        if approve {
+            sqlx::query(
+                r#"
+                    INSERT INTO problem_tags (problem_id, technical_tag_id)
+                    VALUES ($1, $2)
+                    ON CONFLICT (problem_id, technical_tag_id) DO NOTHING
+                "#,
+            )
+            .bind(&problem_id)
+            .bind(&technical_tag_id)
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to update problem_tags")?;
+        }
+
+        let action = if approve {
+            TagProposalAction::Approve
+        } else {
+            TagProposalAction::Reject
+        };
+        insert_event(
+            &mut transaction,
+            proposal_id,
+            reviewer_id,
+            action,
+            comment,
+            now,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        self.get_proposal(proposal_id)
+            .await?
+            .ok_or_else(|| TagProposalError::NotFound.into())
+    }
+
+    /// Get the history of the proposal, the oldest first
+    async fn get_proposal_events(&self, proposal_id: &str) -> Result<Vec<TagProposalEvent>> {
+        let events = sqlx::query_as::<_, TagProposalEvent>(
+            r#"
+                SELECT id, proposal_id, actor_id, action, comment, created_at
+                FROM tag_proposal_events
+                WHERE proposal_id = $1
+                ORDER BY id
+            "#,
+        )
+        .bind(proposal_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the history of tag proposal {proposal_id}"))?;
+
+        Ok(events)
+    }
+}
+
+/// Lock the proposal until the end of the transaction and return its (problem ID, technical tag ID)
+///
+/// Fails if the proposal does not exist or has already been reviewed.
+async fn lock_pending_proposal(
+    transaction: &mut sqlx::Transaction<'_, Postgres>,
+    proposal_id: &str,
+) -> Result<(String, String)> {
+    let (problem_id, technical_tag_id, status) =
+        sqlx::query_as::<_, (String, String, ProposalStatus)>(
+            r#"
+            SELECT problem_id, technical_tag_id, status
+            FROM tag_proposals
+            WHERE id = $1
+            FOR UPDATE
+        "#,
+        )
+        .bind(proposal_id)
+        .fetch_optional(&mut **transaction)
+        .await?
+        .ok_or(TagProposalError::NotFound)?;
+
+    if status != ProposalStatus::Pending {
+        return Err(TagProposalError::AlreadyReviewed.into());
+    }
+
+    Ok((problem_id, technical_tag_id))
+}
+
+async fn insert_event(
+    transaction: &mut sqlx::Transaction<'_, Postgres>,
+    proposal_id: &str,
+    actor_id: &str,
+    action: TagProposalAction,
+    comment: Option<&str>,
+    now: i64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+            INSERT INTO tag_proposal_events (proposal_id, actor_id, action, comment, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(proposal_id)
+    .bind(actor_id)
+    .bind(String::from(action))
+    .bind(comment)
+    .bind(now)
+    .execute(&mut **transaction)
+    .await
+    .with_context(|| format!("Failed to record the history of tag proposal {proposal_id}"))?;
+
+    Ok(())
+}
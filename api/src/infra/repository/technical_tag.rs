@@ -1,17 +1,32 @@
 use anyhow::Result;
 use sqlx::PgPool;
 
-use crate::domain::vo::technique_tag::TechnicalTag;
+use crate::domain::{
+    taxonomy::TagProblemCount,
+    vo::technique_tag::{Algorithm, TechnicalTag},
+};
 
 #[trait_variant::make]
 pub trait TechnicalTagRepositoryTrait {
+    async fn get_algorithms(&self) -> Result<Vec<Algorithm>>;
     async fn get_tags(&self, algo_id: Option<&str>) -> Result<Vec<TechnicalTag>>;
-    async fn create_tag(&self, en_name: &str, ja_name: &str, algorithm_id: &str) -> Result<()>;
+    async fn get_tag_problem_counts(&self) -> Result<Vec<TagProblemCount>>;
+    async fn create_tag(&self, en_name: &str, ja_name: &str, algorithm_id: &str) -> Result<String>;
 }
 
 // In Rust, we can implement a trait for a type that we don't own.
 // So, we do not need to construct a new struct (i.e. TechnicalTagRepository) to implement the trait.
 impl TechnicalTagRepositoryTrait for PgPool {
+    async fn get_algorithms(&self) -> Result<Vec<Algorithm>> {
+        let algorithms = sqlx::query_as::<_, Algorithm>(
+            "SELECT id, name, ja_name FROM algorithms ORDER BY id::INT",
+        )
+        .fetch_all(self)
+        .await?;
+
+        Ok(algorithms)
+    }
+
     /// Get all tags or tags for a specific algorithm
     ///
     /// If `algo_id` is provided, only tags for that algorithm will be returned.
@@ -20,10 +35,11 @@ impl TechnicalTagRepositoryTrait for PgPool {
         let tags = match algo_id {
             Some(algo_id) => {
                 let query = r#"
-                    SELECT t.id, t.en_name, t.ja_name, a.name as algorithm_name
+                    SELECT t.id, t.en_name, t.ja_name, t.algorithm_id, a.name as algorithm_name
                     FROM technical_tags t
                     JOIN algorithms a ON t.algorithm_id = a.id
                     WHERE a.id = $1
+                    ORDER BY t.id::INT
                 "#;
 
                 let tags = sqlx::query_as::<_, TechnicalTag>(&query)
@@ -35,9 +51,10 @@ impl TechnicalTagRepositoryTrait for PgPool {
             }
             None => {
                 let query = r#"
-                    SELECT t.id, t.en_name, t.ja_name, a.name as algorithm_name
+                    SELECT t.id, t.en_name, t.ja_name, t.algorithm_id, a.name as algorithm_name
                     FROM technical_tags t
                     JOIN algorithms a ON t.algorithm_id = a.id
+                    ORDER BY t.id::INT
                 "#;
 
                 let tags = sqlx::query_as::<_, TechnicalTag>(&query)
@@ -48,30 +65,47 @@ impl TechnicalTagRepositoryTrait for PgPool {
             }
         };
 
-        for tag in &tags {
-            println!("{:?}", tag);
-        }
-
         Ok(tags)
     }
 
-    /// Create a new tag
+    /// Count the problems with each tag on each platform
+    ///
+    /// Pairs of a tag and a platform without any problem are not returned.
+    async fn get_tag_problem_counts(&self) -> Result<Vec<TagProblemCount>> {
+        let query = r#"
+            SELECT problem_tags.technical_tag_id, problems.platform, COUNT(*) AS count
+            FROM problem_tags
+            JOIN problems ON problem_tags.problem_id = problems.id
+            GROUP BY problem_tags.technical_tag_id, problems.platform
+        "#;
+
+        let counts = sqlx::query_as::<_, TagProblemCount>(query)
+            .fetch_all(self)
+            .await?;
+
+        Ok(counts)
+    }
+
+    /// Create a new tag and return its ID
     ///
     /// Generally, tags should be complete within the Technical Tag database.
     /// However, it should be possible to add new tags for certain cases.
-    async fn create_tag(&self, en_name: &str, ja_name: &str, algorithm_id: &str) -> Result<()> {
+    ///
+    /// The IDs of the tags are sequential numbers, so the next one is taken from `technical_tags_id_seq`.
+    async fn create_tag(&self, en_name: &str, ja_name: &str, algorithm_id: &str) -> Result<String> {
         let query = r#"
             INSERT INTO technical_tags (id, en_name, ja_name, algorithm_id)
-            VALUES ($1, $2, $3, $4)
+            VALUES (nextval('technical_tags_id_seq')::TEXT, $1, $2, $3)
+            RETURNING id
         "#;
 
-        sqlx::query(&query)
+        let id = sqlx::query_scalar::<_, String>(query)
             .bind(en_name)
             .bind(ja_name)
             .bind(algorithm_id)
-            .execute(self)
+            .fetch_one(self)
             .await?;
 
-        Ok(())
+        Ok(id)
     }
 }
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use sqlx::{PgPool, Postgres};
 
-use crate::domain::{user::User, vo::providers::AuthProvider};
+use crate::domain::{
+    user::User,
+    vo::{platform::Platform, providers::AuthProvider, user_role::UserRole},
+};
 
 #[trait_variant::make]
 pub trait UserRepository {
@@ -11,12 +14,19 @@ pub trait UserRepository {
         user_id: &str,
     ) -> Result<Option<User>>;
     async fn find_by_user_id(&self, user_id: &str) -> Result<User>;
-    // async fn create_or_update(
-    //     &self,
-    //     provider: &AuthProvider,
-    //     user_id: &str,
-    //     user_name: &str,
-    // ) -> Result<()>;
+    async fn update_platform_username(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        username: Option<&str>,
+    ) -> Result<User>;
+    async fn update_role(&self, user_id: &str, role: UserRole) -> Result<User>;
+    async fn create_or_update(
+        &self,
+        provider: &AuthProvider,
+        user_id: &str,
+        user_name: Option<&str>,
+    ) -> Result<User>;
 }
 
 impl UserRepository for PgPool {
@@ -25,48 +35,19 @@ impl UserRepository for PgPool {
         provider: &AuthProvider,
         user_id: &str,
     ) -> Result<Option<User>> {
-        match provider {
-            AuthProvider::Google => {
-                let user = sqlx::query_as::<Postgres, User>(
-                    r"SELECT * FROM internal_users WHERE google_id = $1",
-                )
-                .bind(user_id)
-                .fetch_one(self)
-                .await?;
-
-                return Ok(Some(user));
+        let column = match provider {
+            AuthProvider::Google => "google_id",
+            AuthProvider::Github => "github_id",
+        };
 
-                // if user.github_id.is_none() {
-                //     sqlx::query(
-                //         r"INSERT INTO internal_users (google_id, google_email) VALUES ($1, $2)",
-                //     )
-                //     .bind(user_id)
-                //     .bind(user_name)
-                //     .execute(&mut *transaction)
-                //     .await?;
-                // }
-            }
-            AuthProvider::Github => {
-                let user = sqlx::query_as::<Postgres, User>(
-                    r"SELECT * FROM internal_users WHERE github_id = $1",
-                )
-                .bind(user_id)
-                .fetch_one(self)
-                .await?;
-
-                return Ok(Some(user));
+        let user = sqlx::query_as::<Postgres, User>(&format!(
+            r"SELECT * FROM internal_users WHERE {column} = $1"
+        ))
+        .bind(user_id)
+        .fetch_optional(self)
+        .await?;
 
-                // if user.github_id.is_none() {
-                //     sqlx::query(
-                //         r"INSERT INTO internal_users (github_id, github_username) VALUES ($1, $2)",
-                //     )
-                //     .bind(user_id)
-                //     .bind(user_name)
-                //     .execute(&mut *transaction)
-                //     .await?;
-                // }
-            }
-        }
+        Ok(user)
     }
 
     async fn find_by_user_id(&self, user_id: &str) -> Result<User> {
@@ -83,11 +64,79 @@ impl UserRepository for PgPool {
         Ok(user)
     }
 
-    // async fn create_or_update(
-    //     &self,
-    //     provider: &AuthProvider,
-    //     user_id: &str,
-    //     user_name: &str,
-    // ) -> Result<()> {
-    // }
+    /// Link (or unlink if `username` is `None`) the competitive programming account of the user
+    async fn update_platform_username(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        username: Option<&str>,
+    ) -> Result<User> {
+        let column = match platform {
+            Platform::Atcoder => "atcoder_username",
+            Platform::Codeforces => "codeforces_username",
+            Platform::Yukicoder => "yukicoder_username",
+            Platform::Aoj => "aoj_username",
+            Platform::YOJ => "yoj_username",
+        };
+
+        let user = sqlx::query_as::<Postgres, User>(&format!(
+            r"
+            UPDATE internal_users SET {column} = $1
+            WHERE id = $2
+            RETURNING *
+            "
+        ))
+        .bind(username)
+        .bind(user_id)
+        .fetch_one(self)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Grant the role to the user, in place of the current one
+    async fn update_role(&self, user_id: &str, role: UserRole) -> Result<User> {
+        let user = sqlx::query_as::<Postgres, User>(
+            r"
+            UPDATE internal_users SET role = $1
+            WHERE id = $2
+            RETURNING *
+            ",
+        )
+        .bind(String::from(role))
+        .bind(user_id)
+        .fetch_one(self)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Create the internal user for the external account on the first login,
+    /// or refresh the account name stored for it on later logins
+    async fn create_or_update(
+        &self,
+        provider: &AuthProvider,
+        user_id: &str,
+        user_name: Option<&str>,
+    ) -> Result<User> {
+        let (id_column, name_column) = match provider {
+            AuthProvider::Google => ("google_id", "google_email"),
+            AuthProvider::Github => ("github_id", "github_username"),
+        };
+
+        let user = sqlx::query_as::<Postgres, User>(&format!(
+            r"
+            INSERT INTO internal_users (id, {id_column}, {name_column})
+            VALUES (gen_random_uuid()::text, $1, $2)
+            ON CONFLICT ({id_column}) DO UPDATE SET {name_column} = EXCLUDED.{name_column}
+            RETURNING *
+            "
+        ))
+        .bind(user_id)
+        .bind(user_name)
+        .fetch_one(self)
+        .await?;
+
+        Ok(user)
+    }
 }
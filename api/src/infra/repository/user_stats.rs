@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+
+use crate::domain::user_stats::CachedUserStats;
+
+#[trait_variant::make]
+pub trait UserStatsRepository {
+    async fn get_cached_user_stats(&self, user_id: &str) -> Result<Option<CachedUserStats>>;
+    async fn save_user_stats(&self, user_id: &str, stats: &CachedUserStats, now: i64)
+        -> Result<()>;
+}
+
+impl UserStatsRepository for PgPool {
+    async fn get_cached_user_stats(&self, user_id: &str) -> Result<Option<CachedUserStats>> {
+        let stats = sqlx::query_as::<_, CachedUserStats>(
+            "SELECT fingerprint, stats FROM user_stats WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to fetch the statistics of {user_id}"))?;
+
+        Ok(stats)
+    }
+
+    async fn save_user_stats(
+        &self,
+        user_id: &str,
+        stats: &CachedUserStats,
+        now: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+                INSERT INTO user_stats (user_id, fingerprint, stats, computed_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (user_id) DO UPDATE SET
+                    fingerprint = EXCLUDED.fingerprint,
+                    stats = EXCLUDED.stats,
+                    computed_at = EXCLUDED.computed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&stats.fingerprint)
+        .bind(&stats.stats)
+        .bind(now)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to save the statistics of {user_id}"))?;
+
+        Ok(())
+    }
+}
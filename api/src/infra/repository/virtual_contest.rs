@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+use crate::domain::{
+    problem::Problem,
+    virtual_contest::{VirtualContest, VirtualContestError},
+    vo::scoring_rule::ScoringRule,
+};
+
+const SELECT_VIRTUAL_CONTESTS: &str = r#"
+    SELECT
+        virtual_contests.id,
+        virtual_contests.title,
+        virtual_contests.owner_id,
+        virtual_contests.start_time,
+        virtual_contests.duration,
+        virtual_contests.scoring,
+        ARRAY (
+            SELECT virtual_contest_problems.problem_id
+            FROM virtual_contest_problems
+            WHERE virtual_contest_problems.contest_id = virtual_contests.id
+            ORDER BY virtual_contest_problems.position
+        ) AS problem_ids,
+        ARRAY (
+            SELECT virtual_contest_participants.user_id
+            FROM virtual_contest_participants
+            WHERE virtual_contest_participants.contest_id = virtual_contests.id
+            ORDER BY virtual_contest_participants.user_id
+        ) AS participant_ids,
+        virtual_contests.created_at
+    FROM
+        virtual_contests
+"#;
+
+pub struct NewVirtualContest<'a> {
+    pub title: &'a str,
+    pub owner_id: &'a str,
+    pub start_time: i64,
+    pub duration: i64,
+    pub scoring: ScoringRule,
+    /// In the order shown in the standings.
+    pub problem_ids: &'a [String],
+    /// The owner takes part even if not included.
+    pub participant_ids: &'a [String],
+}
+
+#[trait_variant::make]
+pub trait VirtualContestRepository {
+    async fn get_virtual_contests(&self, user_id: &str) -> Result<Vec<VirtualContest>>;
+    async fn get_virtual_contest(&self, contest_id: &str) -> Result<Option<VirtualContest>>;
+    async fn get_virtual_contest_problems(&self, contest_id: &str) -> Result<Vec<Problem>>;
+    async fn create_virtual_contest(
+        &self,
+        contest: &NewVirtualContest<'_>,
+        now: i64,
+    ) -> Result<VirtualContest>;
+    async fn add_participant(&self, contest_id: &str, user_id: &str) -> Result<()>;
+    async fn remove_participant(&self, contest_id: &str, user_id: &str) -> Result<()>;
+}
+
+impl VirtualContestRepository for PgPool {
+    /// Get the contests which the user owns or takes part in, the latest start first
+    async fn get_virtual_contests(&self, user_id: &str) -> Result<Vec<VirtualContest>> {
+        let contests = sqlx::query_as::<_, VirtualContest>(&format!(
+            r#"
+                {SELECT_VIRTUAL_CONTESTS}
+                WHERE
+                    virtual_contests.owner_id = $1
+                    OR EXISTS (
+                        SELECT 1 FROM virtual_contest_participants
+                        WHERE contest_id = virtual_contests.id AND user_id = $1
+                    )
+                ORDER BY virtual_contests.start_time DESC, virtual_contests.id
+            "#
+        ))
+        .bind(user_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the virtual contests of {user_id}"))?;
+
+        Ok(contests)
+    }
+
+    async fn get_virtual_contest(&self, contest_id: &str) -> Result<Option<VirtualContest>> {
+        let contest = sqlx::query_as::<_, VirtualContest>(&format!(
+            "{SELECT_VIRTUAL_CONTESTS} WHERE virtual_contests.id = $1"
+        ))
+        .bind(contest_id)
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to fetch virtual contest {contest_id}"))?;
+
+        Ok(contest)
+    }
+
+    /// Get the problems of the contest in their order
+    async fn get_virtual_contest_problems(&self, contest_id: &str) -> Result<Vec<Problem>> {
+        let problems = sqlx::query_as::<_, Problem>(
+            r#"
+                SELECT
+                    problems.id,
+                    problems.contest_id,
+                    problems.contest_name,
+                    problems.problem_index AS index,
+                    problems.name,
+                    problems.title,
+                    problems.platform,
+                    problems.raw_point,
+                    problems.difficulty,
+                    problems.category,
+                    problems.is_experimental,
+                    problems.difficulty_confidence,
+                    problems.url,
+                    problems.solver_count,
+                    problems.submissions,
+                    problems.success_rate,
+                    ARRAY_REMOVE (ARRAY_AGG (technical_tags.en_name), NULL) AS tags
+                FROM
+                    virtual_contest_problems
+                    JOIN problems ON virtual_contest_problems.problem_id = problems.id
+                    LEFT JOIN problem_tags ON problems.id = problem_tags.problem_id
+                    LEFT JOIN technical_tags ON problem_tags.technical_tag_id = technical_tags.id
+                WHERE
+                    virtual_contest_problems.contest_id = $1
+                GROUP BY
+                    problems.id, virtual_contest_problems.position
+                ORDER BY
+                    virtual_contest_problems.position
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_all(self)
+        .await
+        .with_context(|| format!("Failed to fetch the problems of virtual contest {contest_id}"))?;
+
+        Ok(problems)
+    }
+
+    /// Create the contest with its problems and participants
+    ///
+    /// Fails with `VirtualContestError::NotFound` if any of the problems or participants does not exist.
+    async fn create_virtual_contest(
+        &self,
+        contest: &NewVirtualContest<'_>,
+        now: i64,
+    ) -> Result<VirtualContest> {
+        let mut transaction = self.begin().await?;
+
+        let mut participant_ids = vec![contest.owner_id.to_string()];
+        participant_ids.extend(
+            contest
+                .participant_ids
+                .iter()
+                .filter(|id| id.as_str() != contest.owner_id)
+                .cloned(),
+        );
+
+        let found = sqlx::query_scalar::<_, bool>(
+            r#"
+                SELECT
+                    (SELECT COUNT(*) FROM problems WHERE id = ANY($1)) = CARDINALITY($1)
+                    AND (SELECT COUNT(*) FROM internal_users WHERE id = ANY($2)) = CARDINALITY($2)
+            "#,
+        )
+        .bind(contest.problem_ids)
+        .bind(&participant_ids)
+        .fetch_one(&mut *transaction)
+        .await?;
+        if !found {
+            return Err(VirtualContestError::NotFound.into());
+        }
+
+        let contest_id = sqlx::query_scalar::<_, String>(
+            r#"
+                INSERT INTO virtual_contests (id, title, owner_id, start_time, duration, scoring, created_at)
+                VALUES (gen_random_uuid()::text, $1, $2, $3, $4, $5, $6)
+                RETURNING id
+            "#,
+        )
+        .bind(contest.title)
+        .bind(contest.owner_id)
+        .bind(contest.start_time)
+        .bind(contest.duration)
+        .bind(String::from(contest.scoring))
+        .bind(now)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to create a virtual contest")?;
+
+        let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+            "INSERT INTO virtual_contest_problems (contest_id, problem_id, position)",
+        );
+        query_builder.push_values(
+            contest.problem_ids.iter().enumerate(),
+            |mut separated, (position, problem_id)| {
+                separated
+                    .push_bind(&contest_id)
+                    .push_bind(problem_id)
+                    .push_bind(position as i32);
+            },
+        );
+        query_builder
+            .build()
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to update virtual_contest_problems")?;
+
+        let mut query_builder: QueryBuilder<Postgres> = sqlx::QueryBuilder::new(
+            "INSERT INTO virtual_contest_participants (contest_id, user_id)",
+        );
+        query_builder.push_values(&participant_ids, |mut separated, user_id| {
+            separated.push_bind(&contest_id).push_bind(user_id);
+        });
+        query_builder
+            .build()
+            .execute(&mut *transaction)
+            .await
+            .context("Failed to update virtual_contest_participants")?;
+
+        transaction.commit().await?;
+
+        self.get_virtual_contest(&contest_id)
+            .await?
+            .ok_or_else(|| VirtualContestError::NotFound.into())
+    }
+
+    /// Fails with `VirtualContestError::NotFound` if the user does not exist.
+    async fn add_participant(&self, contest_id: &str, user_id: &str) -> Result<()> {
+        let inserted = sqlx::query(
+            r#"
+                INSERT INTO virtual_contest_participants (contest_id, user_id)
+                SELECT $1, id FROM internal_users WHERE id = $2
+                ON CONFLICT (contest_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(contest_id)
+        .bind(user_id)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to add a participant to virtual contest {contest_id}"))?
+        .rows_affected();
+
+        if inserted == 0 {
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM internal_users WHERE id = $1)",
+            )
+            .bind(user_id)
+            .fetch_one(self)
+            .await?;
+            if !exists {
+                return Err(VirtualContestError::NotFound.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_participant(&self, contest_id: &str, user_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+                DELETE FROM virtual_contest_participants
+                WHERE contest_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(contest_id)
+        .bind(user_id)
+        .execute(self)
+        .await
+        .with_context(|| {
+            format!("Failed to remove a participant from virtual contest {contest_id}")
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+
+use crate::domain::vo::{platform::Platform, watermark::Watermark};
+
+#[trait_variant::make]
+pub trait WatermarkRepository {
+    async fn get_watermark(&self, platform: &Platform) -> Result<Option<Watermark>>;
+    async fn update_watermark(&self, platform: &Platform, watermark: &Watermark) -> Result<()>;
+}
+
+impl WatermarkRepository for PgPool {
+    /// Get the crawl watermark of the platform
+    ///
+    /// `None` is returned if the platform has never been crawled incrementally.
+    async fn get_watermark(&self, platform: &Platform) -> Result<Option<Watermark>> {
+        let watermark = sqlx::query_as::<_, Watermark>(
+            r#"
+                SELECT last_seconds AS seconds, last_id AS id
+                FROM crawl_watermarks
+                WHERE platform = $1
+            "#,
+        )
+        .bind(String::from(*platform))
+        .fetch_optional(self)
+        .await
+        .with_context(|| format!("Failed to fetch watermark of {:?}", platform))?;
+
+        Ok(watermark)
+    }
+
+    /// Advance the crawl watermark of the platform
+    ///
+    /// This should be called only after the items up to `watermark` have been stored,
+    /// so that a crashed crawl can resume from the last stored item.
+    async fn update_watermark(&self, platform: &Platform, watermark: &Watermark) -> Result<()> {
+        sqlx::query(
+            r#"
+                INSERT INTO crawl_watermarks (platform, last_seconds, last_id, updated_at)
+                VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
+                ON CONFLICT (platform) DO UPDATE SET
+                    last_seconds = EXCLUDED.last_seconds,
+                    last_id = EXCLUDED.last_id,
+                    updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(String::from(*platform))
+        .bind(watermark.seconds)
+        .bind(watermark.id)
+        .execute(self)
+        .await
+        .with_context(|| format!("Failed to update watermark of {:?}", platform))?;
+
+        Ok(())
+    }
+}
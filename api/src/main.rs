@@ -8,13 +8,24 @@ use api::middleware::AuthMiddleware;
 use api::{
     config::CONFIG,
     controller::{
-        auth::AuthController, contest::ContestController, problem::ProblemController,
-        services::config_services, submission::SubmissionController,
+        auth::AuthController, contest::ContestController, ingestion_run::IngestionRunController,
+        problem::ProblemController, problem_list::ProblemListController,
+        rating_history::RatingHistoryController, recommendation::RecommendationController,
+        scheduler::SchedulerController, services::config_services,
+        submission::SubmissionController, tag::TagController, tag_proposal::TagProposalController,
+        user::UserController, user_stats::UserStatsController,
+        virtual_contest::VirtualContestController,
+    },
+    infra::{
+        api::api_client::ApiClient,
+        repository::initialize_pool::{initialize_pool, run_migrations},
     },
-    infra::{api::api_client::ApiClient, repository::initialize_pool::initialize_pool},
     service::{
         auth::AuthUsecase, contest::FetchContestUsecase, problem::FetchProblemUsecase,
-        submission::FetchSubmissionUsecase,
+        problem_list::ProblemListUsecase, rating_history::RatingHistoryUsecase,
+        recommendation::RecommendationUsecase, scheduler::SchedulerUsecase,
+        submission::FetchSubmissionUsecase, tag::FetchTagUsecase, tag_proposal::TagProposalUsecase,
+        user::UserUsecase, user_stats::UserStatsUsecase, virtual_contest::VirtualContestUsecase,
     },
 };
 
@@ -26,21 +37,78 @@ async fn main() -> Result<()> {
     let pool = initialize_pool(&CONFIG.database_url)
         .await
         .expect("Failed to initialize the connection pool");
+    run_migrations(&pool)
+        .await
+        .expect("Failed to apply the migrations");
 
-    let api_client = ApiClient::new();
+    let api_client = ApiClient::new(&CONFIG.http);
 
-    let sub_usecase = Arc::new(FetchSubmissionUsecase::new(api_client));
+    let sub_usecase = Arc::new(FetchSubmissionUsecase::new(
+        api_client.clone(),
+        pool.clone(),
+    ));
     let sub_controller = Arc::new(SubmissionController::new(sub_usecase.clone()));
 
-    let problem_usecase = Arc::new(FetchProblemUsecase::new(pool.clone()));
+    let problem_usecase = Arc::new(FetchProblemUsecase::new(pool.clone(), sub_usecase.clone()));
     let problem_controller = Arc::new(ProblemController::new(problem_usecase.clone()));
 
     let contest_usecase = Arc::new(FetchContestUsecase::new(pool.clone()));
     let contest_controller = Arc::new(ContestController::new(contest_usecase.clone()));
 
-    // let oidc_client = OidcClient::new().await?;
-    // let auth_usecase = Arc::new(AuthUsecase::new(oidc_client, pool.clone()));
-    // let auth_controller = Arc::new(AuthController::new(auth_usecase.clone()));
+    let user_usecase = Arc::new(UserUsecase::new(
+        api_client.clone(),
+        pool.clone(),
+        sub_usecase.clone(),
+    ));
+    let user_controller = Arc::new(UserController::new(user_usecase.clone()));
+
+    let oidc_client = OidcClient::new(api_client.client.clone()).await?;
+    let auth_usecase = Arc::new(AuthUsecase::new(oidc_client, pool.clone()));
+    let auth_controller = Arc::new(AuthController::new(auth_usecase.clone()));
+
+    let tag_proposal_usecase = Arc::new(TagProposalUsecase::new(pool.clone()));
+    let tag_proposal_controller =
+        Arc::new(TagProposalController::new(tag_proposal_usecase.clone()));
+
+    let tag_usecase = Arc::new(FetchTagUsecase::new(pool.clone()));
+    let tag_controller = Arc::new(TagController::new(tag_usecase.clone()));
+
+    let virtual_contest_usecase = Arc::new(VirtualContestUsecase::new(
+        pool.clone(),
+        sub_usecase.clone(),
+    ));
+    let virtual_contest_controller = Arc::new(VirtualContestController::new(
+        virtual_contest_usecase.clone(),
+    ));
+
+    let problem_list_usecase =
+        Arc::new(ProblemListUsecase::new(pool.clone(), user_usecase.clone()));
+    let problem_list_controller =
+        Arc::new(ProblemListController::new(problem_list_usecase.clone()));
+
+    let recommendation_usecase = Arc::new(RecommendationUsecase::new(
+        pool.clone(),
+        user_usecase.clone(),
+    ));
+    let recommendation_controller = Arc::new(RecommendationController::new(
+        recommendation_usecase.clone(),
+    ));
+
+    let user_stats_usecase = Arc::new(UserStatsUsecase::new(pool.clone()));
+    let user_stats_controller = Arc::new(UserStatsController::new(user_stats_usecase.clone()));
+
+    let rating_history_usecase =
+        Arc::new(RatingHistoryUsecase::new(api_client.clone(), pool.clone()));
+    let rating_history_controller =
+        Arc::new(RatingHistoryController::new(rating_history_usecase.clone()));
+
+    let scheduler_usecase = Arc::new(SchedulerUsecase::new(
+        Arc::new(api_client.clone()),
+        Arc::new(pool.clone()),
+    ));
+    scheduler_usecase.fail_stale_runs().await?;
+    let scheduler_controller = Arc::new(SchedulerController::new(scheduler_usecase.clone()));
+    let ingestion_run_controller = Arc::new(IngestionRunController::new(scheduler_usecase.clone()));
 
     HttpServer::new(move || {
         App::new()
@@ -48,7 +116,7 @@ async fn main() -> Result<()> {
             .wrap(
                 Cors::default()
                     .allowed_origin("http://localhost:3000")
-                    .allowed_methods(vec!["GET", "POST"])
+                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
                     .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])
                     .allowed_header(header::CONTENT_TYPE)
                     .supports_credentials()
@@ -60,7 +128,17 @@ async fn main() -> Result<()> {
                     sub_controller.clone(),
                     problem_controller.clone(),
                     contest_controller.clone(),
-                    // auth_controller.clone(),
+                    user_controller.clone(),
+                    auth_controller.clone(),
+                    tag_proposal_controller.clone(),
+                    tag_controller.clone(),
+                    virtual_contest_controller.clone(),
+                    problem_list_controller.clone(),
+                    recommendation_controller.clone(),
+                    user_stats_controller.clone(),
+                    rating_history_controller.clone(),
+                    scheduler_controller.clone(),
+                    ingestion_run_controller.clone(),
                 )
             })
     })
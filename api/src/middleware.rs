@@ -4,7 +4,7 @@ use std::{
 };
 
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::Error;
+use actix_web::{Error, HttpMessage};
 
 use crate::config::CONFIG;
 use crate::utils::cookie;
@@ -14,6 +14,14 @@ type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T> + 'static>>;
 
 pub struct AuthMiddleware;
 
+/// The user authenticated by `AuthMiddleware`.
+///
+/// Handlers under `/api/internal` can extract it with `web::ReqData<AuthenticatedUser>`.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser {
+    pub id: String,
+}
+
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
@@ -51,7 +59,9 @@ where
         if req.path().starts_with("/api/internal") {
             match cookie::get_cookie_value(&req.request(), &CONFIG.jwt_cookie_key) {
                 Some(jwt) => {
-                    if jwt::decode_jwt(&CONFIG.jwt_secret, &jwt).is_ok() {
+                    if let Ok(user_id) = jwt::decode_jwt(&CONFIG.jwt_secret, &jwt) {
+                        req.extensions_mut()
+                            .insert(AuthenticatedUser { id: user_id });
                         return Box::pin(self.service.call(req));
                     } else {
                         return Box::pin(async {
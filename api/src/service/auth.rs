@@ -1,13 +1,20 @@
 use anyhow::{Context, Result};
 
-use crate::domain::user::User;
+use crate::domain::auth_session::AuthSession;
+use crate::domain::user::{PublicUser, User};
 use crate::domain::vo::providers::AuthProvider;
-use crate::infra::{oidc::client::OidcClientTrait, repository::user::UserRepository};
+use crate::infra::{
+    oidc::client::OidcClientTrait,
+    repository::{auth_session::AuthSessionRepository, user::UserRepository},
+};
+
+/// How long a login may take between the redirect to the provider and the callback
+pub const SESSION_TTL_SECONDS: i64 = 10 * 60;
 
 pub struct AuthUsecase<C, R>
 where
     C: OidcClientTrait,
-    R: UserRepository,
+    R: UserRepository + AuthSessionRepository,
 {
     oidc_client: C,
     repository: R,
@@ -15,15 +22,20 @@ where
 
 #[trait_variant::make]
 pub trait Authenticate {
-    async fn get_authenticate_url(&self, provider: &AuthProvider) -> Result<String>;
-    async fn handle_callback(&self, provider: &AuthProvider, code: &str) -> Result<User>;
-    async fn get_user_info(&self, user_id: &str) -> Result<User>;
+    async fn get_authenticate_url(&self, provider: &AuthProvider) -> Result<(String, String)>;
+    async fn handle_callback(
+        &self,
+        provider: &AuthProvider,
+        code: &str,
+        state: &str,
+    ) -> Result<Option<User>>;
+    async fn get_user_info(&self, user_id: &str) -> Result<PublicUser>;
 }
 
 impl<C, R> AuthUsecase<C, R>
 where
     C: OidcClientTrait,
-    R: UserRepository,
+    R: UserRepository + AuthSessionRepository,
 {
     pub fn new(oidc_client: C, repository: R) -> Self {
         Self {
@@ -36,49 +48,71 @@ where
 impl<C, R> Authenticate for AuthUsecase<C, R>
 where
     C: OidcClientTrait,
-    R: UserRepository,
+    R: UserRepository + AuthSessionRepository,
 {
-    async fn get_authenticate_url(&self, provider: &AuthProvider) -> Result<String> {
-        self.oidc_client
-            .get_auth_url(&provider)
+    /// Start a login, and return the URL of the provider to redirect the user to and the state of the login
+    ///
+    /// The state has to be bound to the browser, so that the callback can tell that it comes from the same browser.
+    async fn get_authenticate_url(&self, provider: &AuthProvider) -> Result<(String, String)> {
+        let request = self
+            .oidc_client
+            .get_auth_url(provider)
             .await
             .with_context(|| {
                 format!(
                     "Failed to get authenticate url for provider: {:?}",
                     provider
                 )
-            })
+            })?;
+
+        let now = chrono::Utc::now().timestamp();
+        let session = AuthSession {
+            csrf_token: request.csrf_token.clone(),
+            provider: *provider,
+            nonce: request.nonce,
+            pkce_verifier: request.pkce_verifier,
+            created_at: now,
+        };
+        self.repository
+            .create_session(&session, now - SESSION_TTL_SECONDS)
+            .await?;
+
+        Ok((request.url, request.csrf_token))
     }
 
-    async fn handle_callback(&self, provider: &AuthProvider, code: &str) -> Result<User> {
-        let access_token = self.oidc_client.exchange_code(provider, code).await?;
+    /// Complete the login started by `get_authenticate_url`
+    ///
+    /// `None` is returned if `state` does not match a pending session of the provider,
+    /// or the session has expired.
+    /// The user is created on the first login.
+    async fn handle_callback(
+        &self,
+        provider: &AuthProvider,
+        code: &str,
+        state: &str,
+    ) -> Result<Option<User>> {
+        let Some(session) = self.repository.take_session(state).await? else {
+            return Ok(None);
+        };
+        let now = chrono::Utc::now().timestamp();
+        if session.provider != *provider || session.created_at < now - SESSION_TTL_SECONDS {
+            return Ok(None);
+        }
 
-        let user_info = self
+        let provider_user = self
             .oidc_client
-            .get_user_info(provider, &access_token)
+            .exchange_code(provider, code, &session.pkce_verifier, &session.nonce)
             .await?;
 
         let user = self
             .repository
-            .find_by_provider_user_id(provider, &user_info.0)
+            .create_or_update(provider, &provider_user.id, provider_user.name.as_deref())
             .await?;
 
-        match user {
-            Some(user) => {
-                // todo!("Update user info");
-
-                Ok(user)
-            }
-            None => {
-                todo!("Create user info");
-            }
-        }
+        Ok(Some(user))
     }
 
-    async fn get_user_info(&self, user_id: &str) -> Result<User> {
-        match self.repository.find_by_user_id(user_id).await {
-            Ok(user) => Ok(user),
-            Err(_) => todo!("Handle error"),
-        }
+    async fn get_user_info(&self, user_id: &str) -> Result<PublicUser> {
+        Ok(self.repository.find_by_user_id(user_id).await?.into())
     }
 }
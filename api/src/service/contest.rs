@@ -1,8 +1,17 @@
 use anyhow::Result;
+use chrono::Utc;
 
+use crate::domain::calendar;
 use crate::infra::repository::contest::Condition;
 use crate::{domain::contest::Contest, infra::repository::contest::ContestRepository};
 
+/// Contests which started within this period (in seconds) are kept in the calendar,
+/// so that the events just held do not disappear from the subscribers' calendars.
+const CALENDAR_PAST_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// The maximum number of contests in the calendar.
+const CALENDAR_MAX_CONTESTS: i32 = 1000;
+
 pub struct FetchContestUsecase<R>
 where
     R: ContestRepository,
@@ -13,6 +22,7 @@ where
 #[trait_variant::make]
 pub trait FetchContest {
     async fn fetch_contests(&self, condition: &Condition<'_>) -> Result<Vec<Contest>>;
+    async fn fetch_calendar(&self, platforms: &[&str], categories: &[&str]) -> Result<String>;
 }
 
 impl<R> FetchContestUsecase<R>
@@ -31,4 +41,23 @@ where
     async fn fetch_contests(&self, condition: &Condition<'_>) -> Result<Vec<Contest>> {
         self.repository.get_contests_by_condition(condition).await
     }
+
+    /// Render the recent and upcoming contests on the platforms and in the categories
+    /// as an iCalendar feed
+    async fn fetch_calendar(&self, platforms: &[&str], categories: &[&str]) -> Result<String> {
+        let now = Utc::now().timestamp();
+        let condition = Condition {
+            platforms: platforms.to_vec(),
+            categories: categories.to_vec(),
+            from_start_time: Some(now - CALENDAR_PAST_SECONDS),
+            per_page: Some(CALENDAR_MAX_CONTESTS),
+            ..Default::default()
+        };
+        let contests = self
+            .repository
+            .get_contests_by_condition(&condition)
+            .await?;
+
+        Ok(calendar::to_icalendar(&contests, now))
+    }
 }
@@ -0,0 +1,118 @@
+//! UseCase for Difficulty Estimation
+//!
+//! This module estimates the difficulties of problems in-house,
+//! for the contests which AtCoder Problems has not modeled yet and for Codeforces.
+//!
+//! The results of the contests (the rating of each participant) are fetched and stored first.
+//! Then the difficulty of each problem is fitted on the stored results and submissions,
+//! calibrated onto the AtCoder scale and written back to the problems.
+
+use anyhow::Result;
+use std::{collections::HashMap, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    domain::{
+        calibration,
+        estimation::{self, Sample},
+        vo::platform::Platform,
+    },
+    infra::{
+        api::{atcoder::api_client::AtcoderAPIClient, cf::api_client::CFAPIClient},
+        repository::{contest_result::ContestResultRepository, problem::ProblemRepository},
+    },
+};
+
+/// Platforms whose contest results can be fetched.
+pub const ESTIMATION_PLATFORMS: [Platform; 2] = [Platform::Atcoder, Platform::Codeforces];
+
+pub struct EstimationUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient,
+    R: ContestResultRepository + ProblemRepository,
+{
+    api_client: Arc<C>,
+    repository: Arc<R>,
+}
+
+impl<C, R> EstimationUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient,
+    R: ContestResultRepository + ProblemRepository,
+{
+    pub fn new(api_client: Arc<C>, repository: Arc<R>) -> Self {
+        Self {
+            api_client,
+            repository,
+        }
+    }
+
+    /// Fetch and store the results of the finished contests not fetched yet.
+    ///
+    /// A contest whose results cannot be fetched is skipped and retried on the next run.
+    /// Returns the number of contests stored.
+    pub async fn fetch_contest_results(&self, platform: &Platform) -> Result<usize> {
+        let raw_contest_ids = self
+            .repository
+            .get_contests_without_results(platform)
+            .await?;
+
+        let mut count = 0;
+        for raw_contest_id in raw_contest_ids.iter() {
+            let results = match platform {
+                Platform::Atcoder => {
+                    self.api_client
+                        .get_atcoder_contest_results(raw_contest_id)
+                        .await
+                }
+                Platform::Codeforces => {
+                    self.api_client.get_cf_contest_results(raw_contest_id).await
+                }
+                _ => return Ok(0),
+            };
+
+            match results {
+                Ok(results) => {
+                    let contest_id = format!("{}_{}", String::from(*platform), raw_contest_id);
+                    self.repository
+                        .update_contest_results(&contest_id, &results)
+                        .await?;
+                    count += 1;
+                }
+                Err(e) => log::warn!("Skipped results of {raw_contest_id}: {e}"),
+            }
+
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        Ok(count)
+    }
+
+    /// Estimate the difficulties of the problems from the stored contest results and submissions.
+    ///
+    /// Returns the number of problems estimated.
+    pub async fn estimate_difficulties(&self, platform: &Platform) -> Result<usize> {
+        let mut samples_by_problem: HashMap<String, Vec<Sample>> = HashMap::new();
+        for (problem_id, sample) in self.repository.get_estimation_samples(platform).await? {
+            samples_by_problem
+                .entry(problem_id)
+                .or_default()
+                .push(sample);
+        }
+
+        let difficulties: Vec<_> = samples_by_problem
+            .iter()
+            .filter_map(|(problem_id, samples)| {
+                let estimate = estimation::estimate(samples)?;
+                let difficulty = calibration::from_estimate(*platform, &estimate)?;
+                Some((problem_id.clone(), difficulty, estimate.is_experimental))
+            })
+            .collect();
+
+        self.repository
+            .update_estimated_difficulties(&difficulties)
+            .await?;
+
+        Ok(difficulties.len())
+    }
+}
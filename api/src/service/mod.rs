@@ -1,5 +1,15 @@
 pub mod auth;
 pub mod contest;
+pub mod estimation;
 pub mod problem;
+pub mod problem_list;
+pub mod rating_history;
+pub mod recommendation;
+pub mod scheduler;
 pub mod submission;
+pub mod tag;
+pub mod tag_proposal;
 pub mod update;
+pub mod user;
+pub mod user_stats;
+pub mod virtual_contest;
@@ -1,34 +1,320 @@
 use anyhow::Result;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::infra::repository::problem::Condition;
+use crate::domain::{
+    submission::Submission,
+    vo::{platform::Platform, problem_status::ProblemStatus, verdict::Verdict},
+};
+use crate::infra::repository::{
+    problem::{Condition, SearchCondition},
+    user::UserRepository,
+};
+use crate::service::submission::{FetchSubmission, PageCondition, SUBMISSION_PLATFORMS};
 use crate::{domain::problem::Problem, infra::repository::problem::ProblemRepository};
 
-pub struct FetchProblemUsecase<R>
+/// The user for whom the statuses of the problems are computed.
+pub enum StatusUser<'a> {
+    /// A handle on the platform of the problems.
+    Handle(&'a str),
+    /// An internal user, whose linked account on the platform is used.
+    Internal(&'a str),
+}
+
+pub struct FetchProblemUsecase<R, S>
 where
-    R: ProblemRepository,
+    R: ProblemRepository + UserRepository,
+    S: FetchSubmission,
 {
     repository: R,
+    submission_usecase: Arc<S>,
 }
 
 #[trait_variant::make]
 pub trait FetchProblem {
     async fn fetch_problems(&self, condition: &Condition<'_>) -> Result<Vec<Problem>>;
+    async fn search_problems(&self, condition: &SearchCondition<'_>) -> Result<Vec<Problem>>;
+    async fn fetch_problems_with_status(
+        &self,
+        platform: &Platform,
+        condition: &Condition<'_>,
+        user: &StatusUser<'_>,
+    ) -> Result<Vec<Problem>>;
 }
 
-impl<R> FetchProblemUsecase<R>
+impl<R, S> FetchProblemUsecase<R, S>
 where
-    R: ProblemRepository,
+    R: ProblemRepository + UserRepository,
+    S: FetchSubmission,
 {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+    pub fn new(repository: R, submission_usecase: Arc<S>) -> Self {
+        Self {
+            repository,
+            submission_usecase,
+        }
     }
 }
 
-impl<R> FetchProblem for FetchProblemUsecase<R>
+impl<R, S> FetchProblem for FetchProblemUsecase<R, S>
 where
-    R: ProblemRepository,
+    R: ProblemRepository + UserRepository,
+    S: FetchSubmission,
 {
     async fn fetch_problems(&self, condition: &Condition<'_>) -> Result<Vec<Problem>> {
         self.repository.get_problems_by_condition(condition).await
     }
+
+    async fn search_problems(&self, condition: &SearchCondition<'_>) -> Result<Vec<Problem>> {
+        self.repository.search_problems(condition).await
+    }
+
+    /// Fetch the problems annotated with the status for the user
+    ///
+    /// The status is left unset for platforms whose submissions can not be fetched.
+    /// An internal user without a linked account on the platform has every problem untouched.
+    async fn fetch_problems_with_status(
+        &self,
+        platform: &Platform,
+        condition: &Condition<'_>,
+        user: &StatusUser<'_>,
+    ) -> Result<Vec<Problem>> {
+        let mut problems = self.repository.get_problems_by_condition(condition).await?;
+
+        if !SUBMISSION_PLATFORMS.contains(platform) {
+            return Ok(problems);
+        }
+
+        let internal_user;
+        let handle = match user {
+            StatusUser::Handle(handle) => Some(*handle),
+            StatusUser::Internal(user_id) => {
+                internal_user = self.repository.find_by_user_id(user_id).await?;
+                internal_user.platform_username(platform)
+            }
+        };
+
+        let submissions = match handle {
+            Some(handle) => {
+                let condition = match platform {
+                    Platform::Atcoder => PageCondition::Atcoder {
+                        user: handle,
+                        from_second: None,
+                    },
+                    _ => PageCondition::Other {
+                        user: handle,
+                        page: None,
+                        size: None,
+                    },
+                };
+                self.submission_usecase
+                    .fetch_user_submissions(platform, &condition)
+                    .await?
+            }
+            None => vec![],
+        };
+
+        annotate_statuses(&mut problems, &submissions);
+
+        Ok(problems)
+    }
+}
+
+/// The key identifying a problem within its platform, shared with the submissions to it.
+///
+/// - AtCoder: the task ID in the URL (e.g., abc100_a), since a task can be shared by several contests
+/// - Codeforces: the problem ID
+/// - AOJ: the raw problem ID (e.g., 0001)
+pub(crate) fn problem_key(problem: &Problem) -> String {
+    match problem.platform {
+        Platform::Atcoder => problem
+            .url
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        Platform::Aoj => problem.index.clone(),
+        _ => problem.id.clone(),
+    }
+}
+
+/// The key of the problem the submission was made to. See `problem_key`.
+pub(crate) fn submission_key(submission: &Submission) -> Option<String> {
+    let index = submission.problem.index.as_deref()?;
+
+    match submission.platform {
+        Platform::Atcoder | Platform::Aoj => Some(index.to_string()),
+        _ => {
+            let contest_id = submission.problem.contest_id.as_deref()?;
+            Some(format!(
+                "{}_{}_{}",
+                String::from(submission.platform),
+                contest_id,
+                index
+            ))
+        }
+    }
+}
+
+/// Set the status of each problem from the submissions of a user.
+///
+/// The keys of different platforms never collide, so the problems and submissions can be of several platforms.
+pub(crate) fn annotate_statuses<'a>(
+    problems: impl IntoIterator<Item = &'a mut Problem>,
+    submissions: &[Submission],
+) {
+    let mut statuses: HashMap<String, ProblemStatus> = HashMap::new();
+    for submission in submissions {
+        let Some(key) = submission_key(submission) else {
+            continue;
+        };
+
+        let status = if submission.verdict == Verdict::Accepted {
+            ProblemStatus::Accepted
+        } else {
+            ProblemStatus::Tried
+        };
+        let entry = statuses.entry(key).or_insert(status);
+        if status == ProblemStatus::Accepted {
+            *entry = status;
+        }
+    }
+
+    for problem in problems {
+        problem.status = Some(
+            statuses
+                .get(&problem_key(problem))
+                .copied()
+                .unwrap_or(ProblemStatus::Untouched),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_problem(platform: Platform, contest_id: &str, index: &str, url: &str) -> Problem {
+        Problem::reconstruct(
+            platform,
+            contest_id,
+            "contest",
+            index,
+            "problem",
+            None,
+            None,
+            "Other".to_string(),
+            None,
+            vec![],
+            url,
+            None,
+            None,
+        )
+    }
+
+    fn build_submission(
+        platform: Platform,
+        contest_id: Option<&str>,
+        index: &str,
+        verdict: Verdict,
+    ) -> Submission {
+        Submission::reconstruct(
+            platform,
+            "1".to_string(),
+            "user".to_string(),
+            "C++".to_string(),
+            verdict,
+            None,
+            None,
+            None,
+            0,
+            contest_id.map(String::from),
+            Some(index.to_string()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_annotate_statuses() {
+        let mut problems = vec![
+            build_problem(
+                Platform::Atcoder,
+                "abc100",
+                "A",
+                "https://atcoder.jp/contests/abc100/tasks/abc100_a",
+            ),
+            build_problem(
+                Platform::Atcoder,
+                "abc100",
+                "B",
+                "https://atcoder.jp/contests/abc100/tasks/abc100_b",
+            ),
+            build_problem(
+                Platform::Atcoder,
+                "abc100",
+                "C",
+                "https://atcoder.jp/contests/abc100/tasks/abc100_c",
+            ),
+        ];
+        let submissions = vec![
+            build_submission(
+                Platform::Atcoder,
+                Some("abc100"),
+                "abc100_a",
+                Verdict::WrongAnswer,
+            ),
+            // A task shared with another contest is matched by its task ID.
+            build_submission(
+                Platform::Atcoder,
+                Some("arc099"),
+                "abc100_a",
+                Verdict::Accepted,
+            ),
+            build_submission(
+                Platform::Atcoder,
+                Some("abc100"),
+                "abc100_b",
+                Verdict::WrongAnswer,
+            ),
+        ];
+
+        annotate_statuses(&mut problems, &submissions);
+
+        let statuses: Vec<_> = problems.iter().map(|p| p.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(ProblemStatus::Accepted),
+                Some(ProblemStatus::Tried),
+                Some(ProblemStatus::Untouched),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotate_statuses_codeforces_and_aoj() {
+        let mut problems = vec![
+            build_problem(
+                Platform::Codeforces,
+                "1000",
+                "A",
+                "https://codeforces.com/contest/1000/problem/A",
+            ),
+            build_problem(
+                Platform::Aoj,
+                "volume_0",
+                "0001",
+                "https://onlinejudge.u-aizu.ac.jp/problems/0001",
+            ),
+        ];
+        let submissions = vec![
+            build_submission(Platform::Codeforces, Some("1000"), "A", Verdict::Accepted),
+            build_submission(Platform::Aoj, None, "0001", Verdict::TimeLimitExceeded),
+        ];
+
+        annotate_statuses(&mut problems, &submissions);
+
+        assert_eq!(problems[0].status, Some(ProblemStatus::Accepted));
+        assert_eq!(problems[1].status, Some(ProblemStatus::Tried));
+    }
 }
@@ -0,0 +1,247 @@
+//! UseCase for Personal Problem Lists
+//!
+//! Users create, order, annotate and share lists of problems.
+//! The progress on a list is computed from the linked accounts of the user who sees it.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::{
+    domain::problem_list::{
+        ProblemList, ProblemListDetail, ProblemListEntry, ProblemListError, ProblemListExport,
+        ProblemListProgress,
+    },
+    infra::repository::problem_list::ProblemListRepository,
+    service::{problem::annotate_statuses, user::ManageUser},
+};
+
+/// The maximum number of problems in a list.
+const MAX_ITEMS: usize = 1000;
+
+/// The maximum length of a list name in characters.
+const MAX_NAME_LENGTH: usize = 255;
+
+pub struct ProblemListUsecase<R, U>
+where
+    R: ProblemListRepository,
+    U: ManageUser,
+{
+    repository: R,
+    user_usecase: Arc<U>,
+}
+
+#[trait_variant::make]
+pub trait ManageProblemList {
+    async fn get_problem_lists(&self, user_id: &str) -> Result<Vec<ProblemList>>;
+    async fn get_problem_list(&self, user_id: &str, list_id: &str) -> Result<ProblemListDetail>;
+    async fn create_problem_list(
+        &self,
+        user_id: &str,
+        content: &ProblemListExport,
+        is_public: bool,
+    ) -> Result<ProblemList>;
+    async fn update_problem_list(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        name: &str,
+        description: Option<&str>,
+        is_public: bool,
+    ) -> Result<ProblemList>;
+    async fn replace_items(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        items: &[ProblemListEntry],
+    ) -> Result<ProblemListDetail>;
+    async fn upsert_item(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        item: &ProblemListEntry,
+    ) -> Result<ProblemListDetail>;
+    async fn delete_problem_list(&self, user_id: &str, list_id: &str) -> Result<()>;
+    async fn export_problem_list(&self, user_id: &str, list_id: &str) -> Result<ProblemListExport>;
+}
+
+impl<R, U> ProblemListUsecase<R, U>
+where
+    R: ProblemListRepository,
+    U: ManageUser,
+{
+    pub fn new(repository: R, user_usecase: Arc<U>) -> Self {
+        Self {
+            repository,
+            user_usecase,
+        }
+    }
+
+    /// The owner can see any of their lists, and the others can see the shared ones.
+    async fn get_visible_list(&self, user_id: &str, list_id: &str) -> Result<ProblemList> {
+        let list = self
+            .repository
+            .get_problem_list(list_id)
+            .await?
+            .ok_or(ProblemListError::NotFound)?;
+        if list.owner_id != user_id && !list.is_public {
+            return Err(ProblemListError::Forbidden.into());
+        }
+
+        Ok(list)
+    }
+
+    async fn get_owned_list(&self, user_id: &str, list_id: &str) -> Result<ProblemList> {
+        let list = self.get_visible_list(user_id, list_id).await?;
+        if list.owner_id != user_id {
+            return Err(ProblemListError::Forbidden.into());
+        }
+
+        Ok(list)
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ProblemListError::Invalid(format!(
+            "The name must be between 1 and {MAX_NAME_LENGTH} characters"
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+fn validate_items(items: &[ProblemListEntry]) -> Result<()> {
+    if items.len() > MAX_ITEMS {
+        return Err(ProblemListError::Invalid(format!(
+            "A list can contain at most {MAX_ITEMS} problems"
+        ))
+        .into());
+    }
+    for (i, item) in items.iter().enumerate() {
+        if items[..i].iter().any(|p| p.problem_id == item.problem_id) {
+            return Err(ProblemListError::Invalid(format!(
+                "The problem {} is duplicated",
+                item.problem_id
+            ))
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+impl<R, U> ManageProblemList for ProblemListUsecase<R, U>
+where
+    R: ProblemListRepository,
+    U: ManageUser,
+{
+    async fn get_problem_lists(&self, user_id: &str) -> Result<Vec<ProblemList>> {
+        self.repository.get_problem_lists(user_id).await
+    }
+
+    /// Get the list with its problems and the progress of the user on them
+    async fn get_problem_list(&self, user_id: &str, list_id: &str) -> Result<ProblemListDetail> {
+        let list = self.get_visible_list(user_id, list_id).await?;
+        let mut items = self.repository.get_problem_list_items(list_id).await?;
+
+        if !items.is_empty() {
+            let submissions = self.user_usecase.fetch_user_submissions(user_id).await?;
+            annotate_statuses(items.iter_mut().map(|item| &mut item.problem), &submissions);
+        }
+
+        Ok(ProblemListDetail {
+            progress: ProblemListProgress::from_items(&items),
+            list,
+            items,
+        })
+    }
+
+    /// Create a list, which is also how an exported list is imported
+    async fn create_problem_list(
+        &self,
+        user_id: &str,
+        content: &ProblemListExport,
+        is_public: bool,
+    ) -> Result<ProblemList> {
+        validate_name(&content.name)?;
+        validate_items(&content.items)?;
+
+        self.repository
+            .create_problem_list(user_id, content, is_public, Utc::now().timestamp())
+            .await
+    }
+
+    async fn update_problem_list(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        name: &str,
+        description: Option<&str>,
+        is_public: bool,
+    ) -> Result<ProblemList> {
+        self.get_owned_list(user_id, list_id).await?;
+        validate_name(name)?;
+
+        self.repository
+            .update_problem_list(
+                list_id,
+                name,
+                description,
+                is_public,
+                Utc::now().timestamp(),
+            )
+            .await
+    }
+
+    async fn replace_items(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        items: &[ProblemListEntry],
+    ) -> Result<ProblemListDetail> {
+        self.get_owned_list(user_id, list_id).await?;
+        validate_items(items)?;
+
+        self.repository
+            .replace_problem_list_items(list_id, items, Utc::now().timestamp())
+            .await?;
+
+        self.get_problem_list(user_id, list_id).await
+    }
+
+    async fn upsert_item(
+        &self,
+        user_id: &str,
+        list_id: &str,
+        item: &ProblemListEntry,
+    ) -> Result<ProblemListDetail> {
+        let list = self.get_owned_list(user_id, list_id).await?;
+        if list.item_count >= MAX_ITEMS as i64 {
+            return Err(ProblemListError::Invalid(format!(
+                "A list can contain at most {MAX_ITEMS} problems"
+            ))
+            .into());
+        }
+
+        self.repository
+            .upsert_problem_list_item(list_id, item, Utc::now().timestamp())
+            .await?;
+
+        self.get_problem_list(user_id, list_id).await
+    }
+
+    async fn delete_problem_list(&self, user_id: &str, list_id: &str) -> Result<()> {
+        self.get_owned_list(user_id, list_id).await?;
+
+        self.repository.delete_problem_list(list_id).await
+    }
+
+    async fn export_problem_list(&self, user_id: &str, list_id: &str) -> Result<ProblemListExport> {
+        let list = self.get_visible_list(user_id, list_id).await?;
+        let items = self.repository.get_problem_list_items(list_id).await?;
+
+        Ok(ProblemListExport::new(&list, &items))
+    }
+}
@@ -0,0 +1,131 @@
+//! UseCase for Rating History
+//!
+//! The rating histories of the linked AtCoder and Codeforces accounts are fetched from the platforms,
+//! stored in the database, and combined into a single timeline.
+
+use anyhow::Result;
+
+use crate::{
+    domain::{
+        rating_history::{RatingChange, RatingHistoryError, RATING_PLATFORMS},
+        vo::platform::Platform,
+    },
+    infra::{
+        api::{atcoder::api_client::AtcoderAPIClient, cf::api_client::CFAPIClient},
+        repository::{rating_history::RatingHistoryRepository, user::UserRepository},
+    },
+};
+
+pub struct RatingHistoryUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient,
+    R: UserRepository + RatingHistoryRepository,
+{
+    api_client: C,
+    repository: R,
+}
+
+#[trait_variant::make]
+pub trait FetchRatingHistory {
+    async fn fetch_rating_history(&self, user_id: &str) -> Result<Vec<RatingChange>>;
+}
+
+impl<C, R> RatingHistoryUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient,
+    R: UserRepository + RatingHistoryRepository,
+{
+    pub fn new(api_client: C, repository: R) -> Self {
+        Self {
+            api_client,
+            repository,
+        }
+    }
+
+    /// Fetch the whole rating history of a user from the platform and store it.
+    ///
+    /// Only the platforms in `RATING_PLATFORMS` are supported.
+    pub async fn sync_rating_history(&self, platform: &Platform, user: &str) -> Result<()> {
+        let changes = match platform {
+            Platform::Atcoder => self.api_client.get_atcoder_rating_history(user).await?,
+            Platform::Codeforces => self.api_client.get_cf_rating_history(user).await?,
+            _ => return Err(RatingHistoryError::UnsupportedPlatform(*platform).into()),
+        };
+
+        self.repository.update_rating_history(&changes).await?;
+
+        log::info!(
+            "Stored {} rating changes of {} from {:?}.",
+            changes.len(),
+            user,
+            platform
+        );
+
+        Ok(())
+    }
+}
+
+impl<C, R> FetchRatingHistory for RatingHistoryUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient,
+    R: UserRepository + RatingHistoryRepository,
+{
+    /// Fetch the rating changes of all the linked accounts, oldest first.
+    ///
+    /// If a platform can not be reached, the stored history of the account is returned as it is.
+    async fn fetch_rating_history(&self, user_id: &str) -> Result<Vec<RatingChange>> {
+        let user = self.repository.find_by_user_id(user_id).await?;
+
+        let mut changes: Vec<RatingChange> = vec![];
+        for platform in RATING_PLATFORMS.iter() {
+            let Some(handle) = user.platform_username(platform) else {
+                continue;
+            };
+
+            if let Err(e) = self.sync_rating_history(platform, handle).await {
+                log::warn!(
+                    "Failed to sync the rating history of {} from {:?}: {:?}",
+                    handle,
+                    platform,
+                    e
+                );
+            }
+
+            changes.extend(self.repository.get_rating_history(platform, handle).await?);
+        }
+
+        changes.sort_by(|a, b| {
+            a.rated_at
+                .cmp(&b.rated_at)
+                .then_with(|| a.contest_id.cmp(&b.contest_id))
+        });
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    use crate::{config::HttpConfigs, infra::api::api_client::ApiClient};
+
+    #[actix_web::test]
+    async fn test_sync_unsupported_platform() {
+        // Neither the platform nor the database is reached for an unsupported platform.
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let usecase = RatingHistoryUsecase::new(ApiClient::new(&HttpConfigs::from_env()), pool);
+
+        let error = usecase
+            .sync_rating_history(&Platform::Yukicoder, "alice")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<RatingHistoryError>(),
+            Some(&RatingHistoryError::UnsupportedPlatform(
+                Platform::Yukicoder
+            ))
+        );
+    }
+}
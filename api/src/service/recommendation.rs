@@ -0,0 +1,195 @@
+//! UseCase for Problem Recommendation
+//!
+//! The strength of a user is taken from the stored ratings of the linked accounts,
+//! or estimated from their accepted submissions if the user has no rating, and unsolved problems of the requested level are recommended, optionally of an algorithm or a tag.
+//!
+//! Only the problems of the platforms whose submissions can be fetched are recommended,
+//! since it can not be told whether the user has solved the others.
+
+use anyhow::Result;
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    domain::{
+        rating_history::{rated_strength, RATING_PLATFORMS},
+        recommendation::{
+            estimate_strength, solve_probability, Recommendation, RecommendationLevel,
+            Recommendations, DEFAULT_STRENGTH, RECENT_ACCEPTED,
+        },
+        vo::{platform::Platform, problem_status::ProblemStatus, verdict::Verdict},
+    },
+    infra::repository::{
+        problem::{Condition, ProblemRepository},
+        rating_history::RatingHistoryRepository,
+    },
+    service::{
+        problem::{annotate_statuses, problem_key, submission_key},
+        submission::SUBMISSION_PLATFORMS,
+        user::ManageUser,
+    },
+};
+
+/// The number of problems fetched at once while looking for the unsolved ones.
+const CANDIDATE_PAGE_SIZE: i32 = 500;
+
+/// The maximum number of pages fetched, which bounds the work for users who have solved many problems.
+const MAX_CANDIDATE_PAGES: i32 = 10;
+
+/// The number of unsolved candidates from which the recommendations are chosen.
+const MAX_CANDIDATES: usize = 500;
+
+pub struct RecommendationCondition<'a> {
+    pub level: RecommendationLevel,
+    pub platform: Option<&'a Platform>,
+    pub algo_id: Option<&'a str>,
+    pub technical_tag_id: Option<&'a str>,
+    pub limit: usize,
+}
+
+pub struct RecommendationUsecase<R, U>
+where
+    R: ProblemRepository + RatingHistoryRepository,
+    U: ManageUser,
+{
+    repository: R,
+    user_usecase: Arc<U>,
+}
+
+#[trait_variant::make]
+pub trait Recommend {
+    async fn recommend(
+        &self,
+        user_id: &str,
+        condition: &RecommendationCondition<'_>,
+    ) -> Result<Recommendations>;
+}
+
+impl<R, U> RecommendationUsecase<R, U>
+where
+    R: ProblemRepository + RatingHistoryRepository,
+    U: ManageUser,
+{
+    pub fn new(repository: R, user_usecase: Arc<U>) -> Self {
+        Self {
+            repository,
+            user_usecase,
+        }
+    }
+}
+
+impl<R, U> Recommend for RecommendationUsecase<R, U>
+where
+    R: ProblemRepository + RatingHistoryRepository,
+    U: ManageUser,
+{
+    async fn recommend(
+        &self,
+        user_id: &str,
+        condition: &RecommendationCondition<'_>,
+    ) -> Result<Recommendations> {
+        let user = self.user_usecase.get_user(user_id).await?;
+        // Newest first
+        let submissions = self.user_usecase.fetch_user_submissions(user_id).await?;
+
+        let mut rating_history = vec![];
+        for platform in RATING_PLATFORMS.iter() {
+            if let Some(handle) = user.platform_username(platform) {
+                rating_history.extend(self.repository.get_rating_history(platform, handle).await?);
+            }
+        }
+
+        let mut seen: HashSet<(Platform, String)> = HashSet::new();
+        let recent_accepted: Vec<(Platform, String)> = submissions
+            .iter()
+            .filter(|s| s.verdict == Verdict::Accepted)
+            .filter_map(|s| Some((s.platform, submission_key(s)?)))
+            .filter(|key| seen.insert(key.clone()))
+            .take(RECENT_ACCEPTED)
+            .collect();
+
+        // The solved problems are only looked up for users without a rating.
+        let rated = rated_strength(&rating_history);
+        let mut difficulties = vec![];
+        for platform in SUBMISSION_PLATFORMS.iter().filter(|_| rated.is_none()) {
+            let keys: Vec<String> = recent_accepted
+                .iter()
+                .filter(|(p, _)| p == platform)
+                .map(|(_, key)| key.clone())
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let problems = self
+                .repository
+                .get_problems_by_keys(platform, &keys)
+                .await?;
+            difficulties.extend(
+                problems
+                    .iter()
+                    .filter(|p| keys.contains(&problem_key(p)))
+                    .filter_map(|p| p.difficulty),
+            );
+        }
+
+        let strength = rated.or_else(|| estimate_strength(&difficulties));
+        let is_estimated = strength.is_some();
+        let strength = strength.unwrap_or(DEFAULT_STRENGTH);
+
+        let (from_difficulty, to_difficulty) = condition.level.difficulty_range(strength);
+        let platform = condition.platform.map(|p| String::from(*p));
+
+        let mut candidates = vec![];
+        for page in 1..=MAX_CANDIDATE_PAGES {
+            let mut problems = self
+                .repository
+                .get_problems_by_condition(&Condition {
+                    platform: platform.as_deref(),
+                    algo_id: condition.algo_id,
+                    technical_tag_id: condition.technical_tag_id,
+                    page: Some(page),
+                    page_size: Some(CANDIDATE_PAGE_SIZE),
+                    from_difficulty: Some(from_difficulty.ceil() as i32),
+                    to_difficulty: Some(to_difficulty.floor() as i32),
+                })
+                .await?;
+            let is_last_page = problems.len() < CANDIDATE_PAGE_SIZE as usize;
+
+            annotate_statuses(&mut problems, &submissions);
+            candidates.extend(problems.into_iter().filter(|p| {
+                SUBMISSION_PLATFORMS.contains(&p.platform)
+                    && p.status != Some(ProblemStatus::Accepted)
+            }));
+
+            if is_last_page || candidates.len() >= MAX_CANDIDATES {
+                break;
+            }
+        }
+
+        let target = condition.level.target_probability();
+        let mut recommendations: Vec<Recommendation> = candidates
+            .into_iter()
+            .filter_map(|problem| {
+                let solve_probability = solve_probability(strength, problem.difficulty?);
+                Some(Recommendation {
+                    problem,
+                    solve_probability,
+                })
+            })
+            .collect();
+        recommendations.sort_by(|a, b| {
+            (a.solve_probability - target)
+                .abs()
+                .total_cmp(&(b.solve_probability - target).abs())
+                .then_with(|| a.problem.id.cmp(&b.problem.id))
+        });
+        recommendations.truncate(condition.limit);
+
+        Ok(Recommendations {
+            strength,
+            is_estimated,
+            level: condition.level,
+            problems: recommendations,
+        })
+    }
+}
@@ -0,0 +1,368 @@
+//! UseCase for Scheduled Updates
+//!
+//! The problems and contests of each platform are updated at the cadence given by `SCHEDULES`.
+//! Every run, scheduled or not, holds the lock of its platform while it writes,
+//! so that a manual run never overlaps with the scheduler, and neither do two schedulers.
+//! Admins can also start a run of a platform or a single contest, and inspect the history of the runs.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    domain::{
+        ingest::{IngestError, IngestReport},
+        ingestion_run::{IngestionRun, IngestionRunError, RunMode},
+        scheduler::{is_due, JobRun, JobStatus, Schedule, UpdateMode, SCHEDULES},
+        vo::{platform::Platform, user_role::UserRole},
+    },
+    infra::{
+        api::{
+            aoj::api_client::AojAPIClient, atcoder::api_client::AtcoderAPIClient,
+            cf::api_client::CFAPIClient, yoj::api_client::YOJAPIClient,
+            yuki::api_client::YukicoderAPIClient,
+        },
+        repository::{
+            contest::ContestRepository, ingestion_run::IngestionRunRepository,
+            problem::ProblemRepository, scheduler::SchedulerRepository, user::UserRepository,
+            watermark::WatermarkRepository,
+        },
+    },
+    service::update::UpdateUsecase,
+};
+
+/// How often the scheduler checks whether a platform is due.
+const TICK: Duration = Duration::from_secs(60);
+
+pub struct SchedulerUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
+    R: ProblemRepository
+        + ContestRepository
+        + WatermarkRepository
+        + SchedulerRepository
+        + IngestionRunRepository
+        + UserRepository,
+{
+    update: UpdateUsecase<C, R>,
+    repository: Arc<R>,
+}
+
+#[trait_variant::make]
+pub trait FetchJobStatus {
+    async fn fetch_job_statuses(&self) -> Result<Vec<JobStatus>>;
+}
+
+#[trait_variant::make]
+pub trait ManageIngestionRun {
+    async fn start_refresh(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        contest_id: Option<&str>,
+    ) -> Result<IngestionRun>;
+    async fn finish_refresh(&self, run: &IngestionRun) -> Result<()>;
+    async fn get_runs(
+        &self,
+        user_id: &str,
+        platform: Option<&Platform>,
+        limit: i64,
+    ) -> Result<Vec<IngestionRun>>;
+    async fn get_last_error(
+        &self,
+        user_id: &str,
+        platform: Option<&Platform>,
+    ) -> Result<IngestionRun>;
+}
+
+impl<C, R> SchedulerUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
+    R: ProblemRepository
+        + ContestRepository
+        + WatermarkRepository
+        + SchedulerRepository
+        + IngestionRunRepository
+        + UserRepository,
+{
+    pub fn new(api_client: Arc<C>, repository: Arc<R>) -> Self {
+        Self {
+            update: UpdateUsecase::new(api_client, repository.clone()),
+            repository,
+        }
+    }
+
+    /// Update the platform and its upcoming contests, and record the result as its status.
+    ///
+    /// `None` is returned without updating anything if another run holds the lock of the platform.
+    /// The error of the update is returned after it is recorded.
+    pub async fn run_job(
+        &self,
+        platform: &Platform,
+        mode: UpdateMode,
+    ) -> Result<Option<IngestReport>> {
+        let Some(run) = self.start_run(platform, mode.into(), None, None).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.execute_run(&run).await?))
+    }
+
+    /// Take the lock of the platform and record the start of a run.
+    ///
+    /// `None` is returned if another run holds the lock.
+    async fn start_run(
+        &self,
+        platform: &Platform,
+        mode: RunMode,
+        contest_id: Option<&str>,
+        triggered_by: Option<&str>,
+    ) -> Result<Option<IngestionRun>> {
+        let started_at = Utc::now().timestamp();
+        if !self.repository.try_lock_job(platform, started_at).await? {
+            log::warn!("{:?} is being updated by another run.", platform);
+            return Ok(None);
+        }
+        // A lock taken over leaves the run which held it unfinished.
+        self.fail_stale_runs().await?;
+
+        match self
+            .repository
+            .create_ingestion_run(platform, mode, contest_id, triggered_by, started_at)
+            .await
+        {
+            Ok(run) => Ok(Some(run)),
+            Err(e) => {
+                // The lock is released at once, rather than blocking the platform until it times out.
+                let run = job_run(
+                    started_at,
+                    &Err(IngestError::DbWrite {
+                        table: "ingestion_runs".to_string(),
+                        message: format!("{:#}", e),
+                    }),
+                );
+                self.repository.unlock_job(platform, &run).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Run the update started by `start_run`, record its result and release the lock.
+    async fn execute_run(&self, run: &IngestionRun) -> Result<IngestReport> {
+        let platform = &run.platform;
+        let result = async {
+            let mut report = match run.mode {
+                RunMode::Full => self.update.fetch_and_update(platform).await?,
+                RunMode::Incremental => self.update.fetch_and_update_incremental(platform).await?,
+                RunMode::Contest => {
+                    let contest_id = run.contest_id.as_deref().unwrap_or_default();
+                    let report = self
+                        .update
+                        .fetch_and_update_contest(platform, contest_id)
+                        .await?;
+                    log::info!("{}", report);
+                    return Ok(report);
+                }
+            };
+            log::info!("{}", report);
+
+            // Upcoming contests are fetched apart from the past ones with their problems.
+            let upcoming = self.update.fetch_and_update_upcoming(platform).await?;
+            log::info!("{}", upcoming);
+            report.contests += upcoming.contests;
+            report.contests_inserted += upcoming.contests_inserted;
+            report.skipped.extend(upcoming.skipped);
+
+            Ok::<IngestReport, IngestError>(report)
+        }
+        .await;
+
+        let finished = job_run(run.started_at, &result);
+        let unlocked = self.repository.unlock_job(platform, &finished).await;
+        self.repository
+            .finish_ingestion_run(run.id, &finished)
+            .await?;
+        unlocked?;
+
+        Ok(result?)
+    }
+
+    /// Record the runs interrupted by a crash or a restart as failed, rather than running forever.
+    ///
+    /// This is called on startup, and whenever a run takes over a lock.
+    pub async fn fail_stale_runs(&self) -> Result<()> {
+        let failed = self
+            .repository
+            .fail_stale_ingestion_runs(Utc::now().timestamp())
+            .await?;
+        if failed > 0 {
+            log::warn!("Recorded {} interrupted ingestion runs as failed.", failed);
+        }
+
+        Ok(())
+    }
+
+    /// Run the update of the platform whenever it is due, forever.
+    ///
+    /// The statuses are read from the database on each tick,
+    /// so that a manual run or another scheduler postpones the next run as well.
+    pub async fn run_on_schedule(&self, schedule: &Schedule) {
+        loop {
+            let due = self
+                .fetch_job_status(&schedule.platform)
+                .await
+                .map(|status| is_due(schedule, &status, Utc::now().timestamp()));
+
+            match due {
+                Ok(true) => match self.run_job(&schedule.platform, schedule.mode).await {
+                    Ok(Some(_)) => log::info!("Finished updating {:?}.", schedule.platform),
+                    Ok(None) => {}
+                    Err(e) => log::error!("Failed to update {:?}: {:#}", schedule.platform, e),
+                },
+                Ok(false) => {}
+                Err(e) => log::error!(
+                    "Failed to check the schedule of {:?}: {:#}",
+                    schedule.platform,
+                    e
+                ),
+            }
+
+            sleep(TICK).await;
+        }
+    }
+
+    async fn ensure_admin(&self, user_id: &str) -> Result<()> {
+        let user = self.repository.find_by_user_id(user_id).await?;
+        if user.role != UserRole::Admin {
+            return Err(IngestionRunError::Forbidden.into());
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_job_status(&self, platform: &Platform) -> Result<JobStatus> {
+        let status = self
+            .repository
+            .get_job_statuses()
+            .await?
+            .into_iter()
+            .find(|status| status.platform == *platform)
+            .unwrap_or_else(|| JobStatus::new(*platform));
+
+        Ok(status)
+    }
+}
+
+impl<C, R> FetchJobStatus for SchedulerUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
+    R: ProblemRepository
+        + ContestRepository
+        + WatermarkRepository
+        + SchedulerRepository
+        + IngestionRunRepository
+        + UserRepository,
+{
+    /// The statuses of all the scheduled platforms, including those never updated.
+    async fn fetch_job_statuses(&self) -> Result<Vec<JobStatus>> {
+        let statuses = self.repository.get_job_statuses().await?;
+
+        Ok(SCHEDULES
+            .iter()
+            .map(|schedule| {
+                statuses
+                    .iter()
+                    .find(|status| status.platform == schedule.platform)
+                    .cloned()
+                    .unwrap_or_else(|| JobStatus::new(schedule.platform))
+            })
+            .collect())
+    }
+}
+
+impl<C, R> ManageIngestionRun for SchedulerUsecase<C, R>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
+    R: ProblemRepository
+        + ContestRepository
+        + WatermarkRepository
+        + SchedulerRepository
+        + IngestionRunRepository
+        + UserRepository,
+{
+    /// Take the lock of the platform for a run triggered by the admin, and record its start.
+    ///
+    /// The whole platform is updated in the mode of its schedule, unless a contest is given.
+    /// The update itself is left to `finish_refresh`, so that the caller can answer before it ends.
+    async fn start_refresh(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        contest_id: Option<&str>,
+    ) -> Result<IngestionRun> {
+        self.ensure_admin(user_id).await?;
+
+        let mode = match contest_id {
+            Some(_) => RunMode::Contest,
+            None => SCHEDULES
+                .iter()
+                .find(|schedule| schedule.platform == *platform)
+                .map_or(RunMode::Full, |schedule| schedule.mode.into()),
+        };
+
+        self.start_run(platform, mode, contest_id, Some(user_id))
+            .await?
+            .ok_or_else(|| IngestionRunError::Locked.into())
+    }
+
+    /// Run the update of a run returned by `start_refresh`.
+    async fn finish_refresh(&self, run: &IngestionRun) -> Result<()> {
+        self.execute_run(run).await?;
+
+        Ok(())
+    }
+
+    async fn get_runs(
+        &self,
+        user_id: &str,
+        platform: Option<&Platform>,
+        limit: i64,
+    ) -> Result<Vec<IngestionRun>> {
+        self.ensure_admin(user_id).await?;
+
+        self.repository
+            .get_ingestion_runs(platform, false, limit)
+            .await
+    }
+
+    /// The latest failed run of the platform, or of any platform if `None`.
+    async fn get_last_error(
+        &self,
+        user_id: &str,
+        platform: Option<&Platform>,
+    ) -> Result<IngestionRun> {
+        self.ensure_admin(user_id).await?;
+
+        self.repository
+            .get_ingestion_runs(platform, true, 1)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| IngestionRunError::NotFound.into())
+    }
+}
+
+/// The result of a run which started at `started_at` and finishes now.
+fn job_run(started_at: i64, result: &Result<IngestReport, IngestError>) -> JobRun {
+    JobRun {
+        started_at,
+        finished_at: Utc::now().timestamp(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        problems: result.as_ref().map_or(0, |r| r.problems as i32),
+        problems_inserted: result.as_ref().map_or(0, |r| r.problems_inserted as i32),
+        contests: result.as_ref().map_or(0, |r| r.contests as i32),
+        contests_inserted: result.as_ref().map_or(0, |r| r.contests_inserted as i32),
+        skipped: result.as_ref().map_or(0, |r| r.skipped.len() as i32),
+    }
+}
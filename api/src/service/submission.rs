@@ -3,23 +3,52 @@
 //! This module handles use cases related to submissions.
 //! Its primary functions are to fetch recent submissions and submissions by user.
 //!
+//! Submissions by user are stored in the database.
+//! Only the submissions newer than the last stored one are fetched from the platform,
+//! and the response is built from the database.
+//! A user is synchronized at most once per `SYNC_INTERVAL`, so that repeated requests do not reach the platform.
+//!
 //! Submission is only available for AtCoder, Codeforces, and AOJ.
 
 use anyhow::Result;
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+use tokio::time::{sleep, Duration};
 
 use crate::{
-    domain::{submission::Submission, vo::platform::Platform},
-    infra::api::{
-        aoj::api_client::AojAPIClient, atcoder::api_client::AtcoderAPIClient,
-        cf::api_client::CFAPIClient,
+    domain::{
+        submission::{Submission, SubmissionError},
+        vo::platform::Platform,
+    },
+    infra::{
+        api::{
+            aoj::api_client::AojAPIClient, atcoder::api_client::AtcoderAPIClient,
+            cf::api_client::CFAPIClient,
+        },
+        repository::submission::{Condition, SubmissionRepository},
     },
 };
 
+/// Platforms whose submissions can be fetched.
+///
+/// yukicoder and Library Checker provide no API for the submissions of a user, so they are not included.
+pub const SUBMISSION_PLATFORMS: [Platform; 3] =
+    [Platform::Atcoder, Platform::Codeforces, Platform::Aoj];
+
+/// The maximum number of submissions returned at once by the AtCoder API (kenkoooo).
+const ATCODER_PAGE_SIZE: usize = 500;
+
+/// The number of submissions requested at once from Codeforces and AOJ.
+const PAGE_SIZE: u32 = 500;
+
+/// How long the stored submissions of a user are served without asking the platform for new ones.
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
 pub enum PageCondition<'a> {
     Atcoder {
         user: &'a str,
         from_second: Option<u64>,
     },
+    /// Codeforces pages start from 1 and AOJ pages start from 0, as on the platforms.
     Other {
         user: &'a str,
         page: Option<u32>,
@@ -27,11 +56,15 @@ pub enum PageCondition<'a> {
     },
 }
 
-pub struct FetchSubmissionUsecase<C>
+pub struct FetchSubmissionUsecase<C, R>
 where
     C: AtcoderAPIClient + CFAPIClient + AojAPIClient,
+    R: SubmissionRepository,
 {
     api_client: C,
+    repository: R,
+    /// When each user was last synchronized with the platform, in this process.
+    synced_at: Mutex<HashMap<(Platform, String), Instant>>,
 }
 
 #[trait_variant::make]
@@ -44,31 +77,159 @@ pub trait FetchSubmission {
     ) -> Result<Vec<Submission>>;
 }
 
-impl<C> FetchSubmissionUsecase<C>
+impl<C, R> FetchSubmissionUsecase<C, R>
 where
     C: AtcoderAPIClient + CFAPIClient + AojAPIClient,
+    R: SubmissionRepository,
 {
-    pub fn new(api_client: C) -> Self {
-        Self { api_client }
+    pub fn new(api_client: C, repository: R) -> Self {
+        Self {
+            api_client,
+            repository,
+            synced_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetch the submissions of a user made since the last stored one and store them.
+    ///
+    /// If no submission of the user has been stored yet, the whole history is fetched.
+    /// Nothing is fetched if the user has been synchronized within `SYNC_INTERVAL`.
+    pub async fn sync_user_submissions(&self, platform: &Platform, user: &str) -> Result<()> {
+        // The handles are matched regardless of the case, as the stored submissions are.
+        let key = (*platform, user.to_lowercase());
+        let synced_at = self.synced_at.lock().unwrap().get(&key).copied();
+        if !is_stale(synced_at, Instant::now()) {
+            return Ok(());
+        }
+
+        let last_date = self
+            .repository
+            .get_last_submission_date(platform, user)
+            .await?;
+
+        let submissions = match platform {
+            Platform::Atcoder => {
+                self.fetch_atcoder_submissions_since(user, last_date)
+                    .await?
+            }
+            Platform::Codeforces | Platform::Aoj => {
+                self.fetch_other_submissions_since(platform, user, last_date)
+                    .await?
+            }
+            _ => return Err(SubmissionError::UnsupportedPlatform(*platform).into()),
+        };
+
+        self.repository.update_submissions(&submissions).await?;
+        self.synced_at.lock().unwrap().insert(key, Instant::now());
+
+        log::info!(
+            "Stored {} submissions of {} from {:?}.",
+            submissions.len(),
+            user,
+            platform
+        );
+
+        Ok(())
+    }
+
+    /// AtCoder API returns the submissions in ascending order from `from_second`,
+    /// so we follow the pages until the last one.
+    async fn fetch_atcoder_submissions_since(
+        &self,
+        user: &str,
+        last_date: Option<u64>,
+    ) -> Result<Vec<Submission>> {
+        let mut from_second = last_date.unwrap_or(0);
+        let mut submissions: Vec<Submission> = vec![];
+
+        loop {
+            let page = self
+                .api_client
+                .get_atcoder_user_submissions(user, Some(from_second))
+                .await?;
+
+            let is_last_page = page.len() < ATCODER_PAGE_SIZE;
+            let next_from_second = page
+                .iter()
+                .map(|s| s.submission_date)
+                .max()
+                .unwrap_or(from_second);
+            submissions.extend(page);
+
+            // Guard against a page filled with submissions made in the same second.
+            if is_last_page || next_from_second <= from_second {
+                break;
+            }
+            from_second = next_from_second;
+
+            // AtCoder API (kenkoooo) asks clients to wait between requests.
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        Ok(submissions)
+    }
+
+    /// Codeforces and AOJ APIs return the submissions in descending order,
+    /// so we follow the pages until we reach the last stored submission.
+    async fn fetch_other_submissions_since(
+        &self,
+        platform: &Platform,
+        user: &str,
+        last_date: Option<u64>,
+    ) -> Result<Vec<Submission>> {
+        // Codeforces pages start from 1, whereas AOJ pages start from 0.
+        let mut page = match platform {
+            Platform::Codeforces => 1,
+            _ => 0,
+        };
+        let mut submissions: Vec<Submission> = vec![];
+
+        loop {
+            let page_submissions = match platform {
+                Platform::Codeforces => {
+                    self.api_client
+                        .get_cf_user_submissions(user, Some(page), Some(PAGE_SIZE))
+                        .await?
+                }
+                _ => {
+                    self.api_client
+                        .get_aoj_user_submissions(user, Some(page), Some(PAGE_SIZE))
+                        .await?
+                }
+            };
+
+            let is_last_page = page_submissions.len() < PAGE_SIZE as usize;
+            let reached_stored = page_submissions
+                .iter()
+                .any(|s| last_date.is_some_and(|last_date| s.submission_date < last_date));
+
+            submissions.extend(
+                page_submissions
+                    .into_iter()
+                    .filter(|s| last_date.is_none_or(|last_date| s.submission_date >= last_date)),
+            );
+
+            if is_last_page || reached_stored {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(submissions)
     }
 }
 
-impl<C> FetchSubmission for FetchSubmissionUsecase<C>
+impl<C, R> FetchSubmission for FetchSubmissionUsecase<C, R>
 where
     C: AtcoderAPIClient + CFAPIClient + AojAPIClient,
+    R: SubmissionRepository,
 {
     async fn fetch_recent_submissions(&self, platform: &Platform) -> Result<Vec<Submission>> {
         let submissions = match platform {
-            Platform::Atcoder => self
-                .api_client
-                .get_atcoder_recent_submissions()
-                .await
-                .unwrap(),
-            Platform::Codeforces => self.api_client.get_cf_recent_submissions().await.unwrap(),
-            Platform::Aoj => self.api_client.get_aoj_recent_submissions().await.unwrap(),
-            _ => {
-                unimplemented!("This platform is not currently supported.");
-            }
+            Platform::Atcoder => self.api_client.get_atcoder_recent_submissions().await?,
+            Platform::Codeforces => self.api_client.get_cf_recent_submissions().await?,
+            Platform::Aoj => self.api_client.get_aoj_recent_submissions().await?,
+            _ => return Err(SubmissionError::UnsupportedPlatform(*platform).into()),
         };
 
         Ok(submissions)
@@ -79,45 +240,69 @@ where
         platform: &Platform,
         condition: &PageCondition<'_>,
     ) -> Result<Vec<Submission>> {
-        let submissions = match platform {
-            Platform::Atcoder => {
-                let (user, from_second) = match condition {
-                    PageCondition::Atcoder { user, from_second } => (user, from_second),
-                    _ => unreachable!(),
-                };
-                self.api_client
-                    .get_atcoder_user_submissions(user, *from_second)
-                    .await
-                    .unwrap()
-            }
+        let condition = match condition {
+            PageCondition::Atcoder { user, from_second } => Condition {
+                platform: *platform,
+                user_id: user,
+                from_second: from_second.map(|s| s as i64),
+                page: None,
+                page_size: None,
+            },
+            PageCondition::Other { user, page, size } => Condition {
+                platform: *platform,
+                user_id: user,
+                from_second: None,
+                page: page.map(|p| page_index(platform, p)),
+                page_size: size.map(|s| s as i32),
+            },
+        };
 
-            Platform::Codeforces => {
-                let (user, page, size) = match condition {
-                    PageCondition::Other { user, page, size } => (user, page, size),
-                    _ => unreachable!(),
-                };
+        self.sync_user_submissions(platform, condition.user_id)
+            .await?;
 
-                self.api_client
-                    .get_cf_user_submissions(user, *page, *size)
-                    .await
-                    .unwrap()
-            }
+        self.repository
+            .get_submissions_by_condition(&condition)
+            .await
+    }
+}
 
-            Platform::Aoj => {
-                let (user, page, size) = match condition {
-                    PageCondition::Other { user, page, size } => (user, page, size),
-                    _ => unreachable!(),
-                };
-                self.api_client
-                    .get_aoj_user_submissions(user, *page, *size)
-                    .await
-                    .unwrap()
-            }
-            _ => {
-                unimplemented!("This platform is not currently supported.");
-            }
-        };
+/// Whether the submissions synchronized at `synced_at` should be synchronized again.
+fn is_stale(synced_at: Option<Instant>, now: Instant) -> bool {
+    synced_at.is_none_or(|synced_at| now.saturating_duration_since(synced_at) >= SYNC_INTERVAL)
+}
 
-        Ok(submissions)
+/// The 0-based index of the page, numbered as on the platform.
+fn page_index(platform: &Platform, page: u32) -> i32 {
+    let first_page = match platform {
+        Platform::Aoj => 0,
+        _ => 1,
+    };
+
+    page.saturating_sub(first_page) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stale() {
+        let now = Instant::now();
+        assert!(is_stale(None, now));
+        assert!(!is_stale(
+            Some(now),
+            now + SYNC_INTERVAL - Duration::from_secs(1)
+        ));
+        assert!(is_stale(Some(now), now + SYNC_INTERVAL));
+    }
+
+    #[test]
+    fn test_page_index() {
+        assert_eq!(page_index(&Platform::Codeforces, 1), 0);
+        assert_eq!(page_index(&Platform::Codeforces, 3), 2);
+        // Out of range pages are regarded as the first one.
+        assert_eq!(page_index(&Platform::Codeforces, 0), 0);
+        assert_eq!(page_index(&Platform::Aoj, 0), 0);
+        assert_eq!(page_index(&Platform::Aoj, 2), 2);
     }
 }
@@ -0,0 +1,53 @@
+use anyhow::Result;
+
+use crate::{
+    domain::{
+        taxonomy::{build_hierarchy, localize_tags, LocalizedAlgorithm, LocalizedTag},
+        vo::locale::Locale,
+    },
+    infra::repository::technical_tag::TechnicalTagRepositoryTrait,
+};
+
+pub struct FetchTagUsecase<R>
+where
+    R: TechnicalTagRepositoryTrait,
+{
+    repository: R,
+}
+
+#[trait_variant::make]
+pub trait FetchTag {
+    async fn fetch_algorithms(&self, locale: Locale) -> Result<Vec<LocalizedAlgorithm>>;
+    async fn fetch_tags(&self, algo_id: Option<&str>, locale: Locale) -> Result<Vec<LocalizedTag>>;
+}
+
+impl<R> FetchTagUsecase<R>
+where
+    R: TechnicalTagRepositoryTrait,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R> FetchTag for FetchTagUsecase<R>
+where
+    R: TechnicalTagRepositoryTrait,
+{
+    /// Fetch all algorithms with their tags
+    async fn fetch_algorithms(&self, locale: Locale) -> Result<Vec<LocalizedAlgorithm>> {
+        let algorithms = self.repository.get_algorithms().await?;
+        let tags = self.fetch_tags(None, locale).await?;
+
+        Ok(build_hierarchy(&algorithms, tags, locale))
+    }
+
+    /// Fetch all tags or the tags of the algorithm
+    async fn fetch_tags(&self, algo_id: Option<&str>, locale: Locale) -> Result<Vec<LocalizedTag>> {
+        let algorithms = self.repository.get_algorithms().await?;
+        let tags = self.repository.get_tags(algo_id).await?;
+        let counts = self.repository.get_tag_problem_counts().await?;
+
+        Ok(localize_tags(&tags, &algorithms, &counts, locale))
+    }
+}
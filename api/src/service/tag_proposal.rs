@@ -0,0 +1,153 @@
+//! UseCase for Tag Proposals
+//!
+//! Users propose technical tags for problems and vote on the proposals of others.
+//! Moderators approve or reject the proposals, and create new tags if none of the existing ones fits.
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::{
+    domain::{
+        tag_proposal::{TagProposal, TagProposalError, TagProposalEvent},
+        vo::proposal_status::ProposalStatus,
+    },
+    infra::repository::{
+        tag_proposal::TagProposalRepository, technical_tag::TechnicalTagRepositoryTrait,
+        user::UserRepository,
+    },
+};
+
+pub struct TagProposalUsecase<R>
+where
+    R: TagProposalRepository + TechnicalTagRepositoryTrait + UserRepository,
+{
+    repository: R,
+}
+
+#[trait_variant::make]
+pub trait ManageTagProposal {
+    async fn get_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+        problem_id: Option<&str>,
+    ) -> Result<Vec<TagProposal>>;
+    async fn get_proposal_history(&self, proposal_id: &str) -> Result<Vec<TagProposalEvent>>;
+    async fn propose(
+        &self,
+        user_id: &str,
+        problem_id: &str,
+        technical_tag_id: &str,
+    ) -> Result<TagProposal>;
+    async fn vote(&self, user_id: &str, proposal_id: &str, is_up: bool) -> Result<TagProposal>;
+    async fn review(
+        &self,
+        user_id: &str,
+        proposal_id: &str,
+        approve: bool,
+        comment: Option<&str>,
+    ) -> Result<TagProposal>;
+    async fn create_tag(
+        &self,
+        user_id: &str,
+        en_name: &str,
+        ja_name: &str,
+        algorithm_id: &str,
+    ) -> Result<String>;
+}
+
+impl<R> TagProposalUsecase<R>
+where
+    R: TagProposalRepository + TechnicalTagRepositoryTrait + UserRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    async fn ensure_moderator(&self, user_id: &str) -> Result<()> {
+        let user = self.repository.find_by_user_id(user_id).await?;
+        if !user.role.can_moderate() {
+            return Err(TagProposalError::Forbidden.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> ManageTagProposal for TagProposalUsecase<R>
+where
+    R: TagProposalRepository + TechnicalTagRepositoryTrait + UserRepository,
+{
+    async fn get_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+        problem_id: Option<&str>,
+    ) -> Result<Vec<TagProposal>> {
+        self.repository.get_proposals(status, problem_id).await
+    }
+
+    async fn get_proposal_history(&self, proposal_id: &str) -> Result<Vec<TagProposalEvent>> {
+        if self.repository.get_proposal(proposal_id).await?.is_none() {
+            return Err(TagProposalError::NotFound.into());
+        }
+
+        self.repository.get_proposal_events(proposal_id).await
+    }
+
+    async fn propose(
+        &self,
+        user_id: &str,
+        problem_id: &str,
+        technical_tag_id: &str,
+    ) -> Result<TagProposal> {
+        self.repository
+            .create_proposal(
+                problem_id,
+                technical_tag_id,
+                user_id,
+                Utc::now().timestamp(),
+            )
+            .await
+    }
+
+    async fn vote(&self, user_id: &str, proposal_id: &str, is_up: bool) -> Result<TagProposal> {
+        self.repository
+            .vote_proposal(proposal_id, user_id, is_up, Utc::now().timestamp())
+            .await
+    }
+
+    /// Approve or reject the proposal. Only moderators can review proposals.
+    async fn review(
+        &self,
+        user_id: &str,
+        proposal_id: &str,
+        approve: bool,
+        comment: Option<&str>,
+    ) -> Result<TagProposal> {
+        self.ensure_moderator(user_id).await?;
+
+        self.repository
+            .review_proposal(
+                proposal_id,
+                user_id,
+                approve,
+                comment,
+                Utc::now().timestamp(),
+            )
+            .await
+    }
+
+    /// Create a new tag, which can be proposed afterwards. Only moderators can create tags.
+    async fn create_tag(
+        &self,
+        user_id: &str,
+        en_name: &str,
+        ja_name: &str,
+        algorithm_id: &str,
+    ) -> Result<String> {
+        self.ensure_moderator(user_id).await?;
+
+        self.repository
+            .create_tag(en_name, ja_name, algorithm_id)
+            .await
+    }
+}
@@ -1,22 +1,33 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::sync::Arc;
 
 use crate::{
-    domain::vo::platform::Platform,
+    domain::{
+        contest::Contest,
+        ingest::{IngestError, IngestReport},
+        problem::Problem,
+        vo::platform::Platform,
+    },
     infra::{
         api::{
             aoj::api_client::AojAPIClient, atcoder::api_client::AtcoderAPIClient,
             cf::api_client::CFAPIClient, yoj::api_client::YOJAPIClient,
             yuki::api_client::YukicoderAPIClient,
         },
-        repository::{contest::ContestRepository, problem::ProblemRepository},
+        repository::{
+            contest::ContestRepository, problem::ProblemRepository, watermark::WatermarkRepository,
+        },
     },
 };
 
+/// The number of problems stored at once during an incremental update.
+/// The watermark is advanced after each batch, so a crashed run loses at most one batch.
+const INCREMENTAL_BATCH_SIZE: usize = 50;
+
 pub struct UpdateUsecase<C, R>
 where
     C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
-    R: ProblemRepository + ContestRepository,
+    R: ProblemRepository + ContestRepository + WatermarkRepository,
 {
     api_client: Arc<C>,
     repository: Arc<R>,
@@ -25,7 +36,7 @@ where
 impl<C, R> UpdateUsecase<C, R>
 where
     C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient + YOJAPIClient,
-    R: ProblemRepository + ContestRepository,
+    R: ProblemRepository + ContestRepository + WatermarkRepository,
 {
     pub fn new(api_client: Arc<C>, repository: Arc<R>) -> Self {
         return Self {
@@ -34,47 +45,181 @@ where
         };
     }
 
-    pub async fn fetch_and_update(&self, platform: &Platform) -> Result<()> {
-        let (problems, contests) = match platform {
-            Platform::Atcoder => self
-                .api_client
-                .get_atcoder_problems_and_contests()
-                .await
-                .unwrap(),
-            Platform::Codeforces => self
-                .api_client
-                .get_cf_problems_and_contests()
-                .await
-                .unwrap(),
-            Platform::Yukicoder => self
-                .api_client
-                .get_yuki_problems_and_contests()
-                .await
-                .unwrap(),
-            Platform::Aoj => self
-                .api_client
-                .get_aoj_problems_and_contests()
-                .await
-                .unwrap(),
-            Platform::YOJ => self
+    /// Fetch all problems and contests of the platform and update the database.
+    ///
+    /// Malformed records are skipped and listed in the returned report.
+    /// An error is returned only if the platform cannot be fetched or the database cannot be written.
+    pub async fn fetch_and_update(&self, platform: &Platform) -> Result<IngestReport, IngestError> {
+        let (problems, contests, skipped) = self.fetch(platform).await?;
+
+        let mut report = IngestReport::new(*platform);
+        report.skipped = skipped;
+        self.store(&problems, &contests, &mut report).await?;
+
+        Ok(report)
+    }
+
+    /// Fetch the platform and update only the given contest and its problems.
+    ///
+    /// None of the platforms serves a single contest with its problems,
+    /// so the whole platform is fetched and the rest is thrown away.
+    /// The records skipped elsewhere on the platform are not reported.
+    pub async fn fetch_and_update_contest(
+        &self,
+        platform: &Platform,
+        contest_id: &str,
+    ) -> Result<IngestReport, IngestError> {
+        let (problems, contests, _) = self.fetch(platform).await?;
+
+        let contests: Vec<Contest> = contests
+            .into_iter()
+            .filter(|contest| contest.id == contest_id)
+            .collect();
+        if contests.is_empty() {
+            return Err(IngestError::ContestNotFound {
+                contest_id: contest_id.to_string(),
+            });
+        }
+        let problems: Vec<Problem> = problems
+            .into_iter()
+            .filter(|problem| problem.contest_id == contest_id)
+            .collect();
+
+        let mut report = IngestReport::new(*platform);
+        self.store(&problems, &contests, &mut report).await?;
+
+        Ok(report)
+    }
+
+    /// Fetch only the problems and contests newer than the stored watermark and update the database.
+    ///
+    /// Problems are stored in batches, and the watermark is advanced after each batch.
+    /// If the process stops partway through, the next run resumes from the last stored batch.
+    /// The run also stops at a problem which fails for a retryable reason, so that it is fetched again next time.
+    ///
+    /// Incremental update is only available for yukicoder.
+    /// For the other platforms, this falls back to `fetch_and_update`.
+    pub async fn fetch_and_update_incremental(
+        &self,
+        platform: &Platform,
+    ) -> Result<IngestReport, IngestError> {
+        if *platform != Platform::Yukicoder {
+            return self.fetch_and_update(platform).await;
+        }
+
+        let mut report = IngestReport::new(*platform);
+        let mut watermark = self
+            .repository
+            .get_watermark(platform)
+            .await
+            .map_err(|e| db_write_error("crawl_watermarks", e))?;
+
+        // The lists are fetched once, and only the details of the problems are fetched by batch.
+        let catalog = self.api_client.get_yuki_catalog().await?;
+        report.skipped.extend(catalog.skipped.iter().cloned());
+
+        loop {
+            let (problems, contests, skipped, next_watermark) = self
                 .api_client
-                .get_yoj_problems_and_contests()
+                .get_yuki_problems_and_contests_after(
+                    &catalog,
+                    watermark.as_ref(),
+                    INCREMENTAL_BATCH_SIZE,
+                )
+                .await?;
+
+            // A problem which failed for a retryable reason is fetched again on the next run,
+            // so the watermark stays before it.
+            let retry_later = skipped.iter().any(IngestError::is_retryable);
+            report.skipped.extend(skipped);
+            self.store(&problems, &contests, &mut report).await?;
+
+            let Some(next_watermark) = next_watermark else {
+                break;
+            };
+
+            self.repository
+                .update_watermark(platform, &next_watermark)
                 .await
-                .unwrap(),
+                .map_err(|e| db_write_error("crawl_watermarks", e))?;
+
+            log::info!(
+                "Stored {} problems from {:?} up to {:?}.",
+                problems.len(),
+                platform,
+                next_watermark
+            );
+
+            if retry_later {
+                break;
+            }
+            watermark = Some(next_watermark);
+        }
+
+        Ok(report)
+    }
+
+    /// Fetch the contests which have not finished yet and update the database.
+    ///
+    /// Only Codeforces and yukicoder list their upcoming contests apart from the past ones.
+    /// AtCoder contests are listed with their phases by `fetch_and_update`,
+    /// and the other platforms do not hold contests on schedule.
+    pub async fn fetch_and_update_upcoming(
+        &self,
+        platform: &Platform,
+    ) -> Result<IngestReport, IngestError> {
+        let contests = match platform {
+            Platform::Codeforces => self.api_client.get_cf_upcoming_contests().await?,
+            Platform::Yukicoder => self.api_client.get_yuki_upcoming_contests().await?,
+            _ => vec![],
         };
 
-        self.repository
-            .update_problems(&problems)
+        let mut report = IngestReport::new(*platform);
+        self.store(&[], &contests, &mut report).await?;
+
+        Ok(report)
+    }
+
+    async fn fetch(
+        &self,
+        platform: &Platform,
+    ) -> Result<(Vec<Problem>, Vec<Contest>, Vec<IngestError>), IngestError> {
+        match platform {
+            Platform::Atcoder => self.api_client.get_atcoder_problems_and_contests().await,
+            Platform::Codeforces => self.api_client.get_cf_problems_and_contests().await,
+            Platform::Yukicoder => self.api_client.get_yuki_problems_and_contests().await,
+            Platform::Aoj => self.api_client.get_aoj_problems_and_contests().await,
+            Platform::YOJ => self.api_client.get_yoj_problems_and_contests().await,
+        }
+    }
+
+    async fn store(
+        &self,
+        problems: &[Problem],
+        contests: &Vec<Contest>,
+        report: &mut IngestReport,
+    ) -> Result<(), IngestError> {
+        report.problems_inserted += self
+            .repository
+            .update_problems(problems)
             .await
-            .with_context(|| "Failed to update problems")
-            .unwrap();
+            .map_err(|e| db_write_error("problems", e))?;
+        report.problems += problems.len();
 
-        self.repository
-            .update_contests(&contests)
+        report.contests_inserted += self
+            .repository
+            .update_contests(contests)
             .await
-            .with_context(|| "Failed to update contests")
-            .unwrap();
+            .map_err(|e| db_write_error("contests", e))?;
+        report.contests += contests.len();
 
         Ok(())
     }
 }
+
+fn db_write_error(table: &str, error: anyhow::Error) -> IngestError {
+    IngestError::DbWrite {
+        table: table.to_string(),
+        message: format!("{:#}", error),
+    }
+}
@@ -0,0 +1,319 @@
+//! UseCase for Users
+//!
+//! This module handles use cases related to internal users.
+//! Its primary functions are to link competitive programming accounts to a user,
+//! and to fetch the submissions of the user across all the linked accounts.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::Arc;
+
+use crate::{
+    domain::{
+        submission::Submission,
+        user::{User, UserError},
+        vo::platform::Platform,
+    },
+    infra::{
+        api::{
+            aoj::api_client::AojAPIClient, atcoder::api_client::AtcoderAPIClient,
+            cf::api_client::CFAPIClient, yuki::api_client::YukicoderAPIClient,
+        },
+        repository::user::UserRepository,
+    },
+    service::submission::{FetchSubmission, PageCondition, SUBMISSION_PLATFORMS},
+};
+
+/// Handles are embedded in upstream URLs, so only the characters used by the platforms are accepted.
+static HANDLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9_.\-]{1,64}$").unwrap());
+
+pub struct UserUsecase<C, R, S>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient,
+    R: UserRepository,
+    S: FetchSubmission,
+{
+    api_client: C,
+    repository: R,
+    submission_usecase: Arc<S>,
+}
+
+#[trait_variant::make]
+pub trait ManageUser {
+    async fn get_user(&self, user_id: &str) -> Result<User>;
+
+    /// Returns `None` if the handle does not exist on the platform.
+    /// A handle which cannot exist on any platform is an error, `UserError::InvalidHandle`.
+    async fn link_account(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        handle: &str,
+    ) -> Result<Option<User>>;
+    async fn unlink_account(&self, user_id: &str, platform: &Platform) -> Result<User>;
+    async fn fetch_user_submissions(&self, user_id: &str) -> Result<Vec<Submission>>;
+}
+
+impl<C, R, S> UserUsecase<C, R, S>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient,
+    R: UserRepository,
+    S: FetchSubmission,
+{
+    pub fn new(api_client: C, repository: R, submission_usecase: Arc<S>) -> Self {
+        Self {
+            api_client,
+            repository,
+            submission_usecase,
+        }
+    }
+
+    /// Check whether the handle exists on the platform.
+    ///
+    /// Yosupo Online Judge does not provide user information, so any valid handle is accepted.
+    async fn handle_exists(&self, platform: &Platform, handle: &str) -> Result<bool> {
+        match platform {
+            Platform::Atcoder => self.api_client.atcoder_user_exists(handle).await,
+            Platform::Codeforces => self.api_client.cf_user_exists(handle).await,
+            Platform::Yukicoder => self.api_client.yuki_user_exists(handle).await,
+            Platform::Aoj => self.api_client.aoj_user_exists(handle).await,
+            Platform::YOJ => Ok(true),
+        }
+    }
+}
+
+impl<C, R, S> ManageUser for UserUsecase<C, R, S>
+where
+    C: AtcoderAPIClient + CFAPIClient + YukicoderAPIClient + AojAPIClient,
+    R: UserRepository,
+    S: FetchSubmission,
+{
+    async fn get_user(&self, user_id: &str) -> Result<User> {
+        self.repository.find_by_user_id(user_id).await
+    }
+
+    async fn link_account(
+        &self,
+        user_id: &str,
+        platform: &Platform,
+        handle: &str,
+    ) -> Result<Option<User>> {
+        validate_handle(platform, handle)?;
+        if !self.handle_exists(platform, handle).await? {
+            return Ok(None);
+        }
+
+        let user = self
+            .repository
+            .update_platform_username(user_id, platform, Some(handle))
+            .await?;
+
+        Ok(Some(user))
+    }
+
+    async fn unlink_account(&self, user_id: &str, platform: &Platform) -> Result<User> {
+        self.repository
+            .update_platform_username(user_id, platform, None)
+            .await
+    }
+
+    /// Fetch the submissions of all the linked accounts, newest first.
+    ///
+    /// Linked accounts of platforms which do not provide submissions, yukicoder and Library Checker,
+    /// are ignored. See `SUBMISSION_PLATFORMS`.
+    async fn fetch_user_submissions(&self, user_id: &str) -> Result<Vec<Submission>> {
+        let user = self.repository.find_by_user_id(user_id).await?;
+
+        let mut submissions: Vec<Submission> = vec![];
+        for platform in SUBMISSION_PLATFORMS.iter() {
+            let Some(handle) = user.platform_username(platform) else {
+                continue;
+            };
+            // The handles linked before they were validated are not sent upstream.
+            validate_handle(platform, handle)?;
+
+            let condition = match platform {
+                Platform::Atcoder => PageCondition::Atcoder {
+                    user: handle,
+                    from_second: None,
+                },
+                _ => PageCondition::Other {
+                    user: handle,
+                    page: None,
+                    size: None,
+                },
+            };
+
+            submissions.extend(
+                self.submission_usecase
+                    .fetch_user_submissions(platform, &condition)
+                    .await?,
+            );
+        }
+
+        submissions.sort_by_key(|s| std::cmp::Reverse(s.submission_date));
+
+        Ok(submissions)
+    }
+}
+
+fn validate_handle(platform: &Platform, handle: &str) -> Result<()> {
+    if !HANDLE_REGEX.is_match(handle) {
+        return Err(UserError::InvalidHandle {
+            platform: *platform,
+            handle: handle.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::{
+        config::HttpConfigs,
+        domain::vo::{providers::AuthProvider, user_role::UserRole, verdict::Verdict},
+        infra::api::api_client::ApiClient,
+    };
+
+    struct StubRepository;
+
+    impl UserRepository for StubRepository {
+        async fn find_by_provider_user_id(
+            &self,
+            _: &AuthProvider,
+            _: &str,
+        ) -> Result<Option<User>> {
+            unreachable!()
+        }
+
+        async fn find_by_user_id(&self, user_id: &str) -> Result<User> {
+            Ok(User {
+                id: user_id.to_string(),
+                username: None,
+                github_id: None,
+                github_username: None,
+                google_id: None,
+                google_email: None,
+                atcoder_username: Some("alice_atcoder".to_string()),
+                codeforces_username: Some("alice_cf".to_string()),
+                yukicoder_username: Some("alice_yuki".to_string()),
+                aoj_username: Some("alice_aoj".to_string()),
+                yoj_username: Some("alice_yoj".to_string()),
+                role: UserRole::Member,
+            })
+        }
+
+        async fn update_platform_username(
+            &self,
+            _: &str,
+            _: &Platform,
+            _: Option<&str>,
+        ) -> Result<User> {
+            unreachable!()
+        }
+
+        async fn update_role(&self, _: &str, _: UserRole) -> Result<User> {
+            unreachable!()
+        }
+
+        async fn create_or_update(
+            &self,
+            _: &AuthProvider,
+            _: &str,
+            _: Option<&str>,
+        ) -> Result<User> {
+            unreachable!()
+        }
+    }
+
+    /// Returns a submission per request, dated by the order of the platforms.
+    #[derive(Default)]
+    struct StubSubmissionUsecase {
+        requested: Mutex<Vec<Platform>>,
+    }
+
+    impl FetchSubmission for StubSubmissionUsecase {
+        async fn fetch_recent_submissions(&self, _: &Platform) -> Result<Vec<Submission>> {
+            unreachable!()
+        }
+
+        async fn fetch_user_submissions(
+            &self,
+            platform: &Platform,
+            condition: &PageCondition<'_>,
+        ) -> Result<Vec<Submission>> {
+            let user = match condition {
+                PageCondition::Atcoder { user, .. } | PageCondition::Other { user, .. } => user,
+            };
+            let mut requested = self.requested.lock().unwrap();
+            requested.push(*platform);
+
+            Ok(vec![Submission::reconstruct(
+                *platform,
+                "1".to_string(),
+                user.to_string(),
+                "C++".to_string(),
+                Verdict::Accepted,
+                None,
+                None,
+                None,
+                requested.len() as u64,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )])
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_fetch_user_submissions() {
+        let submission_usecase = Arc::new(StubSubmissionUsecase::default());
+        let usecase = UserUsecase::new(
+            ApiClient::new(&HttpConfigs::from_env()),
+            StubRepository,
+            submission_usecase.clone(),
+        );
+
+        let submissions = usecase.fetch_user_submissions("alice").await.unwrap();
+
+        // yukicoder and Library Checker are not asked for the submissions.
+        assert_eq!(
+            *submission_usecase.requested.lock().unwrap(),
+            SUBMISSION_PLATFORMS.to_vec()
+        );
+        let users: Vec<&str> = submissions.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(users, vec!["alice_aoj", "alice_cf", "alice_atcoder"]);
+    }
+
+    #[actix_web::test]
+    async fn test_link_account_rejects_invalid_handle() {
+        let usecase = UserUsecase::new(
+            ApiClient::new(&HttpConfigs::from_env()),
+            StubRepository,
+            Arc::new(StubSubmissionUsecase::default()),
+        );
+
+        // Rejected before asking the platform
+        let Err(error) = usecase
+            .link_account("alice", &Platform::Atcoder, "../alice")
+            .await
+        else {
+            panic!("The invalid handle is linked");
+        };
+        assert_eq!(
+            error.downcast_ref::<UserError>(),
+            Some(&UserError::InvalidHandle {
+                platform: Platform::Atcoder,
+                handle: "../alice".to_string(),
+            })
+        );
+    }
+}
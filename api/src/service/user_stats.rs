@@ -0,0 +1,226 @@
+//! UseCase for User Statistics
+//!
+//! The statistics of a user are computed from the stored submissions of the linked accounts,
+//! without fetching new ones from the platforms.
+//! They are cached in the database and recomputed only when the stored submissions,
+//! the linked accounts or the day change.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    domain::{
+        submission::Submission,
+        user::User,
+        user_stats::{
+            day_of, streaks, CachedUserStats, DifficultyBandCount, LanguageCount, PlatformCount,
+            UserStats,
+        },
+        vo::{difficulty_band::DifficultyBand, platform::Platform, verdict::Verdict},
+    },
+    infra::repository::{
+        problem::ProblemRepository,
+        submission::{Condition, SubmissionRepository},
+        user::UserRepository,
+        user_stats::UserStatsRepository,
+    },
+    service::{
+        problem::{problem_key, submission_key},
+        submission::SUBMISSION_PLATFORMS,
+    },
+};
+
+/// A problem accepted by the user. See `submission_key`.
+type ProblemKey = (Platform, String);
+
+pub struct UserStatsUsecase<R>
+where
+    R: UserRepository + SubmissionRepository + ProblemRepository + UserStatsRepository,
+{
+    repository: R,
+}
+
+#[trait_variant::make]
+pub trait FetchUserStats {
+    async fn fetch_user_stats(&self, user_id: &str) -> Result<UserStats>;
+}
+
+impl<R> UserStatsUsecase<R>
+where
+    R: UserRepository + SubmissionRepository + ProblemRepository + UserStatsRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Identify the stored submissions of the linked accounts on the day.
+    async fn fingerprint(&self, user: &User, today: i64) -> Result<String> {
+        let mut fingerprint = today.to_string();
+        for platform in SUBMISSION_PLATFORMS.iter() {
+            let Some(handle) = user.platform_username(platform) else {
+                continue;
+            };
+
+            let summary = self
+                .repository
+                .get_submission_summary(platform, handle)
+                .await?;
+            fingerprint += &format!(
+                "|{}:{}:{}:{}:{}",
+                String::from(*platform),
+                handle,
+                summary.count,
+                summary.accepted,
+                summary.last_date.unwrap_or_default()
+            );
+        }
+
+        Ok(fingerprint)
+    }
+
+    async fn compute(&self, user: &User, today: i64, now: i64) -> Result<UserStats> {
+        let mut submissions: Vec<Submission> = vec![];
+        for platform in SUBMISSION_PLATFORMS.iter() {
+            let Some(handle) = user.platform_username(platform) else {
+                continue;
+            };
+
+            submissions.extend(
+                self.repository
+                    .get_submissions_by_condition(&Condition {
+                        platform: *platform,
+                        user_id: handle,
+                        from_second: None,
+                        page: None,
+                        page_size: None,
+                    })
+                    .await?,
+            );
+        }
+
+        let mut days: BTreeSet<i64> = BTreeSet::new();
+        // The difficulty recorded with the submission is used unless the problem is stored.
+        let mut accepted: HashMap<ProblemKey, Option<f64>> = HashMap::new();
+        let mut languages: HashMap<&str, (u32, HashSet<ProblemKey>)> = HashMap::new();
+        for submission in submissions.iter() {
+            let language = languages.entry(&submission.language).or_default();
+            language.0 += 1;
+
+            if submission.verdict != Verdict::Accepted {
+                continue;
+            }
+            days.insert(day_of(submission.submission_date as i64));
+
+            if let Some(key) = submission_key(submission) {
+                accepted
+                    .entry((submission.platform, key.clone()))
+                    .or_insert(submission.problem.difficulty);
+                language.1.insert((submission.platform, key));
+            }
+        }
+
+        for platform in SUBMISSION_PLATFORMS.iter() {
+            let keys: Vec<String> = accepted
+                .keys()
+                .filter(|(p, _)| p == platform)
+                .map(|(_, key)| key.clone())
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+
+            for problem in self
+                .repository
+                .get_problems_by_keys(platform, &keys)
+                .await?
+            {
+                if let Some(difficulty) = accepted.get_mut(&(*platform, problem_key(&problem))) {
+                    *difficulty = problem.difficulty.or(*difficulty);
+                }
+            }
+        }
+
+        let (current_streak, longest_streak) = streaks(&days, today);
+
+        let accepted_by_platform = SUBMISSION_PLATFORMS
+            .iter()
+            .filter(|platform| user.platform_username(platform).is_some())
+            .map(|platform| PlatformCount {
+                platform: *platform,
+                accepted: accepted.keys().filter(|(p, _)| p == platform).count() as u32,
+            })
+            .collect();
+
+        let accepted_by_difficulty = DifficultyBand::ALL
+            .iter()
+            .map(|band| DifficultyBandCount {
+                band: *band,
+                accepted: accepted
+                    .values()
+                    .filter(|d| d.is_some_and(|d| DifficultyBand::from_difficulty(d) == *band))
+                    .count() as u32,
+            })
+            .collect();
+        let accepted_without_difficulty = accepted.values().filter(|d| d.is_none()).count() as u32;
+
+        let mut languages: Vec<LanguageCount> = languages
+            .into_iter()
+            .map(|(language, (submissions, problems))| LanguageCount {
+                language: language.to_string(),
+                submissions,
+                accepted: problems.len() as u32,
+            })
+            .collect();
+        languages.sort_by(|a, b| {
+            b.submissions
+                .cmp(&a.submissions)
+                .then_with(|| a.language.cmp(&b.language))
+        });
+
+        Ok(UserStats {
+            current_streak,
+            longest_streak,
+            accepted_by_platform,
+            accepted_by_difficulty,
+            accepted_without_difficulty,
+            languages,
+            computed_at: now,
+        })
+    }
+}
+
+impl<R> FetchUserStats for UserStatsUsecase<R>
+where
+    R: UserRepository + SubmissionRepository + ProblemRepository + UserStatsRepository,
+{
+    async fn fetch_user_stats(&self, user_id: &str) -> Result<UserStats> {
+        let user = self.repository.find_by_user_id(user_id).await?;
+        let now = Utc::now().timestamp();
+        let today = day_of(now);
+
+        let fingerprint = self.fingerprint(&user, today).await?;
+        if let Some(cached) = self.repository.get_cached_user_stats(user_id).await? {
+            if cached.fingerprint == fingerprint {
+                // The cache is simply recomputed if it was written by an older version.
+                if let Ok(stats) = serde_json::from_str::<UserStats>(&cached.stats) {
+                    return Ok(stats);
+                }
+            }
+        }
+
+        let stats = self.compute(&user, today, now).await?;
+        self.repository
+            .save_user_stats(
+                user_id,
+                &CachedUserStats {
+                    fingerprint,
+                    stats: serde_json::to_string(&stats)?,
+                },
+                now,
+            )
+            .await?;
+
+        Ok(stats)
+    }
+}
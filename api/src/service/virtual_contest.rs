@@ -0,0 +1,339 @@
+//! UseCase for Virtual Contests
+//!
+//! Users hold private virtual contests with any stored problems and invite other users to them.
+//! The standings are built from the submissions of the participants' linked accounts,
+//! which are synchronized with the platforms on every request while the contest is held.
+//!
+//! Problems can only be chosen from the platforms whose submissions can be fetched.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    domain::{
+        problem::Problem,
+        virtual_contest::{
+            rank_rows, score_participant, Attempt, Standings, StandingsProblem, StandingsRow,
+            VirtualContest, VirtualContestError,
+        },
+        vo::{platform::Platform, scoring_rule::ScoringRule, verdict::Verdict},
+    },
+    infra::repository::{
+        user::UserRepository,
+        virtual_contest::{NewVirtualContest, VirtualContestRepository},
+    },
+    service::{
+        problem::{problem_key, submission_key},
+        submission::{FetchSubmission, PageCondition, SUBMISSION_PLATFORMS},
+    },
+};
+
+/// The maximum number of problems in a contest.
+const MAX_PROBLEMS: usize = 100;
+
+/// The maximum number of participants of a contest, including the owner.
+const MAX_PARTICIPANTS: usize = 100;
+
+/// The maximum duration of a contest in seconds.
+const MAX_DURATION: i64 = 7 * 24 * 60 * 60;
+
+/// The score of a problem by the AtCoder rule if the platform does not give one.
+const DEFAULT_POINT: f64 = 100.0;
+
+pub struct VirtualContestUsecase<R, S>
+where
+    R: VirtualContestRepository + UserRepository,
+    S: FetchSubmission,
+{
+    repository: R,
+    submission_usecase: Arc<S>,
+}
+
+#[trait_variant::make]
+pub trait ManageVirtualContest {
+    async fn get_virtual_contests(&self, user_id: &str) -> Result<Vec<VirtualContest>>;
+    async fn get_virtual_contest(&self, user_id: &str, contest_id: &str) -> Result<VirtualContest>;
+    async fn create_virtual_contest(
+        &self,
+        contest: &NewVirtualContest<'_>,
+    ) -> Result<VirtualContest>;
+    async fn add_participant(
+        &self,
+        user_id: &str,
+        contest_id: &str,
+        participant_id: &str,
+    ) -> Result<VirtualContest>;
+    async fn remove_participant(
+        &self,
+        user_id: &str,
+        contest_id: &str,
+        participant_id: &str,
+    ) -> Result<VirtualContest>;
+    async fn get_standings(&self, user_id: &str, contest_id: &str) -> Result<Standings>;
+}
+
+impl<R, S> VirtualContestUsecase<R, S>
+where
+    R: VirtualContestRepository + UserRepository,
+    S: FetchSubmission,
+{
+    pub fn new(repository: R, submission_usecase: Arc<S>) -> Self {
+        Self {
+            repository,
+            submission_usecase,
+        }
+    }
+
+    async fn get_owned_contest(&self, user_id: &str, contest_id: &str) -> Result<VirtualContest> {
+        let contest = self.get_virtual_contest(user_id, contest_id).await?;
+        if contest.owner_id != user_id {
+            return Err(VirtualContestError::Forbidden.into());
+        }
+
+        Ok(contest)
+    }
+
+    /// Collect the attempts of the participant to the problems from the linked accounts.
+    async fn fetch_attempts(
+        &self,
+        contest: &VirtualContest,
+        problems: &[Problem],
+        participant_id: &str,
+    ) -> Result<(Option<String>, Vec<Attempt>)> {
+        let user = self.repository.find_by_user_id(participant_id).await?;
+
+        // The problems are keyed by the platform and `problem_key`.
+        let positions: HashMap<(String, String), usize> = problems
+            .iter()
+            .enumerate()
+            .map(|(i, p)| ((String::from(p.platform), problem_key(p)), i))
+            .collect();
+
+        let mut attempts = vec![];
+        for platform in SUBMISSION_PLATFORMS.iter() {
+            if !problems.iter().any(|p| p.platform == *platform) {
+                continue;
+            }
+            let Some(handle) = user.platform_username(platform) else {
+                continue;
+            };
+
+            let condition = match platform {
+                Platform::Atcoder => PageCondition::Atcoder {
+                    user: handle,
+                    from_second: Some(contest.start_time as u64),
+                },
+                _ => PageCondition::Other {
+                    user: handle,
+                    page: None,
+                    size: None,
+                },
+            };
+            let submissions = self
+                .submission_usecase
+                .fetch_user_submissions(platform, &condition)
+                .await?;
+
+            attempts.extend(submissions.iter().filter_map(|s| {
+                if !Attempt::counts(&s.verdict) {
+                    return None;
+                }
+                let key = (String::from(s.platform), submission_key(s)?);
+                Some(Attempt {
+                    problem: *positions.get(&key)?,
+                    submitted_at: s.submission_date as i64,
+                    accepted: s.verdict == Verdict::Accepted,
+                })
+            }));
+        }
+
+        Ok((user.username, attempts))
+    }
+}
+
+impl<R, S> ManageVirtualContest for VirtualContestUsecase<R, S>
+where
+    R: VirtualContestRepository + UserRepository,
+    S: FetchSubmission,
+{
+    async fn get_virtual_contests(&self, user_id: &str) -> Result<Vec<VirtualContest>> {
+        self.repository.get_virtual_contests(user_id).await
+    }
+
+    /// Only the owner and the participants can see the contest.
+    async fn get_virtual_contest(&self, user_id: &str, contest_id: &str) -> Result<VirtualContest> {
+        let contest = self
+            .repository
+            .get_virtual_contest(contest_id)
+            .await?
+            .ok_or(VirtualContestError::NotFound)?;
+        if !contest.is_member(user_id) {
+            return Err(VirtualContestError::Forbidden.into());
+        }
+
+        Ok(contest)
+    }
+
+    async fn create_virtual_contest(
+        &self,
+        contest: &NewVirtualContest<'_>,
+    ) -> Result<VirtualContest> {
+        if contest.title.is_empty() {
+            return Err(VirtualContestError::Invalid("The title must not be empty".into()).into());
+        }
+        if contest.duration <= 0 || contest.duration > MAX_DURATION {
+            return Err(VirtualContestError::Invalid(format!(
+                "The duration must be between 1 and {MAX_DURATION} seconds"
+            ))
+            .into());
+        }
+        if contest.problem_ids.is_empty() || contest.problem_ids.len() > MAX_PROBLEMS {
+            return Err(VirtualContestError::Invalid(format!(
+                "The number of problems must be between 1 and {MAX_PROBLEMS}"
+            ))
+            .into());
+        }
+        if contest.participant_ids.len() >= MAX_PARTICIPANTS {
+            return Err(VirtualContestError::Invalid(format!(
+                "The number of participants must be at most {MAX_PARTICIPANTS}"
+            ))
+            .into());
+        }
+        for (i, problem_id) in contest.problem_ids.iter().enumerate() {
+            if contest.problem_ids[..i].contains(problem_id) {
+                return Err(VirtualContestError::Invalid(format!(
+                    "The problem {problem_id} is duplicated"
+                ))
+                .into());
+            }
+            let supported = SUBMISSION_PLATFORMS
+                .iter()
+                .any(|p| problem_id.starts_with(&format!("{}_", String::from(*p))));
+            if !supported {
+                return Err(VirtualContestError::Invalid(format!(
+                    "The submissions to the problem {problem_id} cannot be fetched"
+                ))
+                .into());
+            }
+        }
+
+        self.repository
+            .create_virtual_contest(contest, Utc::now().timestamp())
+            .await
+    }
+
+    /// Only the owner can invite users.
+    async fn add_participant(
+        &self,
+        user_id: &str,
+        contest_id: &str,
+        participant_id: &str,
+    ) -> Result<VirtualContest> {
+        let contest = self.get_owned_contest(user_id, contest_id).await?;
+        if contest.participant_ids.len() >= MAX_PARTICIPANTS {
+            return Err(VirtualContestError::Invalid(format!(
+                "The number of participants must be at most {MAX_PARTICIPANTS}"
+            ))
+            .into());
+        }
+
+        self.repository
+            .add_participant(contest_id, participant_id)
+            .await?;
+
+        self.get_virtual_contest(user_id, contest_id).await
+    }
+
+    /// The owner can remove any participant but themselves, and the participants can leave.
+    async fn remove_participant(
+        &self,
+        user_id: &str,
+        contest_id: &str,
+        participant_id: &str,
+    ) -> Result<VirtualContest> {
+        let contest = self.get_virtual_contest(user_id, contest_id).await?;
+        if participant_id == contest.owner_id
+            || (user_id != contest.owner_id && user_id != participant_id)
+        {
+            return Err(VirtualContestError::Forbidden.into());
+        }
+
+        self.repository
+            .remove_participant(contest_id, participant_id)
+            .await?;
+
+        Ok(VirtualContest {
+            participant_ids: contest
+                .participant_ids
+                .into_iter()
+                .filter(|id| id != participant_id)
+                .collect(),
+            ..contest
+        })
+    }
+
+    /// Build the standings from the submissions made during the contest.
+    ///
+    /// The submissions are not fetched before the contest starts, so everyone scores zero then.
+    async fn get_standings(&self, user_id: &str, contest_id: &str) -> Result<Standings> {
+        let contest = self.get_virtual_contest(user_id, contest_id).await?;
+        let problems = self
+            .repository
+            .get_virtual_contest_problems(contest_id)
+            .await?;
+
+        let points: Vec<f64> = problems
+            .iter()
+            .map(|p| match (contest.scoring, p.platform) {
+                (ScoringRule::Icpc, _) => 1.0,
+                (ScoringRule::Atcoder, Platform::Atcoder | Platform::Codeforces) => p
+                    .raw_point
+                    .filter(|point| *point > 0.0)
+                    .unwrap_or(DEFAULT_POINT),
+                (ScoringRule::Atcoder, _) => DEFAULT_POINT,
+            })
+            .collect();
+
+        let started = Utc::now().timestamp() >= contest.start_time;
+        let mut rows = vec![];
+        for participant_id in contest.participant_ids.iter() {
+            let (username, attempts) = if started {
+                self.fetch_attempts(&contest, &problems, participant_id)
+                    .await?
+            } else {
+                let user = self.repository.find_by_user_id(participant_id).await?;
+                (user.username, vec![])
+            };
+
+            let (results, score, penalty) = score_participant(&contest, &points, &attempts);
+            rows.push(StandingsRow {
+                rank: 0,
+                user_id: participant_id.clone(),
+                username,
+                score,
+                penalty,
+                results,
+            });
+        }
+        rank_rows(&mut rows);
+
+        let problems = problems
+            .into_iter()
+            .zip(points)
+            .map(|(p, point)| StandingsProblem {
+                id: p.id,
+                title: p.title,
+                platform: p.platform,
+                url: p.url,
+                point,
+            })
+            .collect();
+
+        Ok(Standings {
+            contest,
+            problems,
+            rows,
+        })
+    }
+}
@@ -1,36 +1,130 @@
-use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    StatusCode,
+};
 
-pub(crate) async fn get_json<T>(url: &str, client: &Client) -> Result<T>
+use super::http::{Fetched, HttpClient};
+use crate::domain::ingest::IngestError;
+
+pub(crate) async fn get_json<T>(url: &str, client: &HttpClient) -> Result<T, IngestError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    get_json_with_headers(url, HeaderMap::new(), client).await
+}
+
+/// The same as `get_json`, but with the headers such as `Authorization`.
+pub(crate) async fn get_json_with_headers<T>(
+    url: &str,
+    mut headers: HeaderMap,
+    client: &HttpClient,
+) -> Result<T, IngestError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    headers.extend(json_headers());
+    let fetched = client.get(url, headers).await?;
+    check_status(url, &fetched)?;
+
+    decode_json(url, &fetched)
+}
+
+/// The same as `get_json`, but the body is decoded whatever the status is.
+///
+/// Use this for APIs which explain in the body why they refused the request, like Codeforces.
+/// An error page which cannot be decoded is reported by its status.
+pub(crate) async fn get_json_with_error_body<T>(
+    url: &str,
+    client: &HttpClient,
+) -> Result<T, IngestError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let fetched = client.get(url, json_headers()).await?;
+
+    decode_json(url, &fetched).or_else(|e| {
+        check_status(url, &fetched)?;
+        Err(e)
+    })
+}
+
+/// The same as `get_json`, but the body is not downloaded again if the file is unchanged since the last run.
+///
+/// Use this for static files which are large and rarely updated.
+pub(crate) async fn get_json_if_modified<T>(
+    url: &str,
+    client: &HttpClient,
+) -> Result<T, IngestError>
 where
     T: serde::de::DeserializeOwned,
 {
-    client
-        .get(url)
-        .header("accept", "application/json")
-        .send()
-        .await
-        .with_context(|| format!("Failed to fetch {}", url))
-        .unwrap()
-        .json::<T>()
-        .await
-        .with_context(|| format!("Failed to parse json from {}", url))
-}
-
-pub(crate) async fn get_toml<T>(url: &str, client: &Client) -> Result<T>
+    let fetched = client.get_if_modified(url, json_headers()).await?;
+    check_status(url, &fetched)?;
+
+    decode_json(url, &fetched)
+}
+
+fn json_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+    headers
+}
+
+/// Fail with a retryable error unless the request succeeded,
+/// so that an error page is not mistaken for a malformed record.
+fn check_status(url: &str, fetched: &Fetched) -> Result<(), IngestError> {
+    match fetched.status {
+        status if status.is_success() => Ok(()),
+        StatusCode::TOO_MANY_REQUESTS => Err(IngestError::RateLimited {
+            url: url.to_string(),
+        }),
+        status => Err(IngestError::Network {
+            url: url.to_string(),
+            message: format!("Unexpected status code: {status}"),
+        }),
+    }
+}
+
+fn decode_json<T>(url: &str, fetched: &Fetched) -> Result<T, IngestError>
 where
     T: serde::de::DeserializeOwned,
 {
-    let text = client
-        .get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to fetch {}", url))?
-        .text()
-        .await
-        .with_context(|| format!("Failed to get text from {}", url))?;
+    serde_json::from_slice::<T>(&fetched.body).map_err(|e| IngestError::Decode {
+        target: url.to_string(),
+        message: e.to_string(),
+    })
+}
+
+pub(crate) async fn get_toml<T>(url: &str, client: &HttpClient) -> Result<T, IngestError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let fetched = client.get(url, HeaderMap::new()).await?;
+    check_status(url, &fetched)?;
+    let text = String::from_utf8(fetched.body).map_err(|e| IngestError::Decode {
+        target: url.to_string(),
+        message: e.to_string(),
+    })?;
 
-    let toml_data = toml::from_str::<T>(&text).with_context(|| "Failed to deserialize TOML")?;
+    let toml_data = toml::from_str::<T>(&text).map_err(|e| IngestError::Decode {
+        target: url.to_string(),
+        message: e.to_string(),
+    })?;
 
     Ok(toml_data)
 }
+
+/// Check whether the resource exists upstream by the status code of the response.
+pub(crate) async fn exists(url: &str, client: &HttpClient) -> Result<bool, IngestError> {
+    let fetched = client.get(url, HeaderMap::new()).await?;
+
+    match fetched.status {
+        status if status.is_success() => Ok(true),
+        StatusCode::NOT_FOUND => Ok(false),
+        status => Err(IngestError::Network {
+            url: url.to_string(),
+            message: format!("Unexpected status code: {status}"),
+        }),
+    }
+}
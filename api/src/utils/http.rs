@@ -0,0 +1,347 @@
+//! Shared HTTP Layer for the Platform Clients
+//!
+//! Every request to an upstream server goes through `HttpClient`, which
+//! - identifies itself with a descriptive User-Agent,
+//! - applies the timeout and the concurrency cap of the host,
+//! - retries with exponential backoff on 429, 5xx, timeouts and connection errors,
+//! - and, on request, sends `If-None-Match`/`If-Modified-Since` so that unchanged files are not downloaded again.
+//!
+//! The bodies of conditional requests are cached on disk, so that they survive across batch runs.
+//!
+//! The layer is configured by `HttpConfigs` in `config.rs`.
+
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    StatusCode,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    fs,
+    sync::Semaphore,
+    time::{sleep, Duration},
+};
+
+use crate::{config::HttpConfigs, domain::ingest::IngestError};
+
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// The first wait before a retry, doubled on each attempt.
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The limits of the hosts which differ from the defaults.
+const HOST_LIMITS: [(&str, HostLimit); 4] = [
+    // The resource files are tens of megabytes, and the maintainer asks for a gentle access.
+    (
+        "kenkoooo.com",
+        HostLimit {
+            timeout: Duration::from_secs(300),
+            max_concurrency: 1,
+        },
+    ),
+    (
+        "atcoder.jp",
+        HostLimit {
+            timeout: Duration::from_secs(30),
+            max_concurrency: 1,
+        },
+    ),
+    (
+        "codeforces.com",
+        HostLimit {
+            timeout: Duration::from_secs(60),
+            max_concurrency: 1,
+        },
+    ),
+    (
+        "judgeapi.u-aizu.ac.jp",
+        HostLimit {
+            timeout: Duration::from_secs(60),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        },
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HostLimit {
+    pub timeout: Duration,
+    pub max_concurrency: usize,
+}
+
+pub struct HttpConfig {
+    pub user_agent: String,
+    pub max_retries: u32,
+    /// The limit of the hosts not listed in `hosts`.
+    pub default_limit: HostLimit,
+    pub hosts: HashMap<String, HostLimit>,
+    /// Where the bodies of conditional requests are cached. `None` disables the conditional requests.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl From<&HttpConfigs> for HttpConfig {
+    fn from(configs: &HttpConfigs) -> Self {
+        Self {
+            user_agent: configs.user_agent.clone(),
+            max_retries: configs.max_retries,
+            default_limit: HostLimit {
+                timeout: Duration::from_secs(configs.timeout_seconds),
+                max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            },
+            hosts: HOST_LIMITS
+                .iter()
+                .map(|(host, limit)| (host.to_string(), *limit))
+                .collect(),
+            cache_dir: configs.cache_dir.clone(),
+        }
+    }
+}
+
+/// The status and the whole body of a response.
+pub struct Fetched {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+/// The validators of a cached body.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct HttpClient {
+    client: reqwest::Client,
+    config: HttpConfig,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HttpClient {
+    pub fn new(config: HttpConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(&config.user_agent)
+            .build()
+            .expect("Failed to build the HTTP client");
+
+        Self {
+            client,
+            config,
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn host_limit(&self, host: &str) -> HostLimit {
+        self.config
+            .hosts
+            .get(host)
+            .copied()
+            .unwrap_or(self.config.default_limit)
+    }
+
+    fn semaphore(&self, host: &str) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().unwrap();
+        semaphores
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.host_limit(host).max_concurrency)))
+            .clone()
+    }
+
+    /// Send a GET request, retrying on transient failures.
+    ///
+    /// Responses with other statuses, such as 404, are returned as they are.
+    pub async fn get(&self, url: &str, headers: HeaderMap) -> Result<Fetched, IngestError> {
+        let (fetched, _) = self.fetch(url, headers).await?;
+
+        Ok(fetched)
+    }
+
+    /// Send a GET request with the validators of the cached body, and use the cache if the file is unchanged.
+    pub async fn get_if_modified(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<Fetched, IngestError> {
+        let Some(cache_dir) = &self.config.cache_dir else {
+            return self.get(url, headers).await;
+        };
+
+        let key: String = Sha256::digest(url)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let body_path = cache_dir.join(format!("{key}.body"));
+        let meta_path = cache_dir.join(format!("{key}.meta"));
+
+        let mut conditional_headers = headers.clone();
+        if let Some(meta) = fs::read(&meta_path)
+            .await
+            .ok()
+            .and_then(|meta| serde_json::from_slice::<CacheMeta>(&meta).ok())
+        {
+            if let Some(etag) = meta.etag.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                conditional_headers.insert(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = meta
+                .last_modified
+                .and_then(|v| HeaderValue::from_str(&v).ok())
+            {
+                conditional_headers.insert(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let (fetched, response_headers) = self.fetch(url, conditional_headers).await?;
+        if fetched.status == StatusCode::NOT_MODIFIED {
+            if let Ok(body) = fs::read(&body_path).await {
+                log::info!("{url} is not modified, so the cached copy is used.");
+                return Ok(Fetched {
+                    status: StatusCode::OK,
+                    body,
+                });
+            }
+            // The cached body has been lost, so the file is downloaded again.
+            return self.get(url, headers).await;
+        }
+
+        let validator = |name: header::HeaderName| {
+            response_headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let meta = CacheMeta {
+            etag: validator(header::ETAG),
+            last_modified: validator(header::LAST_MODIFIED),
+        };
+        if fetched.status.is_success() && (meta.etag.is_some() || meta.last_modified.is_some()) {
+            let stored = async {
+                fs::create_dir_all(cache_dir).await?;
+                fs::write(&body_path, &fetched.body).await?;
+                fs::write(&meta_path, serde_json::to_vec(&meta)?).await?;
+                anyhow::Ok(())
+            };
+            if let Err(e) = stored.await {
+                log::warn!("Failed to cache {url}: {e}");
+            }
+        }
+
+        Ok(fetched)
+    }
+
+    /// Send a GET request until it succeeds or the retries run out, and return the response headers as well.
+    async fn fetch(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<(Fetched, HeaderMap), IngestError> {
+        let network_error = |message: String| IngestError::Network {
+            url: url.to_string(),
+            message,
+        };
+
+        let host = reqwest::Url::parse(url)
+            .map_err(|e| network_error(e.to_string()))?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        let limit = self.host_limit(&host);
+        let semaphore = self.semaphore(&host);
+
+        let mut attempt = 0;
+        loop {
+            let result = {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .map_err(|e| network_error(e.to_string()))?;
+                self.send(url, &headers, limit.timeout).await
+            };
+
+            let (message, retry_after) = match result {
+                Ok((fetched, response_headers)) if is_transient(fetched.status) => {
+                    if attempt >= self.config.max_retries {
+                        return Err(match fetched.status {
+                            StatusCode::TOO_MANY_REQUESTS => IngestError::RateLimited {
+                                url: url.to_string(),
+                            },
+                            status => network_error(format!("Unexpected status code: {status}")),
+                        });
+                    }
+
+                    let retry_after = response_headers
+                        .get(header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse().ok())
+                        .map(Duration::from_secs);
+                    (format!("status code {}", fetched.status), retry_after)
+                }
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    if attempt >= self.config.max_retries {
+                        return Err(network_error(e.to_string()));
+                    }
+                    (e.to_string(), None)
+                }
+            };
+
+            let wait = backoff(attempt, retry_after);
+            log::warn!("Retrying {url} in {wait:?} after {message}");
+            sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+        timeout: Duration,
+    ) -> Result<(Fetched, HeaderMap), reqwest::Error> {
+        let response = self
+            .client
+            .get(url)
+            .headers(headers.clone())
+            .timeout(timeout)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let response_headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+
+        Ok((Fetched { status, body }, response_headers))
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// The wait before the retry after the attempt, which doubles on each attempt.
+///
+/// The wait requested by the server with `Retry-After` is respected, up to `MAX_BACKOFF`.
+fn backoff(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| BASE_BACKOFF.saturating_mul(2_u32.saturating_pow(attempt)))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0, None), Duration::from_secs(1));
+        assert_eq!(backoff(3, None), Duration::from_secs(8));
+        assert_eq!(backoff(10, None), MAX_BACKOFF);
+        assert_eq!(
+            backoff(0, Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(backoff(0, Some(Duration::from_secs(3600))), MAX_BACKOFF);
+    }
+}
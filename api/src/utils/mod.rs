@@ -1,4 +1,6 @@
 pub mod api;
 pub mod cookie;
 pub mod format;
+pub mod http;
 pub mod jwt;
+pub mod rate_limit;
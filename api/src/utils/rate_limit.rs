@@ -0,0 +1,88 @@
+//! Token Bucket Rate Limiter
+//!
+//! A bucket holds up to `capacity` tokens and gains one token every `interval`.
+//! Each request takes a token, waiting for one if the bucket is empty.
+
+use tokio::{
+    sync::Mutex,
+    time::{sleep, Duration, Instant},
+};
+
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    capacity: f64,
+    interval: Duration,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// The bucket starts full, so that the first `capacity` requests are sent at once.
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                capacity: capacity as f64,
+                interval,
+                tokens: capacity as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a token is available and take it.
+    ///
+    /// The lock is held while waiting, so that the requests are sent in the order they arrive.
+    pub async fn acquire(&self) {
+        let mut bucket = self.bucket.lock().await;
+        while let Some(wait) = bucket.take(Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+}
+
+impl Bucket {
+    /// Take a token, or return how long to wait for the next one.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() / self.interval.as_secs_f64()).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(self.interval.mul_f64(1.0 - self.tokens))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take() {
+        let start = Instant::now();
+        let mut bucket = Bucket {
+            capacity: 1.0,
+            interval: Duration::from_secs(2),
+            tokens: 1.0,
+            refilled_at: start,
+        };
+
+        assert_eq!(bucket.take(start), None);
+        assert_eq!(bucket.take(start), Some(Duration::from_secs(2)));
+        assert_eq!(
+            bucket.take(start + Duration::from_millis(500)),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(bucket.take(start + Duration::from_secs(2)), None);
+        // The bucket never holds more than its capacity.
+        assert_eq!(bucket.take(start + Duration::from_secs(10)), None);
+        assert!(bucket.take(start + Duration::from_secs(10)).is_some());
+    }
+}
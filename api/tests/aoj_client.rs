@@ -0,0 +1,164 @@
+mod common;
+
+use api::{
+    domain::{
+        calibration,
+        ingest::IngestError,
+        vo::{phase::Phase, platform::Platform, verdict::Verdict},
+    },
+    infra::api::aoj::api_client::AojAPIClient,
+};
+use common::StubServer;
+
+#[tokio::test]
+async fn test_get_aoj_problems_and_contests() {
+    let server = StubServer::start(&[
+        ("/aoj/problems/filters", "aoj/problems-filters.json"),
+        ("/aoj/problems/volumes/0", "aoj/problems-volume-0.json"),
+        ("/aoj/challenges", "aoj/challenges.json"),
+        (
+            "/aoj/challenges/cl/JOI/Prelim",
+            "aoj/challenges-cl-JOI-Prelim.json",
+        ),
+    ])
+    .await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_aoj_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "aoj_volume_0_0000",
+            "aoj_volume_0_0001",
+            "aoj_JOI_Prelim_2023_0_0660"
+        ]
+    );
+    assert!(skipped.is_empty());
+
+    let qq = &problems[0];
+    assert_eq!(qq.platform, Platform::Aoj);
+    assert_eq!(qq.contest_id, "aoj_volume_0");
+    assert_eq!(qq.title, "0000. QQ");
+    assert_eq!(qq.category, "Volume 0");
+    assert_eq!(
+        qq.difficulty,
+        calibration::from_aoj_statistics(Some(9000), Some(12000)).map(|d| d.value)
+    );
+    assert_eq!(qq.solver_count, Some(9000));
+    assert_eq!(qq.submissions, Some(12000));
+
+    let joi = &problems[2];
+    assert_eq!(joi.contest_id, "aoj_JOI_Prelim_2023_0");
+    assert_eq!(joi.category, "JOI");
+    assert_eq!(
+        joi.url,
+        "https://onlinejudge.u-aizu.ac.jp/challenges/sources/JOI/Prelim/0660?year=2023"
+    );
+
+    assert_eq!(contests.len(), 2);
+    assert_eq!(contests[0].id, "aoj_volume_0");
+    assert_eq!(contests[0].phase, String::from(Phase::Finished));
+    assert_eq!(contests[0].problems.len(), 2);
+    assert_eq!(contests[1].id, "aoj_JOI_Prelim_2023_0");
+    assert_eq!(
+        contests[1].name,
+        "22nd Japanese Olympiad in Informatics, Preliminary Round 1-1"
+    );
+    assert_eq!(
+        contests[1].url,
+        "https://onlinejudge.u-aizu.ac.jp/challenges/sources/JOI/Prelim?year=2023"
+    );
+    assert_eq!(contests[1].problems.len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_aoj_problems_and_contests_skips_malformed_volume() {
+    let server = StubServer::start(&[
+        ("/aoj/problems/filters", "aoj/problems-filters.json"),
+        // Not a volume
+        ("/aoj/problems/volumes/0", "aoj/challenges.json"),
+        ("/aoj/challenges", "aoj/challenges.json"),
+        (
+            "/aoj/challenges/cl/JOI/Prelim",
+            "aoj/challenges-cl-JOI-Prelim.json",
+        ),
+    ])
+    .await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_aoj_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["aoj_JOI_Prelim_2023_0_0660"]);
+    assert_eq!(contests.len(), 1);
+    assert_eq!(skipped.len(), 1);
+    assert!(
+        matches!(&skipped[0], IngestError::Decode { target, .. } if target.ends_with("/problems/volumes/0"))
+    );
+}
+
+#[tokio::test]
+async fn test_get_aoj_problems_and_contests_fails_on_error_status() {
+    // The volume is answered with 404, which must not be taken for a malformed volume.
+    let server = StubServer::start(&[
+        ("/aoj/problems/filters", "aoj/problems-filters.json"),
+        ("/aoj/challenges", "aoj/challenges.json"),
+        (
+            "/aoj/challenges/cl/JOI/Prelim",
+            "aoj/challenges-cl-JOI-Prelim.json",
+        ),
+    ])
+    .await;
+
+    let error = server
+        .api_client()
+        .get_aoj_problems_and_contests()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_retryable());
+    assert!(
+        matches!(&error, IngestError::Network { url, message } if url.ends_with("/problems/volumes/0") && message.contains("404"))
+    );
+}
+
+#[tokio::test]
+async fn test_get_aoj_user_submissions() {
+    let server = StubServer::start(&[(
+        "/aoj/submission_records/users/judge?page=0&size=2",
+        "aoj/submission-records-user.json",
+    )])
+    .await;
+
+    let submissions = server
+        .api_client()
+        .get_aoj_user_submissions("judge", Some(0), Some(2))
+        .await
+        .unwrap();
+
+    assert_eq!(submissions.len(), 2);
+
+    let s = &submissions[0];
+    assert_eq!(s.id, "aoj_8123456");
+    assert_eq!(s.user_id, "judge");
+    assert_eq!(s.raw_language, "C++17");
+    assert_eq!(s.verdict, Verdict::Accepted);
+    // AOJ reports the CPU time in centiseconds and the date in milliseconds.
+    assert_eq!(s.execution_time, Some(30));
+    assert_eq!(s.submission_date, 1700000000);
+    assert_eq!(s.memory, Some(3456));
+    assert_eq!(s.code_size, Some(210));
+    assert_eq!(s.problem.index.as_deref(), Some("0000"));
+    assert_eq!(s.problem.name.as_deref(), Some("QQ"));
+
+    assert_eq!(submissions[1].verdict, Verdict::TimeLimitExceeded);
+    assert_eq!(submissions[1].problem.name, None);
+}
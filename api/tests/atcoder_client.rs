@@ -0,0 +1,104 @@
+mod common;
+
+use api::{
+    domain::{
+        calibration, ingest::IngestError, vo::phase::Phase, vo::platform::Platform,
+        vo::verdict::Verdict,
+    },
+    infra::api::atcoder::api_client::AtcoderAPIClient,
+};
+use common::StubServer;
+
+#[tokio::test]
+async fn test_get_atcoder_problems_and_contests() {
+    let server = StubServer::start(&[
+        ("/atcoder/resources/contests.json", "atcoder/contests.json"),
+        (
+            "/atcoder/resources/merged-problems.json",
+            "atcoder/merged-problems.json",
+        ),
+        (
+            "/atcoder/resources/problem-models.json",
+            "atcoder/problem-models.json",
+        ),
+    ])
+    .await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_atcoder_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec!["atcoder_abc300_A", "atcoder_abc300_B", "atcoder_ahc020_A"]
+    );
+
+    let a = &problems[0];
+    assert_eq!(a.platform, Platform::Atcoder);
+    assert_eq!(a.contest_id, "atcoder_abc300");
+    assert_eq!(a.title, "A. N-choice question");
+    assert_eq!(a.category, "ABC");
+    assert_eq!(a.raw_point, Some(100.0));
+    assert_eq!(a.difficulty, Some(calibration::clip(-1181.0)));
+    assert_eq!(a.is_experimental, Some(false));
+    assert_eq!(a.solver_count, Some(11243));
+    assert_eq!(a.url, "https://atcoder.jp/contests/abc300/tasks/abc300_a");
+
+    // The points of AHC problems are not reliable.
+    let ahc = &problems[2];
+    assert_eq!(ahc.category, "AHC");
+    assert_eq!(ahc.raw_point, None);
+    assert_eq!(ahc.difficulty, None);
+
+    assert_eq!(
+        skipped,
+        vec![IngestError::OrphanProblem {
+            problem_id: "practice2_a".to_string(),
+            contest_id: Some("practice2".to_string()),
+        }]
+    );
+
+    assert_eq!(contests.len(), 2);
+    let abc = &contests[0];
+    assert_eq!(abc.id, "atcoder_abc300");
+    assert_eq!(abc.phase, String::from(Phase::Finished));
+    assert_eq!(abc.start_time_seconds, Some(1682769600));
+    assert_eq!(abc.duration_seconds, Some(6000));
+    assert_eq!(abc.problems.len(), 2);
+    assert_eq!(contests[1].problems.len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_atcoder_user_submissions() {
+    let server = StubServer::start(&[(
+        "/atcoder/atcoder-api/v3/user/submissions?user=chokudai&from_second=0",
+        "atcoder/user-submissions.json",
+    )])
+    .await;
+
+    let submissions = server
+        .api_client()
+        .get_atcoder_user_submissions("chokudai", None)
+        .await
+        .unwrap();
+
+    assert_eq!(submissions.len(), 3);
+
+    let s = &submissions[0];
+    assert_eq!(s.id, "atcoder_41035588");
+    assert_eq!(s.user_id, "chokudai");
+    assert_eq!(s.raw_language, "C++ (GCC 9.2.1)");
+    assert_eq!(s.verdict, Verdict::Accepted);
+    assert_eq!(s.execution_time, Some(6));
+    assert_eq!(s.code_size, Some(211));
+    assert_eq!(s.submission_date, 1682769659);
+    assert_eq!(s.problem.contest_id.as_deref(), Some("abc300"));
+    assert_eq!(s.problem.index.as_deref(), Some("abc300_a"));
+
+    assert_eq!(submissions[1].verdict, Verdict::WrongAnswer);
+    assert_eq!(submissions[2].verdict, Verdict::Waiting);
+    assert_eq!(submissions[2].execution_time, None);
+}
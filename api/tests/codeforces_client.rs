@@ -0,0 +1,132 @@
+mod common;
+
+use api::{
+    domain::{
+        calibration, ingest::IngestError, vo::phase::Phase, vo::platform::Platform,
+        vo::verdict::Verdict,
+    },
+    infra::api::cf::api_client::CFAPIClient,
+};
+use common::StubServer;
+
+#[tokio::test]
+async fn test_get_cf_problems_and_contests() {
+    let server = StubServer::start(&[
+        (
+            "/codeforces/api/problemset.problems",
+            "codeforces/problemset.problems.json",
+        ),
+        (
+            "/codeforces/api/contest.list",
+            "codeforces/contest.list.json",
+        ),
+    ])
+    .await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_cf_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["codeforces_1800_B", "codeforces_1800_A"]);
+
+    let a = &problems[1];
+    assert_eq!(a.platform, Platform::Codeforces);
+    assert_eq!(a.contest_id, "codeforces_1800");
+    assert_eq!(a.contest_name, "Codeforces Round 855 (Div. 3)");
+    assert_eq!(a.title, "A. Is It a Cat?");
+    assert_eq!(a.category, "Div.3");
+    assert_eq!(a.raw_point, Some(500.0));
+    assert_eq!(
+        a.difficulty,
+        calibration::from_cf_rating(Some(800.0)).map(|d| d.value)
+    );
+    assert_eq!(a.tags, vec!["implementation", "strings"]);
+    assert_eq!(a.solver_count, Some(27532));
+    assert_eq!(a.url, "https://codeforces.com/contest/1800/problem/A");
+
+    // The problems of a contest are listed before the contest finishes.
+    assert_eq!(
+        skipped,
+        vec![IngestError::OrphanProblem {
+            problem_id: "1900A".to_string(),
+            contest_id: Some("1900".to_string()),
+        }]
+    );
+
+    assert_eq!(contests.len(), 1);
+    assert_eq!(contests[0].id, "codeforces_1800");
+    assert_eq!(contests[0].phase, String::from(Phase::Finished));
+    assert_eq!(contests[0].start_time_seconds, Some(1677767700));
+    assert_eq!(contests[0].duration_seconds, Some(8100));
+    assert_eq!(contests[0].problems.len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_cf_upcoming_contests() {
+    let server = StubServer::start(&[(
+        "/codeforces/api/contest.list",
+        "codeforces/contest.list.json",
+    )])
+    .await;
+
+    let contests = server
+        .api_client()
+        .get_cf_upcoming_contests()
+        .await
+        .unwrap();
+
+    assert_eq!(contests.len(), 1);
+    assert_eq!(contests[0].id, "codeforces_1900");
+    assert_eq!(contests[0].phase, String::from(Phase::Before));
+    assert!(contests[0].problems.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_cf_user_submissions() {
+    let server = StubServer::start(&[(
+        "/codeforces/api/user.status?handle=tourist&from=1&count=2",
+        "codeforces/user.status.json",
+    )])
+    .await;
+
+    let submissions = server
+        .api_client()
+        .get_cf_user_submissions("tourist", Some(1), Some(2))
+        .await
+        .unwrap();
+
+    assert_eq!(submissions.len(), 2);
+
+    let s = &submissions[0];
+    assert_eq!(s.id, "codeforces_195812345");
+    assert_eq!(s.user_id, "tourist");
+    assert_eq!(s.raw_language, "GNU C++17");
+    assert_eq!(s.verdict, Verdict::Accepted);
+    assert_eq!(s.execution_time, Some(15));
+    assert_eq!(s.memory, Some(100));
+    assert_eq!(s.submission_date, 1677768000);
+    assert_eq!(s.problem.contest_id.as_deref(), Some("1800"));
+    assert_eq!(s.problem.index.as_deref(), Some("A"));
+    assert_eq!(s.problem.difficulty, Some(800.0));
+
+    assert_eq!(submissions[1].verdict, Verdict::Testing);
+}
+
+#[tokio::test]
+async fn test_cf_user_exists() {
+    // Codeforces refuses the unknown handle with 400 and explains why in the body.
+    let server = StubServer::start_with_statuses(&[(
+        "/codeforces/api/user.info?handles=nobody_here",
+        "codeforces/user.info.not-found.json",
+        "400 Bad Request",
+    )])
+    .await;
+    let client = server.api_client();
+
+    assert!(!client.cf_user_exists("nobody_here").await.unwrap());
+    // The stub answers 404 to the unknown handle, which is not a valid response of the API.
+    assert!(client.cf_user_exists("tourist").await.is_err());
+}
@@ -0,0 +1,133 @@
+//! Stub Server for the Platform Clients
+//!
+//! The stub server replays the responses recorded in `tests/fixtures`,
+//! so that the clients are tested against the real schemas without reaching the upstream servers.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use api::{
+    infra::api::api_client::{ApiClient, BaseUrls},
+    utils::{
+        http::{HostLimit, HttpClient, HttpConfig},
+        rate_limit::RateLimiter,
+    },
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+/// The status, the body and the content type of the response by path.
+type Routes = HashMap<String, (&'static str, Vec<u8>, &'static str)>;
+
+pub struct StubServer {
+    pub url: String,
+}
+
+impl StubServer {
+    /// Serve each fixture at the path, which includes the query string if any.
+    ///
+    /// The other paths are answered with 404.
+    pub async fn start(routes: &[(&str, &str)]) -> Self {
+        let routes: Vec<(&str, &str, &'static str)> = routes
+            .iter()
+            .map(|(path, fixture)| (*path, *fixture, "200 OK"))
+            .collect();
+
+        Self::start_with_statuses(&routes).await
+    }
+
+    /// The same as `start`, but each fixture is served with the status, such as `"400 Bad Request"`.
+    pub async fn start_with_statuses(routes: &[(&str, &str, &'static str)]) -> Self {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let routes: Arc<Routes> = Arc::new(
+            routes
+                .iter()
+                .map(|(path, fixture, status)| {
+                    let body = std::fs::read(fixtures.join(fixture))
+                        .unwrap_or_else(|e| panic!("Failed to read the fixture {fixture}: {e}"));
+                    let content_type = if fixture.ends_with(".toml") {
+                        "text/plain"
+                    } else {
+                        "application/json"
+                    };
+                    (path.to_string(), (*status, body, content_type))
+                })
+                .collect(),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(respond(stream, routes.clone()));
+            }
+        });
+
+        Self { url }
+    }
+
+    /// The base URLs of all the platforms, each under its own prefix on this server.
+    pub fn base_urls(&self) -> BaseUrls {
+        BaseUrls {
+            atcoder_resources: format!("{}/atcoder/resources", self.url),
+            atcoder_statistics: format!("{}/atcoder/atcoder-api/v3", self.url),
+            atcoder: format!("{}/atcoder", self.url),
+            codeforces: format!("{}/codeforces/api", self.url),
+            yukicoder: format!("{}/yukicoder/api/v1", self.url),
+            aoj: format!("{}/aoj", self.url),
+            yoj_categories: format!("{}/yoj/categories.toml", self.url),
+        }
+    }
+
+    /// A client which sends all the requests to this server,
+    /// without the disk cache, the retries or the wait for Codeforces.
+    pub fn api_client(&self) -> ApiClient {
+        let config = HttpConfig {
+            user_agent: "aurora-test".to_string(),
+            max_retries: 0,
+            default_limit: HostLimit {
+                timeout: Duration::from_secs(5),
+                max_concurrency: 4,
+            },
+            hosts: HashMap::new(),
+            cache_dir: None,
+        };
+
+        ApiClient {
+            client: Arc::new(HttpClient::new(config)),
+            cf_limiter: Arc::new(RateLimiter::new(100, Duration::from_millis(1))),
+            cf_credentials: None,
+            base_urls: self.base_urls(),
+        }
+    }
+}
+
+async fn respond(mut stream: TcpStream, routes: Arc<Routes>) {
+    let mut request: Vec<u8> = vec![];
+    let mut buffer = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buffer[..n]),
+        }
+    }
+
+    // e.g., "GET /codeforces/api/user.status?handle=tourist HTTP/1.1"
+    let request = String::from_utf8_lossy(&request);
+    let target = request.split_whitespace().nth(1).unwrap_or_default();
+
+    let (status, body, content_type) = match routes.get(target) {
+        Some((status, body, content_type)) => (*status, body.as_slice(), *content_type),
+        None => ("404 Not Found", b"Not Found".as_slice(), "text/plain"),
+    };
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+
+    let _ = stream.write_all(header.as_bytes()).await;
+    let _ = stream.write_all(body).await;
+    let _ = stream.shutdown().await;
+}
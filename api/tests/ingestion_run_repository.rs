@@ -0,0 +1,122 @@
+//! The ingestion run repository against a database.
+//!
+//! These tests need a PostgreSQL server at `DATABASE_URL`, where `sqlx::test` creates a database for each test.
+//! Run them with `cargo test -- --ignored`.
+
+use sqlx::PgPool;
+
+use api::{
+    domain::{
+        ingestion_run::RunMode,
+        scheduler::{JobRun, LOCK_TIMEOUT_SECONDS},
+        vo::{platform::Platform, user_role::UserRole},
+    },
+    infra::repository::{
+        ingestion_run::IngestionRunRepository, scheduler::SchedulerRepository, user::UserRepository,
+    },
+};
+
+const NOW: i64 = 1_000_000;
+
+fn finished_run(started_at: i64) -> JobRun {
+    JobRun {
+        started_at,
+        finished_at: started_at + 10,
+        error: None,
+        problems: 0,
+        problems_inserted: 0,
+        contests: 0,
+        contests_inserted: 0,
+        skipped: 0,
+    }
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_fail_stale_ingestion_runs(pool: PgPool) -> anyhow::Result<()> {
+    // Finished
+    let finished = pool
+        .create_ingestion_run(&Platform::Atcoder, RunMode::Full, None, None, NOW - 100)
+        .await?;
+    pool.finish_ingestion_run(finished.id, &finished_run(NOW - 100))
+        .await?;
+
+    // Still running, holding the lock
+    assert!(pool.try_lock_job(&Platform::Codeforces, NOW - 100).await?);
+    let running = pool
+        .create_ingestion_run(&Platform::Codeforces, RunMode::Full, None, None, NOW - 100)
+        .await?;
+
+    // Left by a crash, whose lock has been released by a later run
+    let crashed = pool
+        .create_ingestion_run(&Platform::Aoj, RunMode::Full, None, None, NOW - 200)
+        .await?;
+    assert!(pool.try_lock_job(&Platform::Aoj, NOW - 100).await?);
+    pool.unlock_job(&Platform::Aoj, &finished_run(NOW - 100))
+        .await?;
+
+    // Left by a crash, whose lock has timed out
+    let timed_out_at = NOW - LOCK_TIMEOUT_SECONDS;
+    assert!(
+        pool.try_lock_job(&Platform::Yukicoder, timed_out_at)
+            .await?
+    );
+    let timed_out = pool
+        .create_ingestion_run(
+            &Platform::Yukicoder,
+            RunMode::Full,
+            None,
+            None,
+            timed_out_at,
+        )
+        .await?;
+
+    assert_eq!(pool.fail_stale_ingestion_runs(NOW).await?, 2);
+
+    let runs = pool.get_ingestion_runs(None, false, 10).await?;
+    let run = |id: i64| runs.iter().find(|run| run.id == id).unwrap();
+    assert_eq!(run(finished.id).succeeded, Some(true));
+    assert_eq!(run(running.id).finished_at, None);
+    for id in [crashed.id, timed_out.id] {
+        assert_eq!(run(id).finished_at, Some(NOW));
+        assert_eq!(run(id).succeeded, Some(false));
+        assert!(run(id).error.is_some());
+    }
+
+    // Nothing is left to fail.
+    assert_eq!(pool.fail_stale_ingestion_runs(NOW).await?, 0);
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_update_role(pool: PgPool) -> anyhow::Result<()> {
+    sqlx::query("INSERT INTO internal_users (id, username) VALUES ('alice', 'alice')")
+        .execute(&pool)
+        .await?;
+    assert_eq!(pool.find_by_user_id("alice").await?.role, UserRole::Member);
+
+    let user = pool.update_role("alice", UserRole::Admin).await?;
+    assert_eq!(user.role, UserRole::Admin);
+    assert_eq!(pool.find_by_user_id("alice").await?.role, UserRole::Admin);
+
+    // No user to grant the role to
+    assert!(pool.update_role("bob", UserRole::Admin).await.is_err());
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_unknown_run_mode_is_error(pool: PgPool) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO ingestion_runs (platform, mode, started_at) VALUES ('atcoder', 'partial', 100)",
+    )
+    .execute(&pool)
+    .await?;
+
+    assert!(pool.get_ingestion_runs(None, false, 10).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! The migrations against a database created from the former `db/table.sql` and `db/seed.sql`.
+//!
+//! These tests need a PostgreSQL server at `DATABASE_URL`, where `sqlx::test` creates a database for each test.
+//! Run them with `cargo test -- --ignored`.
+
+use sqlx::{Executor, PgPool, Row};
+
+use api::{
+    domain::vo::providers::AuthProvider,
+    infra::repository::{initialize_pool::run_migrations, user::UserRepository},
+};
+
+const BASELINE_TABLES: &str = include_str!("fixtures/db/baseline_table.sql");
+const BASELINE_SEED: &str = include_str!("fixtures/db/baseline_seed.sql");
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test(migrations = false)]
+async fn test_migrate_baseline_database(pool: PgPool) -> anyhow::Result<()> {
+    pool.execute(BASELINE_TABLES).await?;
+    pool.execute(BASELINE_SEED).await?;
+    pool.execute(
+        r"
+        INSERT INTO internal_users (id, google_id, google_username)
+        VALUES ('user_1', 'google_1', 'user@example.com')
+        ",
+    )
+    .await?;
+
+    run_migrations(&pool).await?;
+
+    let ja_name: String = sqlx::query("SELECT ja_name FROM algorithms WHERE id = '1'")
+        .fetch_one(&pool)
+        .await?
+        .get("ja_name");
+    assert_eq!(ja_name, "実装");
+
+    let user = pool
+        .find_by_provider_user_id(&AuthProvider::Google, "google_1")
+        .await?
+        .expect("The existing user is kept");
+    assert_eq!(user.id, "user_1");
+    assert_eq!(user.google_email.as_deref(), Some("user@example.com"));
+
+    // The users are upserted on the unique IDs of their accounts.
+    let user = pool
+        .create_or_update(&AuthProvider::Google, "google_1", Some("new@example.com"))
+        .await?;
+    assert_eq!(user.id, "user_1");
+    assert_eq!(user.google_email.as_deref(), Some("new@example.com"));
+
+    sqlx::query("SELECT difficulty_confidence FROM problems")
+        .fetch_all(&pool)
+        .await?;
+
+    // Applying the migrations again changes nothing.
+    run_migrations(&pool).await?;
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test(migrations = false)]
+async fn test_migrate_empty_database(pool: PgPool) -> anyhow::Result<()> {
+    run_migrations(&pool).await?;
+
+    let algorithms: i64 = sqlx::query("SELECT COUNT(*) AS count FROM algorithms")
+        .fetch_one(&pool)
+        .await?
+        .get("count");
+    assert!(algorithms > 0);
+
+    Ok(())
+}
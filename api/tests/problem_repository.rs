@@ -0,0 +1,284 @@
+//! The problem repository against a database.
+//!
+//! These tests need a PostgreSQL server at `DATABASE_URL`, where `sqlx::test` creates a database for each test.
+//! Run them with `cargo test -- --ignored`.
+
+use sqlx::{PgPool, Row};
+
+use api::{
+    domain::{
+        calibration::{self, NormalizedDifficulty},
+        problem::Problem,
+        vo::{difficulty_confidence::DifficultyConfidence, platform::Platform},
+    },
+    infra::repository::problem::{ProblemRepository, SearchCondition},
+};
+
+fn problem(difficulty: Option<NormalizedDifficulty>, tags: Vec<String>) -> Problem {
+    Problem::reconstruct(
+        Platform::Codeforces,
+        "1000",
+        "Codeforces Round 1000",
+        "A",
+        "Theatre Square",
+        None,
+        difficulty,
+        "Div. 2".to_string(),
+        None,
+        tags,
+        "https://codeforces.com/contest/1000/problem/A",
+        None,
+        None,
+    )
+}
+
+async fn stored_difficulty(pool: &PgPool) -> (Option<f64>, Option<String>) {
+    let row = sqlx::query(
+        "SELECT difficulty, difficulty_confidence FROM problems WHERE id = 'codeforces_1000_A'",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    (row.get("difficulty"), row.get("difficulty_confidence"))
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_update_problems_keeps_estimated_difficulty(pool: PgPool) -> anyhow::Result<()> {
+    assert_eq!(pool.update_problems(&[problem(None, vec![])]).await?, 1);
+
+    let estimate = NormalizedDifficulty {
+        value: 1200.0,
+        confidence: DifficultyConfidence::Medium,
+    };
+    pool.update_estimated_difficulties(&[("codeforces_1000_A".to_string(), estimate, false)])
+        .await?;
+
+    // The next ingestion finds no difficulty upstream.
+    assert_eq!(pool.update_problems(&[problem(None, vec![])]).await?, 0);
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(1200.0), Some("medium".to_string()))
+    );
+
+    // A difficulty given upstream replaces the estimate.
+    let upstream = NormalizedDifficulty {
+        value: 800.0,
+        confidence: DifficultyConfidence::High,
+    };
+    pool.update_problems(&[problem(Some(upstream), vec![])])
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(800.0), Some("high".to_string()))
+    );
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_estimates_keep_upstream_difficulty(pool: PgPool) -> anyhow::Result<()> {
+    // The Codeforces rating, which is of medium confidence
+    let rating = calibration::from_cf_rating(Some(1200.0));
+    pool.update_problems(&[problem(rating, vec![])]).await?;
+
+    let estimate = NormalizedDifficulty {
+        value: 1500.0,
+        confidence: DifficultyConfidence::Medium,
+    };
+    pool.update_estimated_difficulties(&[("codeforces_1000_A".to_string(), estimate, false)])
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(700.0), Some("medium".to_string()))
+    );
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_estimates_replace_estimates(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_problems(&[problem(None, vec![])]).await?;
+
+    let estimate = |value: f64, is_experimental: bool| {
+        let confidence = if is_experimental {
+            DifficultyConfidence::Low
+        } else {
+            DifficultyConfidence::Medium
+        };
+        let difficulty = NormalizedDifficulty { value, confidence };
+        [("codeforces_1000_A".to_string(), difficulty, is_experimental)]
+    };
+
+    pool.update_estimated_difficulties(&estimate(1000.0, true))
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(1000.0), Some("low".to_string()))
+    );
+
+    // A non-experimental estimate replaces an experimental one.
+    pool.update_estimated_difficulties(&estimate(1200.0, false))
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(1200.0), Some("medium".to_string()))
+    );
+
+    // But not the other way around.
+    pool.update_estimated_difficulties(&estimate(1400.0, true))
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(1200.0), Some("medium".to_string()))
+    );
+
+    // The later estimate replaces the earlier one.
+    pool.update_estimated_difficulties(&estimate(1300.0, false))
+        .await?;
+    assert_eq!(
+        stored_difficulty(&pool).await,
+        (Some(1300.0), Some("medium".to_string()))
+    );
+
+    Ok(())
+}
+
+async fn stored_tag_ids(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT technical_tag_id FROM problem_tags WHERE problem_id = 'codeforces_1000_A' ORDER BY technical_tag_id",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_update_problems_replaces_tags(pool: PgPool) -> anyhow::Result<()> {
+    let tags = vec![
+        "math".to_string(),
+        "Greedy ".to_string(),
+        "unknown".to_string(),
+    ];
+    pool.update_problems(&[problem(None, tags)]).await?;
+    // Mapped through tag_aliases, where the unknown tag is ignored.
+    assert_eq!(stored_tag_ids(&pool).await, vec!["2", "3"]);
+
+    // A tag removed upstream is removed, and a tag added upstream is added.
+    let tags = vec!["greedy".to_string(), "dp".to_string()];
+    pool.update_problems(&[problem(None, tags)]).await?;
+    assert_eq!(stored_tag_ids(&pool).await, vec!["3", "4"]);
+
+    pool.update_problems(&[problem(None, vec![])]).await?;
+    assert!(stored_tag_ids(&pool).await.is_empty());
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_update_problems_keeps_approved_tags(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_problems(&[problem(None, vec!["math".to_string()])])
+        .await?;
+
+    // "Implementation", attached by an approved proposal
+    sqlx::query(
+        "INSERT INTO internal_users (id, username, role) VALUES ('alice', 'alice', 'member')",
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        "INSERT INTO tag_proposals (id, problem_id, technical_tag_id, proposer_id, status, created_at) VALUES ('1', 'codeforces_1000_A', '1', 'alice', 'approved', 100)",
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        "INSERT INTO problem_tags (problem_id, technical_tag_id) VALUES ('codeforces_1000_A', '1')",
+    )
+    .execute(&pool)
+    .await?;
+
+    pool.update_problems(&[problem(None, vec![])]).await?;
+    assert_eq!(stored_tag_ids(&pool).await, vec!["1"]);
+
+    Ok(())
+}
+
+fn titled_problem(contest_id: &str, name: &str, title: &str) -> Problem {
+    Problem::reconstruct_from_db(
+        format!("codeforces_{contest_id}_A"),
+        format!("codeforces_{contest_id}"),
+        format!("Codeforces Round {contest_id}"),
+        "A".to_string(),
+        name.to_string(),
+        title.to_string(),
+        Platform::Codeforces,
+        "Div. 2".to_string(),
+        None,
+        None,
+        None,
+        None,
+        vec![],
+        format!("https://codeforces.com/contest/{contest_id}/problem/A"),
+        None,
+        None,
+        None,
+    )
+}
+
+async fn search(pool: &PgPool, query: &str) -> anyhow::Result<Vec<String>> {
+    let condition = SearchCondition {
+        query,
+        platform: None,
+        page: None,
+        page_size: None,
+    };
+    let problems = pool.search_problems(&condition).await?;
+
+    Ok(problems.into_iter().map(|problem| problem.id).collect())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_search_problems_by_title(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_problems(&[
+        titled_problem("1000", "Square Root", "A. Square Root"),
+        titled_problem("1001", "Square", "A. Square"),
+        titled_problem("1002", "劇場広場", "A. Theatre Plaza"),
+    ])
+    .await?;
+
+    // The exact match of the title ranks first, then the prefix match.
+    assert_eq!(
+        search(&pool, "A. Square").await?,
+        vec!["codeforces_1001_A", "codeforces_1000_A"]
+    );
+
+    // The title matches by the trigram similarity as well, even with a typo.
+    assert_eq!(
+        search(&pool, "Theater Plaza").await?,
+        vec!["codeforces_1002_A"]
+    );
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_get_problem_by_id(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_problems(&[problem(None, vec!["math".to_string()])])
+        .await?;
+
+    let problem = pool.get_problem_by_id("codeforces_1000_A").await?;
+    assert_eq!(problem.name, "Theatre Square");
+    assert_eq!(problem.category, "Div. 2");
+    assert_eq!(problem.tags, vec!["Math"]);
+
+    assert!(pool.get_problem_by_id("codeforces_1000_Z").await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! The submission repository against a database.
+//!
+//! These tests need a PostgreSQL server at `DATABASE_URL`, where `sqlx::test` creates a database for each test.
+//! Run them with `cargo test -- --ignored`.
+
+use sqlx::PgPool;
+
+use api::{
+    domain::{
+        submission::Submission,
+        vo::{platform::Platform, verdict::Verdict},
+    },
+    infra::repository::submission::{Condition, SubmissionRepository},
+};
+
+fn submission(id: u64) -> Submission {
+    Submission::reconstruct(
+        Platform::Aoj,
+        id.to_string(),
+        "alice".to_string(),
+        "C++".to_string(),
+        Verdict::Accepted,
+        None,
+        None,
+        None,
+        id,
+        None,
+        Some("ITP1_1_A".to_string()),
+        None,
+        None,
+        None,
+    )
+}
+
+async fn page_ids(pool: &PgPool, page: Option<i32>, page_size: Option<i32>) -> Vec<String> {
+    let condition = Condition {
+        platform: Platform::Aoj,
+        user_id: "alice",
+        from_second: None,
+        page,
+        page_size,
+    };
+
+    pool.get_submissions_by_condition(&condition)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.raw_id)
+        .collect()
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_get_submissions_by_page(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_submissions(&(1..=5).map(submission).collect::<Vec<_>>())
+        .await?;
+
+    assert_eq!(page_ids(&pool, None, None).await, ["5", "4", "3", "2", "1"]);
+    assert_eq!(page_ids(&pool, Some(0), Some(2)).await, ["5", "4"]);
+    assert_eq!(page_ids(&pool, None, Some(2)).await, ["5", "4"]);
+    assert_eq!(page_ids(&pool, Some(2), Some(2)).await, ["1"]);
+    assert!(page_ids(&pool, Some(3), Some(2)).await.is_empty());
+    // Negative pages and sizes never reach the database as a negative OFFSET or LIMIT.
+    assert_eq!(page_ids(&pool, Some(-1), Some(2)).await, ["5", "4"]);
+    assert!(page_ids(&pool, Some(1), Some(-2)).await.is_empty());
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_handles_match_regardless_of_case(pool: PgPool) -> anyhow::Result<()> {
+    pool.update_submissions(&[submission(1), submission(2)])
+        .await?;
+
+    let condition = Condition {
+        platform: Platform::Aoj,
+        user_id: "Alice",
+        from_second: None,
+        page: None,
+        page_size: None,
+    };
+    assert_eq!(
+        pool.get_submissions_by_condition(&condition).await?.len(),
+        2
+    );
+    assert_eq!(
+        pool.get_last_submission_date(&Platform::Aoj, "ALICE")
+            .await?,
+        Some(2)
+    );
+    assert_eq!(
+        pool.get_submission_summary(&Platform::Aoj, "Alice")
+            .await?
+            .count,
+        2
+    );
+
+    Ok(())
+}
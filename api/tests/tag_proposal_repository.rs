@@ -0,0 +1,218 @@
+//! The tag proposal workflow and the technical tag repository against a database.
+//!
+//! These tests need a PostgreSQL server at `DATABASE_URL`, where `sqlx::test` creates a database for each test.
+//! Run them with `cargo test -- --ignored`.
+
+use sqlx::PgPool;
+
+use api::{
+    domain::{
+        problem::Problem,
+        tag_proposal::{TagProposalAction, TagProposalError},
+        vo::{platform::Platform, proposal_status::ProposalStatus},
+    },
+    infra::repository::{
+        problem::ProblemRepository, tag_proposal::TagProposalRepository,
+        technical_tag::TechnicalTagRepositoryTrait,
+    },
+};
+
+const PROBLEM_ID: &str = "codeforces_1000_A";
+/// "Implementation" in the seed
+const TAG_ID: &str = "1";
+
+/// Store a problem and the users who propose and review tags for it.
+async fn setup(pool: &PgPool) -> anyhow::Result<()> {
+    let problem = Problem::reconstruct(
+        Platform::Codeforces,
+        "1000",
+        "Codeforces Round 1000",
+        "A",
+        "Theatre Square",
+        None,
+        None,
+        "Div. 2".to_string(),
+        None,
+        vec![],
+        "https://codeforces.com/contest/1000/problem/A",
+        None,
+        None,
+    );
+    pool.update_problems(&[problem]).await?;
+
+    sqlx::query(
+        "INSERT INTO internal_users (id, username, role) VALUES ('alice', 'alice', 'member'), ('bob', 'bob', 'member'), ('admin', 'admin', 'admin')",
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+fn proposal_error(error: anyhow::Error) -> Option<TagProposalError> {
+    error.downcast_ref::<TagProposalError>().cloned()
+}
+
+async fn problem_tag_ids(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT technical_tag_id FROM problem_tags WHERE problem_id = $1 ORDER BY technical_tag_id",
+    )
+    .bind(PROBLEM_ID)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_create_duplicated_proposal(pool: PgPool) -> anyhow::Result<()> {
+    setup(&pool).await?;
+
+    let proposal = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "alice", 100)
+        .await?;
+    assert_eq!(proposal.status, ProposalStatus::Pending);
+
+    // The same tag cannot be proposed again while the proposal is pending, even by another user.
+    let error = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "bob", 200)
+        .await
+        .unwrap_err();
+    assert_eq!(proposal_error(error), Some(TagProposalError::Duplicated));
+
+    // After the rejection, it can be proposed again.
+    pool.review_proposal(&proposal.id, "admin", false, None, 300)
+        .await?;
+    let proposal = pool.create_proposal(PROBLEM_ID, TAG_ID, "bob", 400).await?;
+    assert_eq!(proposal.proposer_id, "bob");
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_vote_proposal(pool: PgPool) -> anyhow::Result<()> {
+    setup(&pool).await?;
+
+    let proposal = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "alice", 100)
+        .await?;
+
+    pool.vote_proposal(&proposal.id, "bob", true, 200).await?;
+    // The second vote of the same user replaces the first one.
+    pool.vote_proposal(&proposal.id, "bob", false, 300).await?;
+    let proposal = pool
+        .vote_proposal(&proposal.id, "alice", false, 400)
+        .await?;
+    assert_eq!(proposal.score, -2);
+
+    pool.review_proposal(&proposal.id, "admin", true, None, 500)
+        .await?;
+
+    // A reviewed proposal can no longer be voted on or reviewed.
+    let error = pool
+        .vote_proposal(&proposal.id, "bob", true, 600)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        proposal_error(error),
+        Some(TagProposalError::AlreadyReviewed)
+    );
+    let error = pool
+        .review_proposal(&proposal.id, "admin", false, None, 700)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        proposal_error(error),
+        Some(TagProposalError::AlreadyReviewed)
+    );
+
+    let error = pool
+        .vote_proposal("unknown", "bob", true, 800)
+        .await
+        .unwrap_err();
+    assert_eq!(proposal_error(error), Some(TagProposalError::NotFound));
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_approve_proposal(pool: PgPool) -> anyhow::Result<()> {
+    setup(&pool).await?;
+
+    let proposal = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "alice", 100)
+        .await?;
+    let proposal = pool
+        .review_proposal(&proposal.id, "admin", true, Some("Looks right"), 200)
+        .await?;
+
+    assert_eq!(proposal.status, ProposalStatus::Approved);
+    assert_eq!(proposal.reviewer_id.as_deref(), Some("admin"));
+    assert_eq!(proposal.reviewed_at, Some(200));
+    assert_eq!(problem_tag_ids(&pool).await, vec![TAG_ID]);
+
+    let events = pool.get_proposal_events(&proposal.id).await?;
+    let actions: Vec<String> = events.iter().map(|e| e.action.clone()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            String::from(TagProposalAction::Propose),
+            String::from(TagProposalAction::Approve)
+        ]
+    );
+    assert_eq!(events[1].comment.as_deref(), Some("Looks right"));
+
+    // The attached tag cannot be proposed again.
+    let error = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "bob", 300)
+        .await
+        .unwrap_err();
+    assert_eq!(proposal_error(error), Some(TagProposalError::Duplicated));
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_reject_proposal(pool: PgPool) -> anyhow::Result<()> {
+    setup(&pool).await?;
+
+    let proposal = pool
+        .create_proposal(PROBLEM_ID, TAG_ID, "alice", 100)
+        .await?;
+    let proposal = pool
+        .review_proposal(&proposal.id, "admin", false, None, 200)
+        .await?;
+
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(proposal.reviewer_id.as_deref(), Some("admin"));
+    assert!(problem_tag_ids(&pool).await.is_empty());
+
+    Ok(())
+}
+
+#[ignore = "needs PostgreSQL at DATABASE_URL"]
+#[sqlx::test]
+async fn test_create_tags_concurrently(pool: PgPool) -> anyhow::Result<()> {
+    let seeded_max: i64 = sqlx::query_scalar("SELECT MAX(id::BIGINT) FROM technical_tags")
+        .fetch_one(&pool)
+        .await?;
+
+    let ids = tokio::try_join!(
+        pool.create_tag("Tag 1", "タグ 1", "1"),
+        pool.create_tag("Tag 2", "タグ 2", "1"),
+        pool.create_tag("Tag 3", "タグ 3", "1"),
+        pool.create_tag("Tag 4", "タグ 4", "1"),
+    )?;
+
+    let mut ids: Vec<i64> = [ids.0, ids.1, ids.2, ids.3]
+        .iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, (seeded_max + 1..=seeded_max + 4).collect::<Vec<i64>>());
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use api::{
+    domain::{
+        ingest::IngestError,
+        vo::{phase::Phase, platform::Platform},
+    },
+    infra::api::yoj::api_client::YOJAPIClient,
+};
+use common::StubServer;
+
+#[tokio::test]
+async fn test_get_yoj_problems_and_contests() {
+    let server = StubServer::start(&[("/yoj/categories.toml", "yoj/categories.toml")]).await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_yoj_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "yosupo_online_judge_Graph_A",
+            "yosupo_online_judge_Graph_B",
+            "yosupo_online_judge_Data Structure_A"
+        ]
+    );
+    // A name which cannot be a part of the URL is skipped.
+    assert_eq!(skipped.len(), 1);
+    assert!(
+        matches!(&skipped[0], IngestError::Decode { message, .. } if message.contains("Broken Name"))
+    );
+
+    let shortest_path = &problems[0];
+    assert_eq!(shortest_path.platform, Platform::YOJ);
+    assert_eq!(shortest_path.contest_id, "yosupo_online_judge_Graph");
+    assert_eq!(shortest_path.title, "A. Shortest Path");
+    assert_eq!(shortest_path.category, "Graph");
+    assert_eq!(shortest_path.difficulty, None);
+    assert_eq!(
+        shortest_path.url,
+        "https://judge.yosupo.jp/problem/shortest_path"
+    );
+
+    assert_eq!(contests.len(), 2);
+    assert_eq!(contests[0].id, "yosupo_online_judge_Graph");
+    assert_eq!(contests[0].phase, String::from(Phase::Finished));
+    assert_eq!(contests[0].problems.len(), 2);
+    assert_eq!(contests[1].category, "Data Structure");
+    assert_eq!(contests[1].problems.len(), 1);
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use api::{
+    domain::{
+        calibration,
+        ingest::IngestError,
+        vo::{phase::Phase, platform::Platform, watermark::Watermark},
+    },
+    infra::api::yuki::api_client::YukicoderAPIClient,
+};
+use common::StubServer;
+
+const PROBLEM_ROUTES: [(&str, &str); 7] = [
+    ("/yukicoder/api/v1/problems", "yukicoder/problems.json"),
+    (
+        "/yukicoder/api/v1/contest/past",
+        "yukicoder/contest-past.json",
+    ),
+    (
+        "/yukicoder/api/v1/contest/current",
+        "yukicoder/contest-current.json",
+    ),
+    (
+        "/yukicoder/api/v1/contest/future",
+        "yukicoder/contest-future.json",
+    ),
+    (
+        "/yukicoder/api/v1/problems/9001",
+        "yukicoder/problem-9001.json",
+    ),
+    (
+        "/yukicoder/api/v1/problems/9002",
+        "yukicoder/problem-9002.json",
+    ),
+    (
+        "/yukicoder/api/v1/problems/9003",
+        "yukicoder/problem-9003.json",
+    ),
+];
+
+#[tokio::test]
+async fn test_get_yuki_problems_and_contests() {
+    let server = StubServer::start(&PROBLEM_ROUTES).await;
+
+    let (problems, contests, skipped) = server
+        .api_client()
+        .get_yuki_problems_and_contests()
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["yukicoder_400_A", "yukicoder_400_B"]);
+
+    let b = &problems[1];
+    assert_eq!(b.platform, Platform::Yukicoder);
+    assert_eq!(b.contest_id, "yukicoder_400");
+    assert_eq!(b.contest_name, "yukicoder contest 400");
+    assert_eq!(b.title, "B. Path Counting");
+    assert_eq!(b.category, "Normal");
+    assert_eq!(b.raw_point, Some(3.0));
+    assert_eq!(
+        b.difficulty,
+        calibration::from_yuki_level(Some(3.0)).map(|d| d.value)
+    );
+    assert_eq!(b.tags, vec!["DP", "グラフ"]);
+    assert_eq!(b.solver_count, Some(120));
+    assert_eq!(b.submissions, Some(300));
+    assert_eq!(b.url, "https://yukicoder.me/problems/no/2301");
+
+    // A problem without statistics is skipped, and so is a problem outside the contests.
+    assert_eq!(skipped.len(), 2);
+    assert!(
+        matches!(&skipped[0], IngestError::Decode { target, .. } if target.ends_with("/problems/9003"))
+    );
+    assert_eq!(
+        skipped[1],
+        IngestError::OrphanProblem {
+            problem_id: "9099".to_string(),
+            contest_id: None,
+        }
+    );
+
+    assert_eq!(contests.len(), 1);
+    assert_eq!(contests[0].id, "yukicoder_400");
+    assert_eq!(contests[0].phase, String::from(Phase::Finished));
+    assert_eq!(contests[0].duration_seconds, Some(7200));
+    assert_eq!(contests[0].problems.len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_yuki_problems_and_contests_after() {
+    let server = StubServer::start(&PROBLEM_ROUTES).await;
+
+    let api_client = server.api_client();
+    let catalog = api_client.get_yuki_catalog().await.unwrap();
+
+    // The batch is extended to the whole contest.
+    let (problems, contests, skipped, watermark) = api_client
+        .get_yuki_problems_and_contests_after(&catalog, None, 1)
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["yukicoder_400_A", "yukicoder_400_B"]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(contests.len(), 1);
+    assert_eq!(contests[0].problems.len(), 2);
+    assert_eq!(watermark, Some(Watermark::new(1683289200, 9003)));
+
+    let (problems, contests, skipped, watermark) = api_client
+        .get_yuki_problems_and_contests_after(&catalog, watermark.as_ref(), 1)
+        .await
+        .unwrap();
+
+    // A problem which no contest lists is passed over, unlike those of the running contests.
+    assert!(problems.is_empty());
+    assert!(contests.is_empty());
+    assert_eq!(
+        skipped,
+        vec![IngestError::OrphanProblem {
+            problem_id: "9099".to_string(),
+            contest_id: None,
+        }]
+    );
+    assert_eq!(watermark, Some(Watermark::new(1685545200, 9099)));
+}
+
+#[tokio::test]
+async fn test_get_yuki_problems_and_contests_after_stops_at_retryable_failure() {
+    // The details of 9002 are not found.
+    let routes: Vec<(&str, &str)> = PROBLEM_ROUTES
+        .into_iter()
+        .filter(|(path, _)| !path.ends_with("/9002"))
+        .collect();
+    let server = StubServer::start(&routes).await;
+    let api_client = server.api_client();
+    let catalog = api_client.get_yuki_catalog().await.unwrap();
+
+    let (problems, _, skipped, watermark) = api_client
+        .get_yuki_problems_and_contests_after(&catalog, None, 10)
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["yukicoder_400_A"]);
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0].is_retryable());
+    // The watermark stays before 9002, so that it is fetched again on the next run.
+    assert_eq!(watermark, Some(Watermark::new(1683289200, 9001)));
+}
+
+#[tokio::test]
+async fn test_get_yuki_problems_and_contests_after_stops_at_unfinished_contest() {
+    // 9099 belongs to a contest running now, which is not in the past contests yet.
+    let routes: Vec<(&str, &str)> = PROBLEM_ROUTES
+        .into_iter()
+        .map(|(path, fixture)| match path {
+            "/yukicoder/api/v1/contest/current" => (path, "yukicoder/contest-current-9099.json"),
+            _ => (path, fixture),
+        })
+        .collect();
+    let server = StubServer::start(&routes).await;
+    let api_client = server.api_client();
+    let catalog = api_client.get_yuki_catalog().await.unwrap();
+
+    let (problems, _, skipped, watermark) = api_client
+        .get_yuki_problems_and_contests_after(&catalog, None, 10)
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = problems.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["yukicoder_400_A", "yukicoder_400_B"]);
+    assert_eq!(
+        skipped.last(),
+        Some(&IngestError::OrphanProblem {
+            problem_id: "9099".to_string(),
+            contest_id: None,
+        })
+    );
+    // The watermark stays before 9099, so that it is fetched once the contest is over.
+    assert_eq!(watermark, Some(Watermark::new(1683289200, 9003)));
+
+    let (problems, _, _, watermark) = api_client
+        .get_yuki_problems_and_contests_after(&catalog, watermark.as_ref(), 10)
+        .await
+        .unwrap();
+    assert!(problems.is_empty());
+    assert_eq!(watermark, None);
+}
+
+#[tokio::test]
+async fn test_get_yuki_catalog_reports_invalid_dates() {
+    let server = StubServer::start(&[
+        (
+            "/yukicoder/api/v1/problems",
+            "yukicoder/problems-invalid-date.json",
+        ),
+        (
+            "/yukicoder/api/v1/contest/past",
+            "yukicoder/contest-past.json",
+        ),
+        (
+            "/yukicoder/api/v1/contest/current",
+            "yukicoder/contest-current.json",
+        ),
+        (
+            "/yukicoder/api/v1/contest/future",
+            "yukicoder/contest-future.json",
+        ),
+    ])
+    .await;
+
+    let catalog = server.api_client().get_yuki_catalog().await.unwrap();
+
+    assert_eq!(catalog.skipped.len(), 1);
+    assert!(
+        matches!(&catalog.skipped[0], IngestError::Decode { target, .. } if target.ends_with("problem 9004"))
+    );
+}
+
+#[tokio::test]
+async fn test_get_yuki_upcoming_contests() {
+    let server = StubServer::start(&[
+        (
+            "/yukicoder/api/v1/contest/current",
+            "yukicoder/contest-current.json",
+        ),
+        (
+            "/yukicoder/api/v1/contest/future",
+            "yukicoder/contest-future.json",
+        ),
+    ])
+    .await;
+
+    let contests = server
+        .api_client()
+        .get_yuki_upcoming_contests()
+        .await
+        .unwrap();
+
+    assert_eq!(contests.len(), 1);
+    assert_eq!(contests[0].id, "yukicoder_999");
+    assert_eq!(contests[0].phase, String::from(Phase::Before));
+    assert!(contests[0].problems.is_empty());
+}